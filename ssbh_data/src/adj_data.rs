@@ -15,9 +15,10 @@ for entry in adj.entries {
 # Ok(()) }
 ```
  */
-use crate::mesh_data::{MeshObjectData, VectorData};
+use crate::mesh_data::{find, float_key, union, MeshObjectData, VectorData};
 use itertools::Itertools;
 use ssbh_lib::formats::adj::{Adj, AdjEntry};
+use std::collections::HashMap;
 use std::convert::TryFrom;
 
 #[cfg(feature = "serde")]
@@ -48,9 +49,70 @@ pub mod error {
             end: usize,
             buffer_size: usize,
         },
+
+        /// A vertex index was detected that would result in an out of bounds access when reading the positions.
+        #[error(
+            "vertex index {} is out of range for a vertex collection of size {}",
+            vertex_index,
+            vertex_count
+        )]
+        VertexIndexOutOfRange {
+            vertex_index: usize,
+            vertex_count: usize,
+        },
+
+        /// The number of vertex indices is not a multiple of 3.
+        #[error("vertex index count {} is not a multiple of 3", vertex_index_count)]
+        NonTriangulatedFaces { vertex_index_count: usize },
+
+        /// The adjacent vertex indices are stored as [i16],
+        /// so the vertex count can't exceed `i16::MAX + 1`.
+        #[error(
+            "vertex count {} exceeds the limit of {} for adjacency data",
+            vertex_count,
+            limit
+        )]
+        VertexCountExceedsLimit { vertex_count: usize, limit: usize },
+
+        /// Each adjacent face uses two entries, so the slot count must be even.
+        #[error("slot count {} is not a multiple of 2", slot_count)]
+        OddSlotCount { slot_count: usize },
     }
 }
 
+/// Options for generating adjacency with [AdjEntryData::from_mesh_object_with_options].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AdjacencyOptions {
+    /// Vertices with positions within this distance are treated as the same vertex.
+    /// This includes faces from vertices split along UV seams or hard edges.
+    /// Welding is transitive, so chains of close vertices may be combined into one group.
+    /// A value of `0.0` only welds vertices with identical positions.
+    pub weld_tolerance: f32,
+    /// The number of adjacent vertex entries stored for each vertex.
+    /// Each adjacent face uses two entries, so this must be a multiple of 2.
+    /// Smash Ultimate uses 18 entries for at most 9 adjacent faces.
+    pub slot_count: usize,
+}
+
+impl Default for AdjacencyOptions {
+    fn default() -> Self {
+        Self {
+            weld_tolerance: 0.0,
+            slot_count: MAX_ADJACENT_VERTICES,
+        }
+    }
+}
+
+/// A vertex with more adjacent vertex entries than [slot_count](struct.AdjacencyOptions.html#structfield.slot_count).
+/// The remaining entries are not included in the adjacency data.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct AdjacencyOverflow {
+    pub mesh_object_index: usize,
+    pub vertex_index: usize,
+    /// The number of adjacent vertex entries before truncating to the slot count.
+    pub adjacent_count: usize,
+}
+
 /// The data associated with an [Adj] file.
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
@@ -59,6 +121,45 @@ pub struct AdjData {
     pub entries: Vec<AdjEntryData>,
 }
 
+impl AdjData {
+    /// Computes the adjacency information for every object in `objects`
+    /// using the object's position in `objects` as the [mesh_object_index](struct.AdjEntryData.html#structfield.mesh_object_index).
+    /// See [AdjEntryData::from_mesh_object_with_options].
+    /**
+    ```rust no_run
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    use ssbh_data::prelude::*;
+    use ssbh_data::adj_data::AdjacencyOptions;
+
+    let mesh = MeshData::from_file("model.numshb")?;
+    let options = AdjacencyOptions {
+        weld_tolerance: 0.0001,
+        ..Default::default()
+    };
+    let (adj, overflows) = AdjData::from_mesh_objects(&mesh.objects, &options)?;
+    for overflow in overflows {
+        println!("{overflow:?}");
+    }
+    adj.write_to_file("model.adjb")?;
+    # Ok(()) }
+    ```
+     */
+    pub fn from_mesh_objects(
+        objects: &[MeshObjectData],
+        options: &AdjacencyOptions,
+    ) -> Result<(Self, Vec<AdjacencyOverflow>), error::Error> {
+        let mut entries = Vec::new();
+        let mut overflows = Vec::new();
+        for (i, object) in objects.iter().enumerate() {
+            let (entry, object_overflows) =
+                AdjEntryData::from_mesh_object_with_options(i, object, options)?;
+            entries.push(entry);
+            overflows.extend(object_overflows);
+        }
+        Ok((Self { entries }, overflows))
+    }
+}
+
 /// Adjacency data for a mesh object.
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
@@ -76,22 +177,34 @@ pub struct AdjEntryData {
 impl AdjEntryData {
     /// Computes the vertex adjacency information from triangle faces.
     /// `vertex_indices.len()` should be a multiple of 3.
+    ///
+    /// Positions can only be compared for equality, so finding vertices with the same position
+    /// takes quadratic time in the number of vertices.
+    /// Prefer [from_vector_data](#method.from_vector_data) for large meshes.
     pub fn from_triangle_faces<T: PartialEq>(
         mesh_object_index: usize,
         vertex_positions: &[T],
         vertex_indices: &[u32],
     ) -> Self {
+        let groups: Vec<_> = vertex_positions
+            .iter()
+            .enumerate()
+            .map(|(i, p)| {
+                vertex_positions
+                    .iter()
+                    .position(|other| other == p)
+                    .unwrap_or(i)
+            })
+            .collect();
         Self {
             mesh_object_index,
-            vertex_adjacency: triangle_adjacency(
-                vertex_indices,
-                vertex_positions,
-                MAX_ADJACENT_VERTICES,
-            ),
+            vertex_adjacency: vertex_adjacency(vertex_indices, &groups, MAX_ADJACENT_VERTICES).0,
         }
     }
 
     /// Computes the vertex adjacency information from triangle faces from the given [MeshObjectData].
+    /// The vertex indices are not checked.
+    /// Use [from_mesh_object_with_options](#method.from_mesh_object_with_options) to return an error for invalid indices.
     // TODO: Show an example.
     pub fn from_mesh_object(mesh_object_index: usize, object: &MeshObjectData) -> Self {
         object
@@ -107,6 +220,7 @@ impl AdjEntryData {
     }
 
     /// Computes the vertex adjacency information from triangle faces from the given [VectorData].
+    /// The running time is linear in the number of vertices and faces.
     pub fn from_vector_data(
         mesh_object_index: usize,
         vertex_positions: &VectorData,
@@ -114,18 +228,88 @@ impl AdjEntryData {
     ) -> Self {
         Self {
             mesh_object_index,
-            vertex_adjacency: match vertex_positions {
-                crate::mesh_data::VectorData::Vector2(v) => {
-                    triangle_adjacency(vertex_indices, v, MAX_ADJACENT_VERTICES)
-                }
-                crate::mesh_data::VectorData::Vector3(v) => {
-                    triangle_adjacency(vertex_indices, v, MAX_ADJACENT_VERTICES)
-                }
-                crate::mesh_data::VectorData::Vector4(v) => {
-                    triangle_adjacency(vertex_indices, v, MAX_ADJACENT_VERTICES)
-                }
-            },
+            vertex_adjacency: triangle_adjacency(
+                vertex_indices,
+                vertex_positions,
+                MAX_ADJACENT_VERTICES,
+            ),
+        }
+    }
+
+    /// Computes the vertex adjacency information from triangle faces from the given [MeshObjectData]
+    /// and reports any vertices with more adjacent vertex entries than the slot count.
+    /// Objects without positions have no adjacency data.
+    ///
+    /// Unlike [from_mesh_object](#method.from_mesh_object), the vertex indices are checked
+    /// and vertices are welded using the [weld_tolerance](struct.AdjacencyOptions.html#structfield.weld_tolerance).
+    /// The running time is linear in the number of vertices and faces.
+    pub fn from_mesh_object_with_options(
+        mesh_object_index: usize,
+        object: &MeshObjectData,
+        options: &AdjacencyOptions,
+    ) -> Result<(Self, Vec<AdjacencyOverflow>), error::Error> {
+        let Some(positions) = object.positions.first() else {
+            return Ok((
+                Self {
+                    mesh_object_index,
+                    vertex_adjacency: Vec::new(),
+                },
+                Vec::new(),
+            ));
+        };
+
+        if !options.slot_count.is_multiple_of(2) {
+            return Err(error::Error::OddSlotCount {
+                slot_count: options.slot_count,
+            });
+        }
+
+        let vertex_count = positions.data.len();
+        let limit = i16::MAX as usize + 1;
+        if vertex_count > limit {
+            return Err(error::Error::VertexCountExceedsLimit {
+                vertex_count,
+                limit,
+            });
+        }
+        if !object.vertex_indices.len().is_multiple_of(3) {
+            return Err(error::Error::NonTriangulatedFaces {
+                vertex_index_count: object.vertex_indices.len(),
+            });
         }
+        if let Some(vertex_index) = object
+            .vertex_indices
+            .iter()
+            .find(|i| **i as usize >= vertex_count)
+        {
+            return Err(error::Error::VertexIndexOutOfRange {
+                vertex_index: *vertex_index as usize,
+                vertex_count,
+            });
+        }
+
+        let groups = position_groups(&positions.data, options.weld_tolerance);
+        let (vertex_adjacency, adjacent_counts) =
+            vertex_adjacency(&object.vertex_indices, &groups, options.slot_count);
+
+        let overflows = adjacent_counts
+            .into_iter()
+            .enumerate()
+            .filter(|(_, count)| *count > options.slot_count)
+            .map(|(vertex_index, adjacent_count)| AdjacencyOverflow {
+                mesh_object_index,
+                vertex_index,
+                adjacent_count,
+            })
+            .collect();
+
+        Ok((
+            Self {
+                mesh_object_index,
+                vertex_adjacency,
+            },
+            overflows,
+        ))
     }
 }
 
@@ -214,19 +398,29 @@ impl TryFrom<Adj> for AdjData {
     }
 }
 
-fn triangle_adjacency<T: PartialEq>(
+fn triangle_adjacency(
     vertex_indices: &[u32],
-    vertex_positions: &[T],
+    vertex_positions: &VectorData,
     padding_size: usize,
 ) -> Vec<i16> {
-    // TODO: It should be doable to do this in fewer allocations.
-    // TODO: This could be done with tinyvec or maintaining a separate count list.
-    // TODO: Return an error for out of range vertices?
     // TODO: Should there be an error if there is a remainder?
+    let groups = position_groups(vertex_positions, 0.0);
+    vertex_adjacency(vertex_indices, &groups, padding_size).0
+}
 
+/// Finds the adjacent vertices for each vertex padded or truncated to `padding_size`
+/// and the number of adjacent vertices before padding.
+/// Vertices with the same value in `groups` share their adjacent faces.
+fn vertex_adjacency(
+    vertex_indices: &[u32],
+    groups: &[usize],
+    padding_size: usize,
+) -> (Vec<i16>, Vec<usize>) {
     // Find the vertex indices from the all adjacent faces for each vertex.
     // We'll assume each face is a triangle with 3 distinct vertex indices.
-    let mut adjacent_vertices = vec![Vec::new(); vertex_positions.len()];
+    // TODO: Return an error for out of range vertices?
+    // AdjEntryData::from_mesh_object_with_options checks the indices and vertex count first.
+    let mut adjacent_vertices = vec![Vec::new(); groups.len()];
 
     // The intuitive approach is to loop over the face list for each vertex.
     // It's more efficient to just loop over the faces once.
@@ -248,30 +442,77 @@ fn triangle_adjacency<T: PartialEq>(
 
     // Smash Ultimate adjb also use adjacent faces from split edges.
     // This prevents seams when recalculating normals.
-    // TODO: Can this be done without a second vec?
-    // TODO: Can this be done faster than O(N^2)?
-    let mut adjacent_vertices_with_seams = vec![Vec::new(); vertex_positions.len()];
-    for (i, _) in adjacent_vertices.iter().enumerate() {
-        // TODO: Does this also include the vertex itself?
-        // TODO: Avoid strict equality?
-        for duplicate_index in vertex_positions
-            .iter()
-            .positions(|p| *p == vertex_positions[i])
-        {
-            adjacent_vertices_with_seams[i].extend_from_slice(&adjacent_vertices[duplicate_index]);
-        }
+    // Combine the faces for each group in ascending vertex order.
+    let mut group_vertices: HashMap<usize, Vec<usize>> = HashMap::new();
+    for (i, group) in groups.iter().enumerate() {
+        group_vertices.entry(*group).or_default().push(i);
     }
 
     // Smash Ultimate adjb files limit the number of adjacent vertices per vertex.
     // The special value of -1 is used for unused entries.
-    // TODO: Is a fixed count per vertex required?
-    adjacent_vertices_with_seams
-        .into_iter()
-        .flat_map(|mut a| {
-            a.resize(padding_size, -1);
-            a
-        })
-        .collect()
+    let mut adjacency = Vec::with_capacity(groups.len() * padding_size);
+    let mut adjacent_counts = Vec::with_capacity(groups.len());
+    for group in groups {
+        let start = adjacency.len();
+        for v in &group_vertices[group] {
+            adjacency.extend_from_slice(&adjacent_vertices[*v]);
+        }
+        adjacent_counts.push(adjacency.len() - start);
+        adjacency.resize(start + padding_size, -1);
+    }
+
+    (adjacency, adjacent_counts)
+}
+
+/// Assigns the same group to vertices with positions within `tolerance`.
+/// Each group is identified by its smallest vertex index.
+fn position_groups(positions: &VectorData, tolerance: f32) -> Vec<usize> {
+    let vertex_count = positions.len();
+    let mut parents: Vec<_> = (0..vertex_count).collect();
+
+    if tolerance > 0.0 {
+        // Hash positions into cells of size tolerance.
+        // Vertices within the tolerance are in the same or a neighboring cell.
+        let cell = |i: usize| -> Vec<i64> {
+            positions
+                .element(i)
+                .iter()
+                .map(|f| (f / tolerance).floor() as i64)
+                .collect()
+        };
+        let mut cells: HashMap<Vec<i64>, Vec<usize>> = HashMap::new();
+        for i in 0..vertex_count {
+            cells.entry(cell(i)).or_default().push(i);
+        }
+
+        let tolerance_squared = tolerance * tolerance;
+        for i in 0..vertex_count {
+            let position = positions.element(i);
+            let key = cell(i);
+            for offset in (0..key.len()).map(|_| -1..=1).multi_cartesian_product() {
+                let neighbor: Vec<_> = key.iter().zip(&offset).map(|(k, o)| k + o).collect();
+                for j in cells.get(&neighbor).into_iter().flatten() {
+                    let distance_squared: f32 = position
+                        .iter()
+                        .zip(positions.element(*j))
+                        .map(|(a, b)| (a - b) * (a - b))
+                        .sum();
+                    if *j != i && distance_squared <= tolerance_squared {
+                        union(&mut parents, i, *j);
+                    }
+                }
+            }
+        }
+    } else {
+        let mut first_vertices = HashMap::new();
+        for i in 0..vertex_count {
+            let key: Vec<_> = positions.element(i).iter().map(|f| float_key(*f)).collect();
+            let first = *first_vertices.entry(key).or_insert(i);
+            union(&mut parents, i, first);
+        }
+    }
+
+    (0..vertex_count).map(|i| find(&mut parents, i)).collect()
 }
 
 #[cfg(test)]
@@ -407,16 +648,20 @@ mod tests {
         x.into_iter().flatten().collect()
     }
 
+    fn positions(values: &[f32]) -> VectorData {
+        VectorData::Vector2(values.iter().map(|v| [*v, 0.0]).collect())
+    }
+
     #[test]
     fn triangle_adjacency_empty() {
-        assert!(triangle_adjacency(&[], &[0.0; 0], MAX_ADJACENT_VERTICES).is_empty());
+        assert!(triangle_adjacency(&[], &positions(&[]), MAX_ADJACENT_VERTICES).is_empty());
     }
 
     #[test]
     fn triangle_adjacency_single_vertex_none_adjacent() {
         assert_eq!(
             vec![-1; 18],
-            triangle_adjacency(&[], &[0.0], MAX_ADJACENT_VERTICES)
+            triangle_adjacency(&[], &positions(&[0.0]), MAX_ADJACENT_VERTICES)
         );
     }
 
@@ -424,14 +669,14 @@ mod tests {
     #[ignore]
     fn triangle_adjacency_single_face_single_vertex() {
         // TODO: Should this be an error?
-        triangle_adjacency(&[0, 1, 2], &[0.0], 4);
+        triangle_adjacency(&[0, 1, 2], &positions(&[0.0]), 4);
     }
 
     #[test]
     fn triangle_adjacency_single_face() {
         assert_eq!(
             flatten(vec![[1, 2, -1], [2, 0, -1], [0, 1, -1]]),
-            triangle_adjacency(&[0, 1, 2], &[0.0, 0.5, 1.0], 3)
+            triangle_adjacency(&[0, 1, 2], &positions(&[0.0, 0.5, 1.0]), 3)
         );
     }
 
@@ -443,7 +688,11 @@ mod tests {
                 [2, 0, 2, 0, 0, 2, -1],
                 [0, 1, 0, 1, 1, 0, -1]
             ]),
-            triangle_adjacency(&[0, 1, 2, 2, 0, 1, 1, 0, 2], &[0.0, 0.5, 1.0], 7)
+            triangle_adjacency(
+                &[0, 1, 2, 2, 0, 1, 1, 0, 2],
+                &positions(&[0.0, 0.5, 1.0]),
+                7
+            )
         );
     }
 
@@ -460,7 +709,163 @@ mod tests {
                 [5, 3, -1, -1, -1],
                 [3, 4, -1, -1, -1],
             ]),
-            triangle_adjacency(&[0, 1, 2, 3, 4, 5], &[0.0, 0.5, 1.0, 0.0, 1.5, 2.0], 5)
+            triangle_adjacency(
+                &[0, 1, 2, 3, 4, 5],
+                &positions(&[0.0, 0.5, 1.0, 0.0, 1.5, 2.0]),
+                5
+            )
+        );
+    }
+
+    fn mesh_object(positions: Vec<[f32; 3]>, vertex_indices: Vec<u32>) -> MeshObjectData {
+        MeshObjectData {
+            vertex_indices,
+            positions: vec![crate::mesh_data::AttributeData::new(
+                "Position0",
+                VectorData::Vector3(positions),
+            )],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn from_triangle_faces_matches_from_vector_data() {
+        let values = [0.0, 0.5, 1.0, -0.0, 1.5, 2.0];
+        let indices = [0, 1, 2, 3, 4, 5];
+        assert_eq!(
+            AdjEntryData::from_triangle_faces(0, &values, &indices),
+            AdjEntryData::from_vector_data(0, &positions(&values), &indices)
+        );
+    }
+
+    #[test]
+    fn adjacency_with_options_weld_tolerance() {
+        // Vertex 3 is split from vertex 0 along a UV seam with a slightly different position.
+        let object = mesh_object(
+            vec![
+                [0.0, 0.0, 0.0],
+                [1.0, 0.0, 0.0],
+                [0.0, 1.0, 0.0],
+                [0.0, 0.00001, 0.0],
+                [-1.0, 0.0, 0.0],
+                [0.0, -1.0, 0.0],
+            ],
+            vec![0, 1, 2, 3, 4, 5],
+        );
+
+        let options = AdjacencyOptions {
+            weld_tolerance: 0.0,
+            slot_count: 4,
+        };
+        let (entry, overflows) =
+            AdjEntryData::from_mesh_object_with_options(2, &object, &options).unwrap();
+        assert_eq!(2, entry.mesh_object_index);
+        assert_eq!(vec![1, 2, -1, -1], entry.vertex_adjacency[..4]);
+        assert!(overflows.is_empty());
+
+        let options = AdjacencyOptions {
+            weld_tolerance: 0.001,
+            slot_count: 4,
+        };
+        let (entry, _) = AdjEntryData::from_mesh_object_with_options(2, &object, &options).unwrap();
+        assert_eq!(
+            flatten(vec![
+                [1, 2, 4, 5],
+                [2, 0, -1, -1],
+                [0, 1, -1, -1],
+                [1, 2, 4, 5],
+                [5, 3, -1, -1],
+                [3, 4, -1, -1],
+            ]),
+            entry.vertex_adjacency
         );
     }
+
+    #[test]
+    fn adjacency_with_options_overflow() {
+        // Vertex 0 is shared by 3 faces.
+        let object = mesh_object(
+            vec![[0.0; 3], [1.0; 3], [2.0; 3], [3.0; 3]],
+            vec![0, 1, 2, 0, 2, 3, 0, 3, 1],
+        );
+        let options = AdjacencyOptions {
+            weld_tolerance: 0.0,
+            slot_count: 4,
+        };
+        let (entry, overflows) =
+            AdjEntryData::from_mesh_object_with_options(1, &object, &options).unwrap();
+        assert_eq!(16, entry.vertex_adjacency.len());
+        assert_eq!(vec![1, 2, 2, 3], entry.vertex_adjacency[..4]);
+        assert_eq!(
+            vec![AdjacencyOverflow {
+                mesh_object_index: 1,
+                vertex_index: 0,
+                adjacent_count: 6
+            }],
+            overflows
+        );
+    }
+
+    #[test]
+    fn adjacency_with_options_invalid_indices() {
+        let options = AdjacencyOptions::default();
+        assert!(matches!(
+            AdjEntryData::from_mesh_object_with_options(
+                0,
+                &mesh_object(vec![[0.0; 3]], vec![0, 1, 2]),
+                &options
+            ),
+            Err(error::Error::VertexIndexOutOfRange {
+                vertex_index: 1,
+                vertex_count: 1
+            })
+        ));
+        assert!(matches!(
+            AdjEntryData::from_mesh_object_with_options(
+                0,
+                &mesh_object(vec![[0.0; 3]; 3], vec![0, 1]),
+                &options
+            ),
+            Err(error::Error::NonTriangulatedFaces {
+                vertex_index_count: 2
+            })
+        ));
+        assert!(matches!(
+            AdjEntryData::from_mesh_object_with_options(
+                0,
+                &mesh_object(vec![[0.0; 3]; 40000], Vec::new()),
+                &options
+            ),
+            Err(error::Error::VertexCountExceedsLimit {
+                vertex_count: 40000,
+                limit: 32768
+            })
+        ));
+        assert!(matches!(
+            AdjEntryData::from_mesh_object_with_options(
+                0,
+                &mesh_object(vec![[0.0; 3]; 3], vec![0, 1, 2]),
+                &AdjacencyOptions {
+                    slot_count: 5,
+                    ..Default::default()
+                }
+            ),
+            Err(error::Error::OddSlotCount { slot_count: 5 })
+        ));
+    }
+
+    #[test]
+    fn adj_data_from_mesh_objects() {
+        let objects = vec![
+            mesh_object(vec![[0.0; 3], [1.0; 3], [2.0; 3]], vec![0, 1, 2]),
+            MeshObjectData::default(),
+        ];
+        let (adj, overflows) =
+            AdjData::from_mesh_objects(&objects, &AdjacencyOptions::default()).unwrap();
+        assert_eq!(2, adj.entries.len());
+        assert_eq!(1, adj.entries[1].mesh_object_index);
+        assert_eq!(54, adj.entries[0].vertex_adjacency.len());
+        assert!(adj.entries[1].vertex_adjacency.is_empty());
+        assert!(overflows.is_empty());
+    }
 }
@@ -38,6 +38,7 @@ for group in anim.groups {
 //! These errors are small in practice but may cause gameplay differences such as online desyncs.
 use binrw::io::{Cursor, Seek, Write};
 use binrw::{BinRead, BinReaderExt};
+use geometry_tools::glam::Mat4;
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 pub use ssbh_lib::formats::anim::GroupType;
@@ -56,6 +57,9 @@ use std::{
     error::Error,
 };
 
+use crate::skel_data::{BoneData, SkelData};
+use crate::skinning::transform_matrix;
+
 mod buffers;
 use buffers::*;
 mod bitutils;
@@ -202,6 +206,14 @@ pub mod error {
         /// An error occurred while reading the compressed header for version 2.0 or later.
         #[error("the track data compression header is malformed and cannot be read")]
         MalformedCompressionHeader,
+
+        /// The transform track for a removed bone or one of its children uses compensate scale,
+        /// which can't be represented after combining the tracks.
+        #[error(
+            "the transform track for bone {} uses compensate scale and can't be combined with its parent",
+            bone_name
+        )]
+        RemoveBoneCompensateScale { bone_name: String },
     }
 }
 
@@ -566,6 +578,141 @@ fn create_track_data_v20(
     })
 }
 
+impl AnimData {
+    /// Renames the [GroupType::Transform] nodes named `name` to `new_name`.
+    /// See [SkelData::rename_bone](crate::skel_data::SkelData::rename_bone).
+    pub fn rename_bone(&mut self, name: &str, new_name: &str) {
+        for node in self.transform_nodes_mut() {
+            if node.name == name {
+                node.name = new_name.to_string();
+            }
+        }
+    }
+
+    /// Removes the [GroupType::Transform] nodes named `name` and returns the number of removed nodes.
+    ///
+    /// The transforms of the removed bone are combined with the tracks for its children in `skel`,
+    /// so the children keep their animated world transforms after calling
+    /// [SkelData::remove_bone](crate::skel_data::SkelData::remove_bone).
+    /// `skel` should be the skeleton before removing the bone.
+    /// Children without a track are given a new track if the removed bone is animated.
+    /// The combined transforms are assumed to not have any shear.
+    ///
+    /// Returns [error::Error::RemoveBoneCompensateScale] and leaves the data unchanged
+    /// if the removed bone or an affected child uses compensate scale.
+    pub fn remove_bone(&mut self, name: &str, skel: &SkelData) -> Result<usize, error::Error> {
+        let transform_track = |data: &Self, bone_name: &str| {
+            data.groups
+                .iter()
+                .filter(|g| g.group_type == GroupType::Transform)
+                .flat_map(|g| &g.nodes)
+                .filter(|n| n.name == bone_name)
+                .flat_map(|n| &n.tracks)
+                .find_map(|t| match &t.values {
+                    TrackValues::Transform(values) => Some((values.clone(), t.compensate_scale)),
+                    _ => None,
+                })
+        };
+
+        // Combine the tracks before modifying anything in case of errors.
+        let mut child_tracks = Vec::new();
+        if let Some(index) = skel.bones.iter().position(|b| b.name == name) {
+            let removed_bone = &skel.bones[index];
+            let removed_track = transform_track(self, name);
+            for child in skel.bones.iter().filter(|b| b.parent_index == Some(index)) {
+                let child_track = transform_track(self, &child.name);
+                if removed_track.is_none() && child_track.is_none() {
+                    // The skeleton already combines the rest transforms.
+                    continue;
+                }
+
+                for (bone_name, track) in [(name, &removed_track), (&child.name, &child_track)] {
+                    if matches!(track, Some((_, true))) {
+                        return Err(error::Error::RemoveBoneCompensateScale {
+                            bone_name: bone_name.to_string(),
+                        });
+                    }
+                }
+
+                // Tracks with fewer frames use their last value.
+                let matrix = |track: &Option<(Vec<Transform>, bool)>, bone: &BoneData, frame| {
+                    track
+                        .as_ref()
+                        .and_then(|(values, _)| values.get(frame).or_else(|| values.last()))
+                        .map(transform_matrix)
+                        .unwrap_or_else(|| Mat4::from_cols_array_2d(&bone.transform))
+                };
+                let frame_count = [&removed_track, &child_track]
+                    .iter()
+                    .filter_map(|t| t.as_ref().map(|(values, _)| values.len()))
+                    .max()
+                    .unwrap_or_default()
+                    .max(1);
+                let values = (0..frame_count)
+                    .map(|frame| {
+                        let transform = matrix(&removed_track, removed_bone, frame)
+                            * matrix(&child_track, child, frame);
+                        let (scale, rotation, translation) =
+                            transform.to_scale_rotation_translation();
+                        Transform {
+                            scale: scale.to_array().into(),
+                            rotation: rotation.to_array().into(),
+                            translation: translation.to_array().into(),
+                        }
+                    })
+                    .collect();
+                child_tracks.push((child.name.as_str(), values));
+            }
+        }
+
+        for (child_name, values) in child_tracks {
+            let track = self
+                .transform_nodes_mut()
+                .filter(|n| n.name == child_name)
+                .flat_map(|n| &mut n.tracks)
+                .find(|t| matches!(t.values, TrackValues::Transform(_)));
+            match track {
+                Some(track) => track.values = TrackValues::Transform(values),
+                None => {
+                    // The removed bone is animated, so there is at least one transform group.
+                    if let Some(group) = self
+                        .groups
+                        .iter_mut()
+                        .find(|g| g.group_type == GroupType::Transform)
+                    {
+                        group.nodes.push(NodeData {
+                            name: child_name.to_string(),
+                            tracks: vec![TrackData {
+                                name: "Transform".to_string(),
+                                compensate_scale: false,
+                                transform_flags: TransformFlags::default(),
+                                values: TrackValues::Transform(values),
+                            }],
+                        });
+                    }
+                }
+            }
+        }
+
+        let mut count = 0;
+        for group in &mut self.groups {
+            if group.group_type == GroupType::Transform {
+                let node_count = group.nodes.len();
+                group.nodes.retain(|n| n.name != name);
+                count += node_count - group.nodes.len();
+            }
+        }
+        Ok(count)
+    }
+
+    fn transform_nodes_mut(&mut self) -> impl Iterator<Item = &mut NodeData> {
+        self.groups
+            .iter_mut()
+            .filter(|g| g.group_type == GroupType::Transform)
+            .flat_map(|g| &mut g.nodes)
+    }
+}
+
 /// Data associated with a [Group].
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
@@ -1271,4 +1418,127 @@ mod tests {
             })
         ));
     }
+
+    #[test]
+    fn rename_remove_bone() {
+        let node = |name: &str| NodeData {
+            name: name.to_string(),
+            tracks: Vec::new(),
+        };
+        let mut data = AnimData {
+            major_version: 2,
+            minor_version: 0,
+            final_frame_index: 0.0,
+            groups: vec![
+                GroupData {
+                    group_type: GroupType::Transform,
+                    nodes: vec![node("Hip"), node("Helper")],
+                },
+                GroupData {
+                    group_type: GroupType::Visibility,
+                    nodes: vec![node("Helper")],
+                },
+            ],
+        };
+
+        data.rename_bone("Helper", "H_Helper");
+        assert_eq!("H_Helper", data.groups[0].nodes[1].name);
+        assert_eq!("Helper", data.groups[1].nodes[0].name);
+
+        let skel = SkelData {
+            major_version: 1,
+            minor_version: 0,
+            bones: Vec::new(),
+        };
+        assert_eq!(1, data.remove_bone("H_Helper", &skel).unwrap());
+        assert_eq!(1, data.groups[0].nodes.len());
+        assert_eq!(0, data.remove_bone("Helper", &skel).unwrap());
+        assert_eq!(1, data.groups[1].nodes.len());
+    }
+
+    fn translation_node(name: &str, translations: &[[f32; 3]]) -> NodeData {
+        NodeData {
+            name: name.to_string(),
+            tracks: vec![TrackData {
+                name: "Transform".to_string(),
+                compensate_scale: false,
+                transform_flags: TransformFlags::default(),
+                values: TrackValues::Transform(
+                    translations
+                        .iter()
+                        .map(|t| Transform {
+                            translation: (*t).into(),
+                            ..Transform::IDENTITY
+                        })
+                        .collect(),
+                ),
+            }],
+        }
+    }
+
+    fn translations(node: &NodeData) -> Vec<[f32; 3]> {
+        match &node.tracks[0].values {
+            TrackValues::Transform(values) => values
+                .iter()
+                .map(|t| [t.translation.x, t.translation.y, t.translation.z])
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    #[test]
+    fn remove_bone_combines_child_tracks() {
+        let bone = |name: &str, parent_index, x: f32| BoneData {
+            name: name.to_string(),
+            transform: Mat4::from_translation([x, 0.0, 0.0].into()).to_cols_array_2d(),
+            parent_index,
+            billboard_type: crate::skel_data::BillboardType::Disabled,
+        };
+        let skel = SkelData {
+            major_version: 1,
+            minor_version: 0,
+            bones: vec![
+                bone("Trans", None, 0.0),
+                bone("Helper", Some(0), 1.0),
+                bone("Arm", Some(1), 2.0),
+                bone("Leg", Some(1), 4.0),
+            ],
+        };
+        let mut data = AnimData {
+            major_version: 2,
+            minor_version: 0,
+            final_frame_index: 1.0,
+            groups: vec![GroupData {
+                group_type: GroupType::Transform,
+                nodes: vec![
+                    translation_node("Helper", &[[1.0, 0.0, 0.0], [1.0, 5.0, 0.0]]),
+                    translation_node("Arm", &[[2.0, 0.0, 0.0]]),
+                ],
+            }],
+        };
+
+        let mut compensate_scale = data.clone();
+        compensate_scale.groups[0].nodes[1].tracks[0].compensate_scale = true;
+        assert!(matches!(
+            compensate_scale.remove_bone("Helper", &skel),
+            Err(error::Error::RemoveBoneCompensateScale { bone_name }) if bone_name == "Arm"
+        ));
+        assert_eq!(2, compensate_scale.groups[0].nodes.len());
+
+        assert_eq!(1, data.remove_bone("Helper", &skel).unwrap());
+
+        // The children keep their animated world transforms.
+        let nodes = &data.groups[0].nodes;
+        assert_eq!(2, nodes.len());
+        assert_eq!("Arm", nodes[0].name);
+        assert_eq!(
+            vec![[3.0, 0.0, 0.0], [3.0, 5.0, 0.0]],
+            translations(&nodes[0])
+        );
+        assert_eq!("Leg", nodes[1].name);
+        assert_eq!(
+            vec![[5.0, 0.0, 0.0], [5.0, 5.0, 0.0]],
+            translations(&nodes[1])
+        );
+    }
 }
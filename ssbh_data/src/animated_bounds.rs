@@ -0,0 +1,577 @@
+//! Bounding information that contains the skinned mesh for every frame of a set of animations.
+//!
+//! The bounding information calculated when saving a [MeshData] or [MeshExData] only uses the rest pose.
+//! Animations that move vertices outside these bounds may cause objects to be culled in game
+//! or have clipped shadows. [AnimatedBounds] expands the bounds to include every animated pose.
+//!
+//! # Examples
+/*!
+```rust no_run
+# fn main() -> Result<(), Box<dyn std::error::Error>> {
+use ssbh_data::prelude::*;
+use ssbh_data::animated_bounds::AnimatedBounds;
+use ssbh_lib::formats::mesh::Mesh;
+
+let mesh_data = MeshData::from_file("model.numshb")?;
+let skel = SkelData::from_file("model.nusktb")?;
+let wait = AnimData::from_file("a00wait1.nuanmb")?;
+let run = AnimData::from_file("a00dash.nuanmb")?;
+
+let bounds = AnimatedBounds::from_animations(&mesh_data, &skel, &[&wait, &run])?;
+
+let mut mesh = Mesh::try_from(&mesh_data)?;
+bounds.apply_to_mesh(&mut mesh);
+mesh.write_to_file("model.numshb")?;
+
+let mut meshex = MeshExData::from_file("model.numshexb")?;
+bounds.apply_to_meshex(&mut meshex);
+meshex.write_to_file("model.numshexb")?;
+# Ok(()) }
+```
+ */
+use binrw::BinRead;
+use geometry_tools::bounding::calculate_aabb_from_points;
+use geometry_tools::glam::{Vec3A, Vec4};
+use ssbh_lib::formats::mesh::{BoundingInfo, BoundingSphere, Mesh, MeshInner};
+use ssbh_lib::Vector3;
+use ssbh_write::SsbhWrite;
+
+use crate::mesh_data::{calculate_bounding_info, calculate_bounding_sphere, BoundingMethod};
+use crate::prelude::*;
+use crate::skinning::{
+    frame_count, skinning_transforms, transform_tracks, world_transforms, Binding,
+};
+
+pub mod error {
+    use thiserror::Error;
+
+    /// Errors while calculating [AnimatedBounds](super::AnimatedBounds).
+    #[derive(Debug, Error)]
+    pub enum Error {
+        /// The mesh objects have invalid vertex data or influences for bones not in the skeleton.
+        #[error(transparent)]
+        Mesh(#[from] crate::mesh_data::error::Error),
+
+        /// An error occurred while calculating a transformation matrix.
+        #[error(transparent)]
+        BoneTransform(#[from] crate::skel_data::BoneTransformError),
+    }
+}
+
+/// The bounding information for a [MeshObjectData](crate::mesh_data::MeshObjectData).
+#[derive(Debug, PartialEq, Clone)]
+pub struct ObjectBounds {
+    pub name: String,
+    pub subindex: u64,
+    pub bounding_info: BoundingInfo,
+}
+
+/// The bounding sphere for a [MeshObjectGroupData](crate::meshex_data::MeshObjectGroupData).
+#[derive(Debug, PartialEq, Clone)]
+pub struct GroupBounds {
+    pub mesh_object_full_name: String,
+    pub bounding_sphere: BoundingSphere,
+}
+
+/// Bounding information for the rest pose and all animated poses of a [MeshData].
+#[derive(Debug, PartialEq, Clone)]
+pub struct AnimatedBounds {
+    /// The bounding information for all objects.
+    pub bounding_info: BoundingInfo,
+    /// The bounding information for each object in the same order as the [MeshData] objects.
+    pub objects: Vec<ObjectBounds>,
+    /// The bounding spheres for each group of objects with the same name.
+    pub groups: Vec<GroupBounds>,
+}
+
+impl AnimatedBounds {
+    /// Calculates bounding information containing the rest pose of `mesh`
+    /// and the skinned pose for every frame in `anims` using the [BoundingMethod](crate::mesh_data::BoundingMethod)
+    /// of `mesh`.
+    ///
+    /// The bounds for each frame are combined without storing the skinned vertices.
+    /// The combined spheres contain the sphere for each frame but may not be minimal,
+    /// and the oriented bounding boxes are aligned to the coordinate axes.
+    ///
+    /// Objects with [bone_influences](crate::mesh_data::MeshObjectData#structfield.bone_influences) are skinned
+    /// using their weights, and objects without influences follow their
+    /// [parent_bone_name](crate::mesh_data::MeshObjectData#structfield.parent_bone_name).
+    /// Bones without an animated transform in the [GroupType::Transform] group use their transform from `skel`.
+    pub fn from_animations(
+        mesh: &MeshData,
+        skel: &SkelData,
+        anims: &[&AnimData],
+    ) -> Result<Self, error::Error> {
+        let rest_transforms = world_transforms(skel, |_| None)?;
+        let inverse_rest_transforms: Vec<_> = rest_transforms.iter().map(|t| t.inverse()).collect();
+
+        let objects = mesh
+            .objects
+            .iter()
+            .map(|o| {
+                let positions = o
+                    .positions
+                    .first()
+                    .map(|a| a.data.to_glam_vec3a())
+                    .unwrap_or_default();
+                Ok((positions, Binding::new(o, skel)?))
+            })
+            .collect::<Result<Vec<_>, crate::mesh_data::error::Error>>()?;
+
+        // Group objects the same way as MeshExData::from_mesh_objects.
+        let mut group_names: Vec<&String> = Vec::new();
+        let object_groups: Vec<_> = mesh
+            .objects
+            .iter()
+            .map(|o| {
+                if group_names.last() != Some(&&o.name) {
+                    group_names.push(&o.name);
+                }
+                group_names.len() - 1
+            })
+            .collect();
+
+        let method = mesh.bounding_method;
+        let mut object_bounds = vec![RunningBounds::default(); objects.len()];
+        let mut group_bounds = vec![RunningBounds::default(); group_names.len()];
+        let mut add_points = |object_index: usize, points: &[Vec3A]| {
+            object_bounds[object_index].add_points(points, method);
+            group_bounds[object_groups[object_index]].add_points(points, method);
+        };
+
+        // Start with the rest pose since animations don't necessarily include it.
+        for (i, (positions, _)) in objects.iter().enumerate() {
+            add_points(i, positions);
+        }
+
+        // Only store the points for the current frame.
+        let mut frame_points = Vec::new();
+        for anim in anims {
+            let tracks = transform_tracks(anim);
+            for frame in 0..frame_count(anim) {
+                let transforms =
+                    skinning_transforms(skel, &tracks, frame, &inverse_rest_transforms)?;
+
+                for (i, (positions, binding)) in objects.iter().enumerate() {
+                    // Static objects are already included in the rest pose.
+                    if !matches!(binding, Binding::Static) {
+                        frame_points.clear();
+                        frame_points.extend(
+                            positions
+                                .iter()
+                                .enumerate()
+                                .map(|(i, p)| binding.skin_position(i, *p, &transforms)),
+                        );
+                        add_points(i, &frame_points);
+                    }
+                }
+            }
+        }
+
+        let mut all_bounds = RunningBounds::default();
+        for bounds in &group_bounds {
+            all_bounds.add(bounds);
+        }
+
+        Ok(Self {
+            bounding_info: all_bounds.bounding_info(method),
+            objects: mesh
+                .objects
+                .iter()
+                .zip(&object_bounds)
+                .map(|(o, b)| ObjectBounds {
+                    name: o.name.clone(),
+                    subindex: o.subindex,
+                    bounding_info: b.bounding_info(method),
+                })
+                .collect(),
+            groups: group_bounds
+                .iter()
+                .zip(group_names)
+                .map(|(bounds, name)| GroupBounds {
+                    mesh_object_full_name: name.clone(),
+                    bounding_sphere: bounds.bounding_info(method).bounding_sphere,
+                })
+                .collect(),
+        })
+    }
+
+    /// Replaces the mesh and object bounding information in `mesh`.
+    /// Objects are matched by name and subindex, and objects without bounds are unchanged.
+    pub fn apply_to_mesh(&self, mesh: &mut Mesh) {
+        match mesh {
+            Mesh::V8(mesh) => self.apply_to_mesh_inner(mesh),
+            Mesh::V9(mesh) => self.apply_to_mesh_inner(mesh),
+            Mesh::V10(mesh) => self.apply_to_mesh_inner(mesh),
+        }
+    }
+
+    fn apply_to_mesh_inner<A, W>(&self, mesh: &mut MeshInner<A, W>)
+    where
+        A: for<'a> BinRead<Args<'a> = ()> + SsbhWrite,
+        W: for<'a> BinRead<Args<'a> = ()> + SsbhWrite,
+    {
+        mesh.bounding_info = self.bounding_info;
+        for object in &mut mesh.objects.elements {
+            let name = object.name.to_string_lossy();
+            if let Some(bounds) = self
+                .objects
+                .iter()
+                .find(|b| b.name == name && b.subindex == object.subindex)
+            {
+                object.bounding_info = bounds.bounding_info;
+            }
+        }
+    }
+
+    /// Replaces the bounding spheres for groups in `meshex` with the same full name.
+    /// Groups without bounds are unchanged.
+    pub fn apply_to_meshex(&self, meshex: &mut MeshExData) {
+        for group in &mut meshex.mesh_object_groups {
+            if let Some(bounds) = self
+                .groups
+                .iter()
+                .find(|b| b.mesh_object_full_name == group.mesh_object_full_name)
+            {
+                group.bounding_sphere = bounds.bounding_sphere;
+            }
+        }
+    }
+}
+
+/// Bounds that are updated with the points for each frame to avoid storing all the points.
+#[derive(Debug, Default, Clone)]
+struct RunningBounds {
+    aabb: Option<(Vec3A, Vec3A)>,
+    sphere: Option<Vec4>,
+}
+
+impl RunningBounds {
+    fn add_points(&mut self, points: &[Vec3A], method: BoundingMethod) {
+        if !points.is_empty() {
+            self.add(&Self {
+                aabb: Some(calculate_aabb_from_points(points)),
+                sphere: Some(calculate_bounding_sphere(points, method)),
+            });
+        }
+    }
+
+    fn add(&mut self, other: &Self) {
+        self.aabb = match (self.aabb, other.aabb) {
+            (Some((min, max)), Some((other_min, other_max))) => {
+                Some((min.min(other_min), max.max(other_max)))
+            }
+            (aabb, other_aabb) => aabb.or(other_aabb),
+        };
+        self.sphere = match (self.sphere, other.sphere) {
+            (Some(sphere), Some(other_sphere)) => Some(merge_spheres(sphere, other_sphere)),
+            (sphere, other_sphere) => sphere.or(other_sphere),
+        };
+    }
+
+    fn bounding_info(&self, method: BoundingMethod) -> BoundingInfo {
+        match (self.aabb, self.sphere) {
+            (Some((min, max)), Some(sphere)) => {
+                // The box for the corners is the combined axis aligned box.
+                let mut info = calculate_bounding_info(&[min, max], BoundingMethod::Fast);
+                info.bounding_sphere = BoundingSphere {
+                    center: Vector3::new(sphere.x, sphere.y, sphere.z),
+                    radius: sphere.w,
+                };
+                info
+            }
+            _ => calculate_bounding_info(&[], method),
+        }
+    }
+}
+
+/// Calculates the smallest sphere containing spheres `a` and `b` with the center in xyz and the radius in w.
+fn merge_spheres(a: Vec4, b: Vec4) -> Vec4 {
+    let distance = a.truncate().distance(b.truncate());
+    if distance + b.w <= a.w {
+        a
+    } else if distance + a.w <= b.w {
+        b
+    } else {
+        let radius = (distance + a.w + b.w) / 2.0;
+        let center = a.truncate().lerp(b.truncate(), (radius - a.w) / distance);
+        center.extend(radius)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::anim_data::{
+        GroupData, GroupType, NodeData, TrackData, TrackValues, Transform, TransformFlags,
+    };
+    use crate::mesh_data::{
+        AttributeData, BoneInfluence, BoundingMethod, MeshObjectData, VectorData, VertexWeight,
+    };
+    use crate::skel_data::{BillboardType, BoneData, BoneTransformError};
+    use geometry_tools::glam::{Mat4, Quat};
+    use ssbh_lib::Vector4;
+
+    fn bone(name: &str, parent_index: Option<usize>, translation: [f32; 3]) -> BoneData {
+        BoneData {
+            name: name.to_string(),
+            transform: Mat4::from_translation(translation.into()).to_cols_array_2d(),
+            parent_index,
+            billboard_type: BillboardType::Disabled,
+        }
+    }
+
+    fn skel() -> SkelData {
+        SkelData {
+            major_version: 1,
+            minor_version: 0,
+            bones: vec![
+                bone("Trans", None, [0.0, 0.0, 0.0]),
+                bone("Arm", Some(0), [1.0, 0.0, 0.0]),
+            ],
+        }
+    }
+
+    fn object(name: &str, subindex: u64, parent_bone_name: &str) -> MeshObjectData {
+        MeshObjectData {
+            name: name.to_string(),
+            subindex,
+            parent_bone_name: parent_bone_name.to_string(),
+            positions: vec![AttributeData::new(
+                "Position0",
+                VectorData::Vector3(vec![[1.0, 0.0, 0.0], [2.0, 0.0, 0.0]]),
+            )],
+            ..Default::default()
+        }
+    }
+
+    fn mesh(objects: Vec<MeshObjectData>) -> MeshData {
+        MeshData {
+            major_version: 1,
+            minor_version: 10,
+            objects,
+            layout_hints: None,
+            bounding_method: BoundingMethod::Fast,
+        }
+    }
+
+    fn translation_anim(bone_name: &str, translations: &[[f32; 3]]) -> AnimData {
+        AnimData {
+            major_version: 2,
+            minor_version: 0,
+            final_frame_index: (translations.len() - 1) as f32,
+            groups: vec![GroupData {
+                group_type: GroupType::Transform,
+                nodes: vec![NodeData {
+                    name: bone_name.to_string(),
+                    tracks: vec![TrackData {
+                        name: "Transform".to_string(),
+                        compensate_scale: false,
+                        transform_flags: TransformFlags::default(),
+                        values: TrackValues::Transform(
+                            translations
+                                .iter()
+                                .map(|[x, y, z]| Transform {
+                                    translation: Vector3::new(*x, *y, *z),
+                                    ..Transform::IDENTITY
+                                })
+                                .collect(),
+                        ),
+                    }],
+                }],
+            }],
+        }
+    }
+
+    fn aabb(info: &BoundingInfo) -> ([f32; 3], [f32; 3]) {
+        let v = info.bounding_volume;
+        ([v.min.x, v.min.y, v.min.z], [v.max.x, v.max.y, v.max.z])
+    }
+
+    #[test]
+    fn skinned_object_bounds() {
+        let mut skinned = object("a", 0, "");
+        skinned.bone_influences = vec![BoneInfluence {
+            bone_name: "Arm".to_string(),
+            vertex_weights: vec![
+                VertexWeight {
+                    vertex_index: 0,
+                    vertex_weight: 1.0,
+                },
+                VertexWeight {
+                    vertex_index: 1,
+                    vertex_weight: 0.5,
+                },
+            ],
+        }];
+        let anim = translation_anim("Arm", &[[1.0, 0.0, 0.0], [1.0, 4.0, 0.0]]);
+
+        let bounds =
+            AnimatedBounds::from_animations(&mesh(vec![skinned]), &skel(), &[&anim]).unwrap();
+
+        // The second vertex is only half weighted, so it moves half as far.
+        assert_eq!(
+            ([1.0, 0.0, 0.0], [2.0, 4.0, 0.0]),
+            aabb(&bounds.objects[0].bounding_info)
+        );
+        assert_eq!(bounds.objects[0].bounding_info, bounds.bounding_info);
+    }
+
+    #[test]
+    fn parent_bone_and_static_object_bounds() {
+        let anim = translation_anim("Arm", &[[1.0, 0.0, -3.0]]);
+
+        let bounds = AnimatedBounds::from_animations(
+            &mesh(vec![object("a", 0, "Arm"), object("b", 0, "")]),
+            &skel(),
+            &[&anim],
+        )
+        .unwrap();
+
+        assert_eq!(
+            ([1.0, 0.0, -3.0], [2.0, 0.0, 0.0]),
+            aabb(&bounds.objects[0].bounding_info)
+        );
+        assert_eq!(
+            ([1.0, 0.0, 0.0], [2.0, 0.0, 0.0]),
+            aabb(&bounds.objects[1].bounding_info)
+        );
+        assert_eq!(
+            ([1.0, 0.0, -3.0], [2.0, 0.0, 0.0]),
+            aabb(&bounds.bounding_info)
+        );
+    }
+
+    #[test]
+    fn compensate_scale_bounds() {
+        let mut anim = translation_anim("Trans", &[[0.0, 0.0, 0.0]]);
+        anim.groups[0].nodes[0].tracks[0].values = TrackValues::Transform(vec![Transform {
+            scale: Vector3::new(2.0, 2.0, 2.0),
+            ..Transform::IDENTITY
+        }]);
+        let mut arm = translation_anim("Arm", &[[1.0, 0.0, 0.0]]).groups[0].nodes[0].clone();
+        arm.tracks[0].compensate_scale = true;
+        anim.groups[0].nodes.push(arm);
+
+        let bounds =
+            AnimatedBounds::from_animations(&mesh(vec![object("a", 0, "Arm")]), &skel(), &[&anim])
+                .unwrap();
+
+        // Only the parent's scale is reverted, so the translation is still scaled.
+        assert_eq!(
+            ([1.0, 0.0, 0.0], [3.0, 0.0, 0.0]),
+            aabb(&bounds.objects[0].bounding_info)
+        );
+    }
+
+    #[test]
+    fn apply_bounds_to_mesh_and_meshex() {
+        let data = mesh(vec![object("a", 0, "Arm"), object("a", 1, "Arm")]);
+        let anim = translation_anim("Arm", &[[1.0, 0.0, 0.0], [1.0, 0.0, 10.0]]);
+        let bounds = AnimatedBounds::from_animations(&data, &skel(), &[&anim]).unwrap();
+
+        let mut mesh = Mesh::try_from(&data).unwrap();
+        bounds.apply_to_mesh(&mut mesh);
+        match mesh {
+            Mesh::V10(mesh) => {
+                assert_eq!(bounds.bounding_info, mesh.bounding_info);
+                assert_eq!(
+                    bounds.objects[1].bounding_info,
+                    mesh.objects.elements[1].bounding_info
+                );
+            }
+            _ => panic!("unexpected version"),
+        }
+
+        let mut meshex = MeshExData::from_mesh_objects(&data.objects);
+        bounds.apply_to_meshex(&mut meshex);
+        assert_eq!(1, bounds.groups.len());
+        assert_eq!(
+            bounds.groups[0].bounding_sphere,
+            meshex.mesh_object_groups[0].bounding_sphere
+        );
+        assert!(meshex.mesh_object_groups[0].bounding_sphere.radius >= 5.0);
+    }
+
+    #[test]
+    fn missing_influence_bone() {
+        let mut skinned = object("a", 0, "");
+        skinned.bone_influences = vec![BoneInfluence {
+            bone_name: "Missing".to_string(),
+            vertex_weights: Vec::new(),
+        }];
+
+        let result = AnimatedBounds::from_animations(&mesh(vec![skinned]), &skel(), &[]);
+        assert!(matches!(result, Err(error::Error::Mesh(_))));
+    }
+
+    #[test]
+    fn cycle_detected() {
+        let mut skel = skel();
+        skel.bones[0].parent_index = Some(1);
+
+        let result = AnimatedBounds::from_animations(&mesh(Vec::new()), &skel, &[]);
+        assert!(matches!(
+            result,
+            Err(error::Error::BoneTransform(
+                BoneTransformError::CycleDetected { .. }
+            ))
+        ));
+    }
+
+    #[test]
+    fn child_bone_before_parent() {
+        let skel = SkelData {
+            major_version: 1,
+            minor_version: 0,
+            bones: vec![
+                bone("Arm", Some(1), [1.0, 0.0, 0.0]),
+                bone("Trans", None, [0.0, 0.0, 0.0]),
+            ],
+        };
+        let anim = translation_anim("Trans", &[[0.0, 2.0, 0.0]]);
+
+        let bounds =
+            AnimatedBounds::from_animations(&mesh(vec![object("a", 0, "Arm")]), &skel, &[&anim])
+                .unwrap();
+
+        assert_eq!(
+            ([1.0, 0.0, 0.0], [2.0, 2.0, 0.0]),
+            aabb(&bounds.objects[0].bounding_info)
+        );
+    }
+
+    #[test]
+    fn merge_bounding_spheres() {
+        let a = Vec4::new(0.0, 0.0, 0.0, 1.0);
+        assert_eq!(a, merge_spheres(a, Vec4::new(0.5, 0.0, 0.0, 0.5)));
+        assert_eq!(
+            Vec4::new(0.0, 0.0, 0.0, 3.0),
+            merge_spheres(Vec4::new(1.0, 0.0, 0.0, 1.0), Vec4::new(0.0, 0.0, 0.0, 3.0))
+        );
+        assert_eq!(
+            Vec4::new(1.5, 0.0, 0.0, 2.5),
+            merge_spheres(a, Vec4::new(3.0, 0.0, 0.0, 1.0))
+        );
+    }
+
+    #[test]
+    fn rotation_track() {
+        let mut anim = translation_anim("Arm", &[[1.0, 0.0, 0.0]]);
+        let q = Quat::from_rotation_z(std::f32::consts::FRAC_PI_2);
+        anim.groups[0].nodes[0].tracks[0].values = TrackValues::Transform(vec![Transform {
+            rotation: Vector4::new(q.x, q.y, q.z, q.w),
+            translation: Vector3::new(1.0, 0.0, 0.0),
+            ..Transform::IDENTITY
+        }]);
+
+        let bounds =
+            AnimatedBounds::from_animations(&mesh(vec![object("a", 0, "Arm")]), &skel(), &[&anim])
+                .unwrap();
+
+        // The vertex at the bone origin stays in place and the other rotates around it.
+        let (min, max) = aabb(&bounds.objects[0].bounding_info);
+        assert!((max[1] - 1.0).abs() < 1e-6);
+        assert!((min[0] - 1.0).abs() < 1e-6 && (max[0] - 2.0).abs() < 1e-6);
+    }
+}
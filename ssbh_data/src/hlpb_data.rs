@@ -58,6 +58,61 @@ pub struct OrientConstraintData {
     pub range_max: Vector3,
 }
 
+impl HlpbData {
+    /// Renames the bones named `name` to `new_name` for all constraints.
+    /// See [SkelData::rename_bone](crate::skel_data::SkelData::rename_bone).
+    pub fn rename_bone(&mut self, name: &str, new_name: &str) {
+        let bone_names = self
+            .aim_constraints
+            .iter_mut()
+            .flat_map(|a| a.bone_names_mut())
+            .chain(
+                self.orient_constraints
+                    .iter_mut()
+                    .flat_map(|o| o.bone_names_mut()),
+            );
+        for bone_name in bone_names {
+            if bone_name == name {
+                *bone_name = new_name.to_string();
+            }
+        }
+    }
+
+    /// Removes all constraints that reference the bone named `name`
+    /// and returns the number of removed constraints.
+    /// See [SkelData::remove_bone](crate::skel_data::SkelData::remove_bone).
+    pub fn remove_bone(&mut self, name: &str) -> usize {
+        let count = self.aim_constraints.len() + self.orient_constraints.len();
+        self.aim_constraints
+            .retain_mut(|a| a.bone_names_mut().iter().all(|b| *b != name));
+        self.orient_constraints
+            .retain_mut(|o| o.bone_names_mut().iter().all(|b| *b != name));
+        count - self.aim_constraints.len() - self.orient_constraints.len()
+    }
+}
+
+impl AimConstraintData {
+    fn bone_names_mut(&mut self) -> [&mut String; 4] {
+        [
+            &mut self.aim_bone_name1,
+            &mut self.aim_bone_name2,
+            &mut self.target_bone_name1,
+            &mut self.target_bone_name2,
+        ]
+    }
+}
+
+impl OrientConstraintData {
+    fn bone_names_mut(&mut self) -> [&mut String; 4] {
+        [
+            &mut self.parent_bone_name1,
+            &mut self.parent_bone_name2,
+            &mut self.source_bone_name,
+            &mut self.target_bone_name,
+        ]
+    }
+}
+
 // Define two way conversions between types.
 impl From<Hlpb> for HlpbData {
     fn from(h: Hlpb) -> Self {
@@ -330,4 +385,37 @@ mod tests {
         assert_eq!(data, HlpbData::from(&ssbh));
         assert_eq!(ssbh, Hlpb::from(&data));
     }
+
+    #[test]
+    fn rename_remove_bone() {
+        let orient = |name: &str, source: &str| OrientConstraintData {
+            name: name.to_string(),
+            parent_bone_name1: "ArmL".to_string(),
+            parent_bone_name2: "ArmL".to_string(),
+            source_bone_name: source.to_string(),
+            target_bone_name: "H_WristL".to_string(),
+            unk_type: 2,
+            constraint_axes: Vector3::new(0.5, 0.5, 0.5),
+            quat1: Vector4::new(0.0, 0.0, 0.0, 1.0),
+            quat2: Vector4::new(0.0, 0.0, 0.0, 1.0),
+            range_min: Vector3::new(-180.0, -180.0, -180.0),
+            range_max: Vector3::new(180.0, 180.0, 180.0),
+        };
+        let mut data = HlpbData {
+            major_version: 1,
+            minor_version: 1,
+            aim_constraints: Vec::new(),
+            orient_constraints: vec![orient("orient1", "HandL"), orient("orient2", "ArmR")],
+        };
+
+        data.rename_bone("ArmL", "ArmL2");
+        assert_eq!("ArmL2", data.orient_constraints[0].parent_bone_name1);
+        assert_eq!("ArmL2", data.orient_constraints[1].parent_bone_name2);
+        assert_eq!("orient1", data.orient_constraints[0].name);
+
+        assert_eq!(1, data.remove_bone("HandL"));
+        assert_eq!("orient2", data.orient_constraints[0].name);
+        assert_eq!(1, data.remove_bone("H_WristL"));
+        assert!(data.orient_constraints.is_empty());
+    }
 }
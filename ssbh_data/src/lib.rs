@@ -45,13 +45,19 @@ data.write_to_file("model_new.numshb")?;
 //! should use [ssbh_lib](https://crates.io/crates/ssbh_lib).
 pub mod adj_data;
 pub mod anim_data;
+pub mod animated_bounds;
 pub mod hlpb_data;
 pub mod matl_data;
 pub mod mesh_data;
 pub mod meshex_data;
+pub mod model_edit;
+pub mod model_folder;
 pub mod modl_data;
 pub mod shdr_data;
 pub mod skel_data;
+mod skinning;
+pub mod spatial;
+pub mod validation;
 
 use binrw::io::{Read, Seek, Write};
 use ssbh_lib::prelude::*;
@@ -91,6 +97,7 @@ pub mod prelude {
     pub use crate::matl_data::MatlData;
     pub use crate::mesh_data::MeshData;
     pub use crate::meshex_data::MeshExData;
+    pub use crate::model_folder::ModelFolder;
     pub use crate::modl_data::ModlData;
     pub use crate::shdr_data::ShdrData;
     pub use crate::skel_data::SkelData;
@@ -40,7 +40,15 @@ use ssbh_lib::{
     },
     Color4f, RelPtr64, SsbhEnum64, Vector4, Version,
 };
-use std::{convert::TryFrom, ops::Deref};
+use std::{
+    collections::HashMap,
+    convert::TryFrom,
+    ops::Deref,
+    path::{Path, PathBuf},
+};
+
+mod diff;
+pub use diff::{MatlDiff, MatlEntryDiff, MatlMerge, MergeConflict, ParamDiff, ParamValue};
 
 pub type BlendStateParam = ParamData<BlendStateData>;
 pub type FloatParam = ParamData<f32>;
@@ -54,7 +62,8 @@ pub type UvTransformParam = ParamData<UvTransform>;
 pub mod error {
     use thiserror::Error;
 
-    /// Errors while creating a [Matl](super::Matl) from [MatlData](super::MatlData).
+    /// Errors while creating a [Matl](super::Matl) from [MatlData](super::MatlData)
+    /// or comparing [MatlData](super::MatlData).
     #[derive(Debug, Error)]
     pub enum Error {
         /// Creating a [Matl](super::Matl) file for the given version is not supported.
@@ -71,6 +80,11 @@ pub mod error {
         /// An error occurred while writing data.
         #[error(transparent)]
         Io(#[from] std::io::Error),
+
+        /// More than one material uses the same label,
+        /// so materials can't be matched by label.
+        #[error("material label {} is used by more than one material", material_label)]
+        DuplicateMaterialLabel { material_label: String },
     }
 }
 
@@ -140,6 +154,188 @@ impl<T> ParamData<T> {
     }
 }
 
+/// A texture path from a [TextureParam] and the materials that reference it.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct TextureReference {
+    /// The texture path such as `"def_mario_001_col"` or `"/common/shader/sfxpbs/default_white"`.
+    pub path: String,
+    /// The material and parameter for each [TextureParam] with this path.
+    pub usages: Vec<TextureUsage>,
+}
+
+/// A [TextureParam] in the material with label [material_label](#structfield.material_label).
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct TextureUsage {
+    pub material_label: String,
+    pub param_id: ParamId,
+}
+
+/// A [TextureReference] and the location of its `.nutexb` file.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ResolvedTexture {
+    pub reference: TextureReference,
+    pub file: TextureFile,
+}
+
+/// The result of searching the model folder for a texture's `.nutexb` file.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum TextureFile {
+    /// The `.nutexb` file exists at this path.
+    Found(PathBuf),
+    /// The `.nutexb` file does not exist at the expected path.
+    Missing(PathBuf),
+    /// The texture is shared between models like `"/common/shader/sfxpbs/default_white"`
+    /// and is not stored in the model folder.
+    NotInModelFolder,
+}
+
+impl TextureReference {
+    /// Returns `true` if [path](#structfield.path) refers to a texture in the model folder.
+    /// Absolute paths like `"/common/shader/sfxpbs/default_white"` refer to textures shared between models.
+    pub fn is_in_model_folder(&self) -> bool {
+        !(self.path.starts_with('/')
+            || self.path.starts_with('\\')
+            || Path::new(&self.path).is_absolute())
+    }
+
+    /// The expected location of the `.nutexb` file for [path](#structfield.path) relative to `model_folder`
+    /// or [None] if the texture is not in the model folder.
+    /// This does not check if the file exists.
+    /**
+    ```rust
+    # use ssbh_data::matl_data::TextureReference;
+    let texture = TextureReference {
+        path: "def_mario_001_col".to_string(),
+        usages: Vec::new(),
+    };
+    assert_eq!(
+        Some(std::path::Path::new("c00/def_mario_001_col.nutexb").to_owned()),
+        texture.nutexb_path("c00")
+    );
+
+    let texture = TextureReference {
+        path: "/common/shader/sfxpbs/default_white".to_string(),
+        usages: Vec::new(),
+    };
+    assert_eq!(None, texture.nutexb_path("c00"));
+    ```
+     */
+    pub fn nutexb_path<P: AsRef<Path>>(&self, model_folder: P) -> Option<PathBuf> {
+        self.is_in_model_folder()
+            .then(|| model_folder.as_ref().join(format!("{}.nutexb", self.path)))
+    }
+}
+
+impl MatlData {
+    /// Lists the unique texture paths for all [TextureParam] in order of first appearance.
+    /// Paths are compared exactly, so paths differing only by case are listed separately.
+    /// # Examples
+    /**
+    ```rust no_run
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    use ssbh_data::prelude::*;
+
+    let matl = MatlData::from_file("model.numatb")?;
+    for texture in matl.texture_references() {
+        println!("{}: {} usages", texture.path, texture.usages.len());
+    }
+    # Ok(()) }
+    ```
+     */
+    pub fn texture_references(&self) -> Vec<TextureReference> {
+        let mut references: Vec<TextureReference> = Vec::new();
+        let mut index_by_path: HashMap<&String, usize> = HashMap::new();
+        for entry in &self.entries {
+            for texture in &entry.textures {
+                let usage = TextureUsage {
+                    material_label: entry.material_label.clone(),
+                    param_id: texture.param_id,
+                };
+                match index_by_path.get(&texture.data) {
+                    Some(i) => references[*i].usages.push(usage),
+                    None => {
+                        index_by_path.insert(&texture.data, references.len());
+                        references.push(TextureReference {
+                            path: texture.data.clone(),
+                            usages: vec![usage],
+                        });
+                    }
+                }
+            }
+        }
+        references
+    }
+
+    /// Finds the `.nutexb` file in `model_folder` for each texture from [MatlData::texture_references].
+    ///
+    /// Textures shared between models like `"/common/shader/sfxpbs/default_white"`
+    /// are not stored in the model folder and use [TextureFile::NotInModelFolder] without checking any files.
+    pub fn resolve_textures<P: AsRef<Path>>(&self, model_folder: P) -> Vec<ResolvedTexture> {
+        self.texture_references()
+            .into_iter()
+            .map(|reference| {
+                let file = match reference.nutexb_path(model_folder.as_ref()) {
+                    Some(path) if path.is_file() => TextureFile::Found(path),
+                    Some(path) => TextureFile::Missing(path),
+                    None => TextureFile::NotInModelFolder,
+                };
+                ResolvedTexture { reference, file }
+            })
+            .collect()
+    }
+
+    /// Replaces each texture path with the value returned by `f` for all [TextureParam].
+    /// Paths are unchanged if `f` returns [None].
+    /// Returns the number of [TextureParam] that were modified.
+    /// # Examples
+    /// Port the textures for a costume slot by changing the texture name suffix.
+    /**
+    ```rust
+    # use ssbh_data::matl_data::{MatlData, MatlEntryData, ParamId, TextureParam};
+    # let mut matl = MatlData {
+    #     major_version: 1,
+    #     minor_version: 6,
+    #     entries: vec![MatlEntryData {
+    #         material_label: "a".to_string(),
+    #         shader_label: String::new(),
+    #         blend_states: Vec::new(),
+    #         floats: Vec::new(),
+    #         booleans: Vec::new(),
+    #         vectors: Vec::new(),
+    #         rasterizer_states: Vec::new(),
+    #         samplers: Vec::new(),
+    #         textures: vec![TextureParam::new(ParamId::Texture0, "def_mario_001_col".to_string())],
+    #         uv_transforms: Vec::new(),
+    #     }],
+    # };
+    let count = matl.rename_textures(|path| {
+        path.strip_suffix("_col").map(|p| format!("{p}_c05_col"))
+    });
+    assert_eq!(1, count);
+    assert_eq!("def_mario_001_c05_col", matl.entries[0].textures[0].data);
+    ```
+     */
+    pub fn rename_textures<F: FnMut(&str) -> Option<String>>(&mut self, mut f: F) -> usize {
+        let mut count = 0;
+        for texture in self.entries.iter_mut().flat_map(|e| e.textures.iter_mut()) {
+            if let Some(new_path) = f(&texture.data) {
+                if new_path != texture.data {
+                    texture.data = new_path;
+                    count += 1;
+                }
+            }
+        }
+        count
+    }
+
+    /// Replaces texture paths using the exact matches in `table` from old path to new path.
+    /// Returns the number of [TextureParam] that were modified.
+    /// For more advanced renaming, see [MatlData::rename_textures].
+    pub fn remap_textures(&mut self, table: &HashMap<String, String>) -> usize {
+        self.rename_textures(|path| table.get(path).cloned())
+    }
+}
+
 /// Data associated with a [Sampler].
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "serde", serde(default))]
@@ -1577,4 +1773,155 @@ mod tests {
             );
         }
     }
+
+    fn texture_entry(material_label: &str, textures: &[(ParamId, &str)]) -> MatlEntryData {
+        MatlEntryData {
+            material_label: material_label.to_string(),
+            shader_label: String::new(),
+            blend_states: Vec::new(),
+            floats: Vec::new(),
+            booleans: Vec::new(),
+            vectors: Vec::new(),
+            rasterizer_states: Vec::new(),
+            samplers: Vec::new(),
+            textures: textures
+                .iter()
+                .map(|(p, t)| ParamData::new(*p, t.to_string()))
+                .collect(),
+            uv_transforms: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn texture_references_shared_paths() {
+        let data = MatlData {
+            major_version: 1,
+            minor_version: 6,
+            entries: vec![
+                texture_entry(
+                    "a",
+                    &[(ParamId::Texture0, "col"), (ParamId::Texture4, "nor")],
+                ),
+                texture_entry("b", &[(ParamId::Texture0, "col")]),
+            ],
+        };
+
+        assert_eq!(
+            vec![
+                TextureReference {
+                    path: "col".to_string(),
+                    usages: vec![
+                        TextureUsage {
+                            material_label: "a".to_string(),
+                            param_id: ParamId::Texture0
+                        },
+                        TextureUsage {
+                            material_label: "b".to_string(),
+                            param_id: ParamId::Texture0
+                        }
+                    ]
+                },
+                TextureReference {
+                    path: "nor".to_string(),
+                    usages: vec![TextureUsage {
+                        material_label: "a".to_string(),
+                        param_id: ParamId::Texture4
+                    }]
+                }
+            ],
+            data.texture_references()
+        );
+    }
+
+    #[test]
+    fn resolve_textures_missing_folder() {
+        let data = MatlData {
+            major_version: 1,
+            minor_version: 6,
+            entries: vec![texture_entry("a", &[(ParamId::Texture0, "col")])],
+        };
+
+        let textures = data.resolve_textures("this/folder/does/not/exist");
+        assert_eq!(1, textures.len());
+        assert_eq!(
+            TextureFile::Missing(Path::new("this/folder/does/not/exist/col.nutexb").to_owned()),
+            textures[0].file
+        );
+    }
+
+    #[test]
+    fn resolve_textures_existing_file() {
+        let folder = std::env::temp_dir().join("ssbh_data_matl_resolve_textures");
+        std::fs::create_dir_all(&folder).unwrap();
+        std::fs::write(folder.join("col.nutexb"), []).unwrap();
+
+        let data = MatlData {
+            major_version: 1,
+            minor_version: 6,
+            entries: vec![texture_entry(
+                "a",
+                &[(ParamId::Texture0, "col"), (ParamId::Texture4, "nor")],
+            )],
+        };
+
+        let textures = data.resolve_textures(&folder);
+        assert_eq!(
+            vec![
+                TextureFile::Found(folder.join("col.nutexb")),
+                TextureFile::Missing(folder.join("nor.nutexb"))
+            ],
+            textures.into_iter().map(|t| t.file).collect::<Vec<_>>()
+        );
+
+        std::fs::remove_dir_all(&folder).unwrap();
+    }
+
+    #[test]
+    fn resolve_textures_common_path() {
+        let data = MatlData {
+            major_version: 1,
+            minor_version: 6,
+            entries: vec![texture_entry(
+                "a",
+                &[(ParamId::Texture0, "/common/shader/sfxpbs/default_white")],
+            )],
+        };
+
+        // Shared textures should not be checked relative to the file system root.
+        let textures = data.resolve_textures("/");
+        assert_eq!(TextureFile::NotInModelFolder, textures[0].file);
+    }
+
+    #[test]
+    fn remap_textures_table() {
+        let mut data = MatlData {
+            major_version: 1,
+            minor_version: 6,
+            entries: vec![
+                texture_entry(
+                    "a",
+                    &[(ParamId::Texture0, "c00_col"), (ParamId::Texture4, "nor")],
+                ),
+                texture_entry("b", &[(ParamId::Texture0, "c00_col")]),
+            ],
+        };
+
+        let table = [("c00_col".to_string(), "c05_col".to_string())].into();
+        assert_eq!(2, data.remap_textures(&table));
+        assert_eq!("c05_col", data.entries[0].textures[0].data);
+        assert_eq!("nor", data.entries[0].textures[1].data);
+        assert_eq!("c05_col", data.entries[1].textures[0].data);
+    }
+
+    #[test]
+    fn rename_textures_unchanged() {
+        let mut data = MatlData {
+            major_version: 1,
+            minor_version: 6,
+            entries: vec![texture_entry("a", &[(ParamId::Texture0, "col")])],
+        };
+
+        assert_eq!(0, data.rename_textures(|p| Some(p.to_string())));
+        assert_eq!(0, data.rename_textures(|_| None));
+    }
 }
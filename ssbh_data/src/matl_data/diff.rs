@@ -0,0 +1,718 @@
+use super::*;
+
+/// The value of any material parameter type.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, PartialEq, Clone)]
+pub enum ParamValue {
+    BlendState(BlendStateData),
+    Float(f32),
+    Boolean(bool),
+    Vector4(Vector4),
+    RasterizerState(RasterizerStateData),
+    Sampler(SamplerData),
+    Texture(String),
+    UvTransform(UvTransform),
+}
+
+/// The differences between two [MatlData] with materials identified by
+/// [material_label](struct.MatlEntryData.html#structfield.material_label).
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct MatlDiff {
+    pub entries: Vec<MatlEntryDiff>,
+}
+
+impl MatlDiff {
+    /// Returns `true` if there are no differences.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+/// The differences for a single material.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, PartialEq, Clone)]
+pub enum MatlEntryDiff {
+    /// The material is only present in the new data.
+    Added(MatlEntryData),
+    /// The material is only present in the old data.
+    Removed(MatlEntryData),
+    /// The material is present in both but with different values.
+    Changed {
+        material_label: String,
+        /// The old and new shader label if the shader changed.
+        shader_label: Option<(String, String)>,
+        params: Vec<ParamDiff>,
+    },
+}
+
+/// The differences for a single parameter identified by its [ParamId].
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, PartialEq, Clone)]
+pub enum ParamDiff {
+    Added {
+        param_id: ParamId,
+        value: ParamValue,
+    },
+    Removed {
+        param_id: ParamId,
+        value: ParamValue,
+    },
+    Changed {
+        param_id: ParamId,
+        old: ParamValue,
+        new: ParamValue,
+    },
+}
+
+/// The result of [MatlData::merge].
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, PartialEq, Clone)]
+pub struct MatlMerge {
+    /// The merged data with the value from `ours` used for any conflicts.
+    pub data: MatlData,
+    pub conflicts: Vec<MergeConflict>,
+}
+
+/// A value changed differently in both `ours` and `theirs` relative to `base`.
+/// Values are [None] if the item is not present.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, PartialEq, Clone)]
+pub enum MergeConflict {
+    Version {
+        base: (u16, u16),
+        ours: (u16, u16),
+        theirs: (u16, u16),
+    },
+    /// A material was added differently by both sides or removed by one side and changed by the other.
+    Entry {
+        material_label: String,
+        base: Option<Box<MatlEntryData>>,
+        ours: Option<Box<MatlEntryData>>,
+        theirs: Option<Box<MatlEntryData>>,
+    },
+    ShaderLabel {
+        material_label: String,
+        base: Option<String>,
+        ours: String,
+        theirs: String,
+    },
+    Param {
+        material_label: String,
+        param_id: ParamId,
+        base: Option<ParamValue>,
+        ours: Option<ParamValue>,
+        theirs: Option<ParamValue>,
+    },
+}
+
+impl MatlData {
+    /// Compares the materials in `self` and `other` by label.
+    /// Parameters within a material are compared by [ParamId],
+    /// so reordering parameters is not considered a change.
+    /// Floating point values that are both NaN are considered equal.
+    ///
+    /// Returns [error::Error::DuplicateMaterialLabel] if a material label is not unique in either data.
+    /// # Examples
+    /**
+    ```rust no_run
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    use ssbh_data::prelude::*;
+    use ssbh_data::matl_data::MatlEntryDiff;
+
+    let old = MatlData::from_file("c00/model.numatb")?;
+    let new = MatlData::from_file("c01/model.numatb")?;
+    for entry in old.diff(&new)?.entries {
+        if let MatlEntryDiff::Changed { material_label, params, .. } = entry {
+            println!("{material_label}: {params:?}");
+        }
+    }
+    # Ok(()) }
+    ```
+     */
+    pub fn diff(&self, other: &MatlData) -> Result<MatlDiff, error::Error> {
+        check_unique_labels(&self.entries)?;
+        check_unique_labels(&other.entries)?;
+
+        let mut entries = Vec::new();
+
+        for old in &self.entries {
+            match find_entry(&other.entries, &old.material_label) {
+                Some(new) => {
+                    let shader_label = (old.shader_label != new.shader_label)
+                        .then(|| (old.shader_label.clone(), new.shader_label.clone()));
+                    let params = diff_params(&entry_params(old), &entry_params(new));
+                    if shader_label.is_some() || !params.is_empty() {
+                        entries.push(MatlEntryDiff::Changed {
+                            material_label: old.material_label.clone(),
+                            shader_label,
+                            params,
+                        });
+                    }
+                }
+                None => entries.push(MatlEntryDiff::Removed(old.clone())),
+            }
+        }
+
+        for new in &other.entries {
+            if find_entry(&self.entries, &new.material_label).is_none() {
+                entries.push(MatlEntryDiff::Added(new.clone()));
+            }
+        }
+
+        Ok(MatlDiff { entries })
+    }
+
+    /// Performs a three-way merge of the changes from `base` to `ours` and `base` to `theirs`.
+    /// Changes are merged per material label, shader label, and [ParamId].
+    /// Conflicting changes use the value from `ours` and are listed in [conflicts](struct.MatlMerge.html#structfield.conflicts).
+    ///
+    /// Materials and parameters are ordered as in `ours` followed by any additions from `theirs`.
+    /// Values are compared the same way as [MatlData::diff].
+    ///
+    /// Returns [error::Error::DuplicateMaterialLabel] if a material label is not unique in any of the data.
+    pub fn merge(
+        base: &MatlData,
+        ours: &MatlData,
+        theirs: &MatlData,
+    ) -> Result<MatlMerge, error::Error> {
+        check_unique_labels(&base.entries)?;
+        check_unique_labels(&ours.entries)?;
+        check_unique_labels(&theirs.entries)?;
+
+        let mut conflicts = Vec::new();
+
+        let base_version = (base.major_version, base.minor_version);
+        let ours_version = (ours.major_version, ours.minor_version);
+        let theirs_version = (theirs.major_version, theirs.minor_version);
+        let (major_version, minor_version) = match merge_value(
+            Some(&base_version),
+            Some(&ours_version),
+            Some(&theirs_version),
+            PartialEq::eq,
+        ) {
+            Some(Some(version)) => version,
+            _ => {
+                conflicts.push(MergeConflict::Version {
+                    base: base_version,
+                    ours: ours_version,
+                    theirs: theirs_version,
+                });
+                ours_version
+            }
+        };
+
+        let labels = ours
+            .entries
+            .iter()
+            .chain(theirs.entries.iter())
+            .map(|e| e.material_label.as_str())
+            .unique();
+
+        let mut entries = Vec::new();
+        for label in labels {
+            let base_entry = find_entry(&base.entries, label);
+            let ours_entry = find_entry(&ours.entries, label);
+            let theirs_entry = find_entry(&theirs.entries, label);
+
+            match (ours_entry, theirs_entry) {
+                (Some(o), Some(t)) => {
+                    if base_entry.is_none() && !entries_equal(o, t) {
+                        conflicts.push(MergeConflict::Entry {
+                            material_label: label.to_string(),
+                            base: None,
+                            ours: Some(Box::new(o.clone())),
+                            theirs: Some(Box::new(t.clone())),
+                        });
+                        entries.push(o.clone());
+                    } else {
+                        entries.push(merge_entry(base_entry, o, t, &mut conflicts));
+                    }
+                }
+                (Some(e), None) | (None, Some(e)) => match base_entry {
+                    // The entry was removed by one side.
+                    Some(b) if !entries_equal(b, e) => {
+                        conflicts.push(MergeConflict::Entry {
+                            material_label: label.to_string(),
+                            base: Some(Box::new(b.clone())),
+                            ours: ours_entry.cloned().map(Box::new),
+                            theirs: theirs_entry.cloned().map(Box::new),
+                        });
+                        if let Some(o) = ours_entry {
+                            entries.push(o.clone());
+                        }
+                    }
+                    Some(_) => (),
+                    // The entry was added by one side.
+                    None => entries.push(e.clone()),
+                },
+                (None, None) => (),
+            }
+        }
+
+        Ok(MatlMerge {
+            data: MatlData {
+                major_version,
+                minor_version,
+                entries,
+            },
+            conflicts,
+        })
+    }
+}
+
+fn check_unique_labels(entries: &[MatlEntryData]) -> Result<(), error::Error> {
+    match entries
+        .iter()
+        .map(|e| &e.material_label)
+        .duplicates()
+        .next()
+    {
+        Some(material_label) => Err(error::Error::DuplicateMaterialLabel {
+            material_label: material_label.clone(),
+        }),
+        None => Ok(()),
+    }
+}
+
+fn find_entry<'a>(entries: &'a [MatlEntryData], material_label: &str) -> Option<&'a MatlEntryData> {
+    entries.iter().find(|e| e.material_label == material_label)
+}
+
+fn find_param(params: &[(ParamId, ParamValue)], param_id: ParamId) -> Option<&ParamValue> {
+    params.iter().find(|(p, _)| *p == param_id).map(|(_, v)| v)
+}
+
+// Returns None if the value was changed differently by both sides.
+fn merge_value<T: Clone>(
+    base: Option<&T>,
+    ours: Option<&T>,
+    theirs: Option<&T>,
+    eq: impl Fn(&T, &T) -> bool,
+) -> Option<Option<T>> {
+    let eq = |a: Option<&T>, b: Option<&T>| match (a, b) {
+        (Some(a), Some(b)) => eq(a, b),
+        (a, b) => a.is_none() && b.is_none(),
+    };
+    if eq(ours, theirs) || eq(base, theirs) {
+        Some(ours.cloned())
+    } else if eq(base, ours) {
+        Some(theirs.cloned())
+    } else {
+        None
+    }
+}
+
+fn entries_equal(a: &MatlEntryData, b: &MatlEntryData) -> bool {
+    a.material_label == b.material_label
+        && a.shader_label == b.shader_label
+        && diff_params(&entry_params(a), &entry_params(b)).is_empty()
+}
+
+// Treat NaN as equal to NaN so unchanged NaN values aren't reported as changes.
+fn floats_equal(a: f32, b: f32) -> bool {
+    a == b || (a.is_nan() && b.is_nan())
+}
+
+fn all_floats_equal<const N: usize>(a: [f32; N], b: [f32; N]) -> bool {
+    a.into_iter().zip(b).all(|(a, b)| floats_equal(a, b))
+}
+
+fn values_equal(a: &ParamValue, b: &ParamValue) -> bool {
+    match (a, b) {
+        (ParamValue::Float(a), ParamValue::Float(b)) => floats_equal(*a, *b),
+        (ParamValue::Vector4(a), ParamValue::Vector4(b)) => {
+            all_floats_equal(a.to_array(), b.to_array())
+        }
+        (ParamValue::RasterizerState(a), ParamValue::RasterizerState(b)) => {
+            floats_equal(a.depth_bias, b.depth_bias)
+                && a.fill_mode == b.fill_mode
+                && a.cull_mode == b.cull_mode
+        }
+        (ParamValue::Sampler(a), ParamValue::Sampler(b)) => {
+            let color = |c: &Color4f| [c.r, c.g, c.b, c.a];
+            floats_equal(a.lod_bias, b.lod_bias)
+                && all_floats_equal(color(&a.border_color), color(&b.border_color))
+                && a.wraps == b.wraps
+                && a.wrapt == b.wrapt
+                && a.wrapr == b.wrapr
+                && a.min_filter == b.min_filter
+                && a.mag_filter == b.mag_filter
+                && a.max_anisotropy == b.max_anisotropy
+        }
+        (ParamValue::UvTransform(a), ParamValue::UvTransform(b)) => all_floats_equal(
+            [
+                a.scale_u,
+                a.scale_v,
+                a.rotation,
+                a.translate_u,
+                a.translate_v,
+            ],
+            [
+                b.scale_u,
+                b.scale_v,
+                b.rotation,
+                b.translate_u,
+                b.translate_v,
+            ],
+        ),
+        _ => a == b,
+    }
+}
+
+fn merge_entry(
+    base: Option<&MatlEntryData>,
+    ours: &MatlEntryData,
+    theirs: &MatlEntryData,
+    conflicts: &mut Vec<MergeConflict>,
+) -> MatlEntryData {
+    let shader_label = match merge_value(
+        base.map(|b| &b.shader_label),
+        Some(&ours.shader_label),
+        Some(&theirs.shader_label),
+        PartialEq::eq,
+    ) {
+        Some(Some(shader_label)) => shader_label,
+        _ => {
+            conflicts.push(MergeConflict::ShaderLabel {
+                material_label: ours.material_label.clone(),
+                base: base.map(|b| b.shader_label.clone()),
+                ours: ours.shader_label.clone(),
+                theirs: theirs.shader_label.clone(),
+            });
+            ours.shader_label.clone()
+        }
+    };
+
+    let base_params = base.map(entry_params).unwrap_or_default();
+    let ours_params = entry_params(ours);
+    let theirs_params = entry_params(theirs);
+
+    let param_ids = ours_params
+        .iter()
+        .chain(theirs_params.iter())
+        .map(|(p, _)| *p)
+        .fold(Vec::new(), |mut ids, p| {
+            if !ids.contains(&p) {
+                ids.push(p);
+            }
+            ids
+        });
+
+    let mut params = Vec::new();
+    for param_id in param_ids {
+        let b = find_param(&base_params, param_id);
+        let o = find_param(&ours_params, param_id);
+        let t = find_param(&theirs_params, param_id);
+        match merge_value(b, o, t, values_equal) {
+            Some(value) => params.extend(value.map(|v| (param_id, v))),
+            None => {
+                conflicts.push(MergeConflict::Param {
+                    material_label: ours.material_label.clone(),
+                    param_id,
+                    base: b.cloned(),
+                    ours: o.cloned(),
+                    theirs: t.cloned(),
+                });
+                params.extend(o.cloned().map(|v| (param_id, v)));
+            }
+        }
+    }
+
+    entry_from_params(ours.material_label.clone(), shader_label, params)
+}
+
+fn diff_params(old: &[(ParamId, ParamValue)], new: &[(ParamId, ParamValue)]) -> Vec<ParamDiff> {
+    let mut params = Vec::new();
+    for (param_id, old_value) in old {
+        match find_param(new, *param_id) {
+            Some(new_value) => {
+                if !values_equal(old_value, new_value) {
+                    params.push(ParamDiff::Changed {
+                        param_id: *param_id,
+                        old: old_value.clone(),
+                        new: new_value.clone(),
+                    });
+                }
+            }
+            None => params.push(ParamDiff::Removed {
+                param_id: *param_id,
+                value: old_value.clone(),
+            }),
+        }
+    }
+
+    for (param_id, new_value) in new {
+        if find_param(old, *param_id).is_none() {
+            params.push(ParamDiff::Added {
+                param_id: *param_id,
+                value: new_value.clone(),
+            });
+        }
+    }
+    params
+}
+
+fn entry_params(e: &MatlEntryData) -> Vec<(ParamId, ParamValue)> {
+    e.blend_states
+        .iter()
+        .map(|p| (p.param_id, ParamValue::BlendState(p.data.clone())))
+        .chain(
+            e.floats
+                .iter()
+                .map(|p| (p.param_id, ParamValue::Float(p.data))),
+        )
+        .chain(
+            e.booleans
+                .iter()
+                .map(|p| (p.param_id, ParamValue::Boolean(p.data))),
+        )
+        .chain(
+            e.vectors
+                .iter()
+                .map(|p| (p.param_id, ParamValue::Vector4(p.data))),
+        )
+        .chain(
+            e.rasterizer_states
+                .iter()
+                .map(|p| (p.param_id, ParamValue::RasterizerState(p.data.clone()))),
+        )
+        .chain(
+            e.samplers
+                .iter()
+                .map(|p| (p.param_id, ParamValue::Sampler(p.data.clone()))),
+        )
+        .chain(
+            e.textures
+                .iter()
+                .map(|p| (p.param_id, ParamValue::Texture(p.data.clone()))),
+        )
+        .chain(
+            e.uv_transforms
+                .iter()
+                .map(|p| (p.param_id, ParamValue::UvTransform(p.data.clone()))),
+        )
+        .collect()
+}
+
+fn entry_from_params(
+    material_label: String,
+    shader_label: String,
+    params: Vec<(ParamId, ParamValue)>,
+) -> MatlEntryData {
+    let mut entry = MatlEntryData {
+        material_label,
+        shader_label,
+        blend_states: Vec::new(),
+        floats: Vec::new(),
+        booleans: Vec::new(),
+        vectors: Vec::new(),
+        rasterizer_states: Vec::new(),
+        samplers: Vec::new(),
+        textures: Vec::new(),
+        uv_transforms: Vec::new(),
+    };
+    for (param_id, value) in params {
+        match value {
+            ParamValue::BlendState(v) => entry.blend_states.push(ParamData::new(param_id, v)),
+            ParamValue::Float(v) => entry.floats.push(ParamData::new(param_id, v)),
+            ParamValue::Boolean(v) => entry.booleans.push(ParamData::new(param_id, v)),
+            ParamValue::Vector4(v) => entry.vectors.push(ParamData::new(param_id, v)),
+            ParamValue::RasterizerState(v) => {
+                entry.rasterizer_states.push(ParamData::new(param_id, v))
+            }
+            ParamValue::Sampler(v) => entry.samplers.push(ParamData::new(param_id, v)),
+            ParamValue::Texture(v) => entry.textures.push(ParamData::new(param_id, v)),
+            ParamValue::UvTransform(v) => entry.uv_transforms.push(ParamData::new(param_id, v)),
+        }
+    }
+    entry
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(material_label: &str, floats: &[(ParamId, f32)]) -> MatlEntryData {
+        entry_from_params(
+            material_label.to_string(),
+            "shader".to_string(),
+            floats
+                .iter()
+                .map(|(p, v)| (*p, ParamValue::Float(*v)))
+                .collect(),
+        )
+    }
+
+    fn matl(entries: Vec<MatlEntryData>) -> MatlData {
+        MatlData {
+            major_version: 1,
+            minor_version: 6,
+            entries,
+        }
+    }
+
+    #[test]
+    fn diff_identical() {
+        let a = matl(vec![entry("a", &[(ParamId::CustomFloat0, 1.0)])]);
+        assert!(a.diff(&a.clone()).unwrap().is_empty());
+    }
+
+    #[test]
+    fn diff_added_removed_changed() {
+        let old = matl(vec![
+            entry(
+                "a",
+                &[(ParamId::CustomFloat0, 1.0), (ParamId::CustomFloat1, 2.0)],
+            ),
+            entry("b", &[]),
+        ]);
+        let new = matl(vec![
+            entry(
+                "a",
+                &[(ParamId::CustomFloat0, 3.0), (ParamId::CustomFloat8, 4.0)],
+            ),
+            entry("c", &[]),
+        ]);
+
+        assert_eq!(
+            MatlDiff {
+                entries: vec![
+                    MatlEntryDiff::Changed {
+                        material_label: "a".to_string(),
+                        shader_label: None,
+                        params: vec![
+                            ParamDiff::Changed {
+                                param_id: ParamId::CustomFloat0,
+                                old: ParamValue::Float(1.0),
+                                new: ParamValue::Float(3.0)
+                            },
+                            ParamDiff::Removed {
+                                param_id: ParamId::CustomFloat1,
+                                value: ParamValue::Float(2.0)
+                            },
+                            ParamDiff::Added {
+                                param_id: ParamId::CustomFloat8,
+                                value: ParamValue::Float(4.0)
+                            }
+                        ]
+                    },
+                    MatlEntryDiff::Removed(entry("b", &[])),
+                    MatlEntryDiff::Added(entry("c", &[])),
+                ]
+            },
+            old.diff(&new).unwrap()
+        );
+    }
+
+    #[test]
+    fn merge_independent_changes() {
+        let base = matl(vec![entry(
+            "a",
+            &[(ParamId::CustomFloat0, 1.0), (ParamId::CustomFloat1, 2.0)],
+        )]);
+        let ours = matl(vec![entry(
+            "a",
+            &[(ParamId::CustomFloat0, 5.0), (ParamId::CustomFloat1, 2.0)],
+        )]);
+        let theirs = matl(vec![
+            entry("a", &[(ParamId::CustomFloat0, 1.0)]),
+            entry("b", &[]),
+        ]);
+
+        let result = MatlData::merge(&base, &ours, &theirs).unwrap();
+        assert!(result.conflicts.is_empty());
+        assert_eq!(
+            matl(vec![
+                entry("a", &[(ParamId::CustomFloat0, 5.0)]),
+                entry("b", &[])
+            ]),
+            result.data
+        );
+    }
+
+    #[test]
+    fn merge_conflicting_param() {
+        let base = matl(vec![entry("a", &[(ParamId::CustomFloat0, 1.0)])]);
+        let ours = matl(vec![entry("a", &[(ParamId::CustomFloat0, 2.0)])]);
+        let theirs = matl(vec![entry("a", &[(ParamId::CustomFloat0, 3.0)])]);
+
+        let result = MatlData::merge(&base, &ours, &theirs).unwrap();
+        assert_eq!(ours, result.data);
+        assert_eq!(
+            vec![MergeConflict::Param {
+                material_label: "a".to_string(),
+                param_id: ParamId::CustomFloat0,
+                base: Some(ParamValue::Float(1.0)),
+                ours: Some(ParamValue::Float(2.0)),
+                theirs: Some(ParamValue::Float(3.0)),
+            }],
+            result.conflicts
+        );
+    }
+
+    #[test]
+    fn merge_remove_modified_entry() {
+        let base = matl(vec![entry("a", &[(ParamId::CustomFloat0, 1.0)])]);
+        let ours = matl(Vec::new());
+        let theirs = matl(vec![entry("a", &[(ParamId::CustomFloat0, 3.0)])]);
+
+        let result = MatlData::merge(&base, &ours, &theirs).unwrap();
+        assert!(result.data.entries.is_empty());
+        assert!(matches!(
+            result.conflicts.as_slice(),
+            [MergeConflict::Entry { ours: None, .. }]
+        ));
+    }
+
+    #[test]
+    fn merge_remove_unmodified_entry() {
+        let base = matl(vec![entry("a", &[(ParamId::CustomFloat0, 1.0)])]);
+        let ours = base.clone();
+        let theirs = matl(Vec::new());
+
+        let result = MatlData::merge(&base, &ours, &theirs).unwrap();
+        assert!(result.data.entries.is_empty());
+        assert!(result.conflicts.is_empty());
+    }
+
+    #[test]
+    fn diff_nan_unchanged() {
+        let a = matl(vec![entry("a", &[(ParamId::CustomFloat0, f32::NAN)])]);
+        assert!(a.diff(&a.clone()).unwrap().is_empty());
+
+        let b = matl(vec![entry("a", &[(ParamId::CustomFloat0, 1.0)])]);
+        assert_eq!(1, a.diff(&b).unwrap().entries.len());
+    }
+
+    #[test]
+    fn diff_duplicate_labels() {
+        let a = matl(vec![entry("a", &[]), entry("a", &[])]);
+        let b = matl(vec![entry("a", &[])]);
+        assert!(matches!(
+            b.diff(&a),
+            Err(error::Error::DuplicateMaterialLabel { material_label }) if material_label == "a"
+        ));
+        assert!(matches!(
+            MatlData::merge(&b, &b, &a),
+            Err(error::Error::DuplicateMaterialLabel { material_label }) if material_label == "a"
+        ));
+    }
+
+    #[test]
+    fn merge_nan_unchanged() {
+        let base = matl(vec![entry("a", &[(ParamId::CustomFloat0, f32::NAN)])]);
+        let ours = matl(vec![entry(
+            "a",
+            &[
+                (ParamId::CustomFloat0, f32::NAN),
+                (ParamId::CustomFloat1, 1.0),
+            ],
+        )]);
+        let theirs = base.clone();
+
+        let result = MatlData::merge(&base, &ours, &theirs).unwrap();
+        assert!(result.conflicts.is_empty());
+        assert!(ours.diff(&result.data).unwrap().is_empty());
+    }
+}
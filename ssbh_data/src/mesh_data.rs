@@ -13,15 +13,20 @@
 //! is handled automatically when saving or converting to [Mesh].
 //!
 //! # File Differences
-//! Unmodified files are not guaranteed to be binary identical after saving.
 //! [VectorData] uses [f32], which has enough precision to encode all known data types used for [Mesh] buffers.
-//! When converting to [Mesh], the buffers are rebuilt using data types selected to balance
-//! precision and space based on the attribute's usage. The resulting buffer is often identical in practice,
-//! but this depends on the original file's data types.
+//! When converting to [Mesh], the buffers are rebuilt using the [data_type](struct.AttributeData.html#structfield.data_type) for each attribute.
+//! Attributes read from a file use their original data types, so unmodified buffer data is preserved.
+//! Attributes without a data type use data types selected to balance precision and space based on the attribute's usage.
 //!
-//! Bounding information is recalculated on export and is unlikely to match the original file
+//! Reading also stores [MeshLayoutHints] with the original attribute order, buffer offsets, strides, index type, and bounding info.
+//! Objects that have not been modified since reading are saved using their original layout.
+//! This preserves most of the data for unmodified files with objects in the original order,
+//! but saving is not guaranteed to produce a binary identical file.
+//! Modified objects or objects without hints are rebuilt using the conventional layout for the specified version.
+//!
+//! Bounding information is recalculated on export for modified objects and is unlikely to match the original file
 //! due to algorithmic differences and floating point errors.
-//! The current algorithm is efficient but often overestimates the required bounding sphere size.
+//! See [BoundingMethod] for the supported algorithms.
 use ahash::{AHashMap, AHashSet};
 use binrw::io::Seek;
 use binrw::{io::Cursor, BinRead};
@@ -30,10 +35,7 @@ use half::f16;
 use itertools::Itertools;
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
-use ssbh_lib::formats::mesh::{
-    AttributeV9, BoundingInfo, BoundingSphere, BoundingVolume, DepthFlags, MeshInner,
-    OrientedBoundingBox,
-};
+use ssbh_lib::formats::mesh::{AttributeV9, DepthFlags, MeshInner};
 use ssbh_lib::{
     formats::mesh::{
         AttributeDataTypeV10, AttributeDataTypeV8, AttributeUsageV8, AttributeUsageV9,
@@ -42,7 +44,7 @@ use ssbh_lib::{
     },
     SsbhByteBuffer,
 };
-use ssbh_lib::{Matrix3x3, SsbhArray, Vector3, Version};
+use ssbh_lib::{SsbhArray, Version};
 use ssbh_write::SsbhWrite;
 use std::collections::{HashMap, HashSet};
 use std::convert::{TryFrom, TryInto};
@@ -50,14 +52,62 @@ use std::io::{Read, SeekFrom};
 use std::{error::Error, io::Write};
 
 mod vector_data;
+pub(crate) use vector_data::{float_key, position_ids};
+
+mod union_find;
+pub(crate) use union_find::{find, union};
 pub use vector_data::VectorData;
+use vector_data::VectorDataV10;
 
 mod mesh_attributes;
 use mesh_attributes::*;
 
-// A union of data types across all mesh versions.
-#[derive(Debug, PartialEq, Clone, Copy)]
-pub(crate) enum DataType {
+mod optimize;
+
+mod skin_weights;
+pub use skin_weights::{FixedSkinWeights, SkinWeights};
+
+mod tangent_space;
+pub use tangent_space::TangentSpaceOptions;
+
+pub(crate) mod bvh;
+
+mod bounding;
+pub use bounding::BoundingMethod;
+pub(crate) use bounding::{calculate_bounding_info, calculate_bounding_sphere};
+
+mod merge_split;
+
+mod view;
+pub use view::{MeshObjectView, MeshView};
+
+mod simplify;
+pub use simplify::SimplifyOptions;
+
+mod uv_color;
+pub use uv_color::{color_set_scale, UvLayer};
+
+mod version_conversion;
+pub use version_conversion::VersionConversionIssue;
+
+mod topology;
+pub use topology::{DiagnosticOptions, TopologyIssue};
+
+mod weight_transfer;
+pub use weight_transfer::WeightTransferMethod;
+
+mod layout_hints;
+use layout_hints::UnmodifiedObjects;
+pub use layout_hints::{AttributeLayouts, MeshLayoutHints, MeshObjectLayoutHints};
+
+/// The data type for storing an [AttributeData] in the vertex buffers.
+///
+/// This is a union of the data types across all mesh versions.
+/// [AttributeDataType::HalfFloat2] is only supported by version 1.10.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub enum AttributeDataType {
     Float2,
     Float3,
     Float4,
@@ -67,7 +117,7 @@ pub(crate) enum DataType {
 }
 
 // A union of usages across all mesh versions.
-#[derive(Debug, PartialEq, Clone, Copy)]
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
 enum AttributeUsage {
     Position,
     Normal,
@@ -141,6 +191,55 @@ pub mod error {
             mesh_object_subindex: u64,
         },
 
+        /// A [BoneInfluence](super::BoneInfluence) uses a bone that is not in the list of bone names.
+        #[error("bone {} is not in the list of bone names", bone_name)]
+        InfluenceBoneNotFound { bone_name: String },
+
+        /// A vertex has more influences than the available number of slots.
+        #[error(
+            "vertex {} has {} influences, exceeding the limit of {}",
+            vertex_index,
+            influence_count,
+            max_influences
+        )]
+        TooManyInfluences {
+            vertex_index: usize,
+            influence_count: usize,
+            max_influences: usize,
+        },
+
+        /// A triangle index is out of range for the triangles in the vertex indices.
+        #[error(
+            "triangle index {} is out of range for {} triangles",
+            triangle_index,
+            triangle_count
+        )]
+        TriangleIndexOutOfRange {
+            triangle_index: usize,
+            triangle_count: usize,
+        },
+
+        /// An attribute required for calculating vertex data is missing.
+        #[error("missing required attribute {}", attribute_name)]
+        MissingAttribute { attribute_name: String },
+
+        /// The same [UvLayer](super::UvLayer) was provided more than once.
+        #[error("uv layer {} is repeated", attribute_name)]
+        DuplicateUvLayer { attribute_name: String },
+
+        /// Converting to the given version would remove or modify data in strict mode.
+        #[error(
+            "converting to mesh version {}.{} would remove or modify data in {} places",
+            major_version,
+            minor_version,
+            issues.len()
+        )]
+        LossyVersionConversion {
+            major_version: u16,
+            minor_version: u16,
+            issues: Vec<super::VersionConversionIssue>,
+        },
+
         /// An error occurred while writing data to a buffer.
         #[error(transparent)]
         Io(#[from] std::io::Error),
@@ -183,7 +282,7 @@ pub struct VertexWeight {
     pub vertex_weight: f32,
 }
 
-impl From<AttributeDataTypeV10> for DataType {
+impl From<AttributeDataTypeV10> for AttributeDataType {
     fn from(value: AttributeDataTypeV10) -> Self {
         match value {
             AttributeDataTypeV10::Float3 => Self::Float3,
@@ -196,7 +295,7 @@ impl From<AttributeDataTypeV10> for DataType {
     }
 }
 
-impl From<AttributeDataTypeV8> for DataType {
+impl From<AttributeDataTypeV8> for AttributeDataType {
     fn from(value: AttributeDataTypeV8) -> Self {
         match value {
             AttributeDataTypeV8::Float3 => Self::Float3,
@@ -253,12 +352,14 @@ impl From<f32> for Half {
     }
 }
 
-trait Attribute: BinRead + SsbhWrite
+trait Attribute: BinRead + SsbhWrite + Clone
 where
     Self: for<'a> BinRead<Args<'a> = ()>,
 {
     fn to_attribute(&self) -> MeshAttribute;
     fn usage(&self) -> AttributeUsage;
+    fn to_layouts(attributes: &[Self]) -> AttributeLayouts;
+    fn from_layouts(layouts: &AttributeLayouts) -> Option<&[Self]>;
 }
 
 // TODO: Test this
@@ -291,6 +392,17 @@ impl Attribute for AttributeV8 {
             AttributeUsageV8::ColorSet => AttributeUsage::ColorSet,
         }
     }
+
+    fn to_layouts(attributes: &[Self]) -> AttributeLayouts {
+        AttributeLayouts::V8(attributes.to_vec())
+    }
+
+    fn from_layouts(layouts: &AttributeLayouts) -> Option<&[Self]> {
+        match layouts {
+            AttributeLayouts::V8(attributes) => Some(attributes),
+            _ => None,
+        }
+    }
 }
 // TODO: Test this.
 impl Attribute for AttributeV9 {
@@ -313,6 +425,17 @@ impl Attribute for AttributeV9 {
             AttributeUsageV9::ColorSet => AttributeUsage::ColorSet,
         }
     }
+
+    fn to_layouts(attributes: &[Self]) -> AttributeLayouts {
+        AttributeLayouts::V9(attributes.to_vec())
+    }
+
+    fn from_layouts(layouts: &AttributeLayouts) -> Option<&[Self]> {
+        match layouts {
+            AttributeLayouts::V9(attributes) => Some(attributes),
+            _ => None,
+        }
+    }
 }
 impl Attribute for AttributeV10 {
     fn to_attribute(&self) -> MeshAttribute {
@@ -334,6 +457,17 @@ impl Attribute for AttributeV10 {
             AttributeUsageV9::ColorSet => AttributeUsage::ColorSet,
         }
     }
+
+    fn to_layouts(attributes: &[Self]) -> AttributeLayouts {
+        AttributeLayouts::V10(attributes.to_vec())
+    }
+
+    fn from_layouts(layouts: &AttributeLayouts) -> Option<&[Self]> {
+        match layouts {
+            AttributeLayouts::V10(attributes) => Some(attributes),
+            _ => None,
+        }
+    }
 }
 
 trait Weight: BinRead + SsbhWrite
@@ -438,6 +572,7 @@ fn read_attributes<A: Attribute, W: Weight>(
         attributes.push(AttributeData {
             name: attribute.name.to_string(),
             data,
+            data_type: Some(attribute.data_type),
         })
     }
     Ok(attributes)
@@ -478,6 +613,8 @@ pub struct BoneInfluence {
 
 /// The data associated with a [Mesh] file.
 /// Supported versions are 1.8, 1.9, and 1.10.
+///
+/// Use [MeshData::new] instead of a struct literal to use the defaults for any fields added in later releases.
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[derive(Debug, Clone)]
@@ -485,6 +622,34 @@ pub struct MeshData {
     pub major_version: u16,
     pub minor_version: u16,
     pub objects: Vec<MeshObjectData>,
+    /// The layout of the original file used to preserve unmodified objects when saving
+    /// or [None] to rebuild all objects.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub layout_hints: Option<MeshLayoutHints>,
+    /// The algorithm for calculating bounding information for modified objects when saving.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub bounding_method: BoundingMethod,
+}
+
+impl MeshData {
+    /// Creates a new [MeshData] with no [layout_hints](#structfield.layout_hints)
+    /// and the default [bounding_method](#structfield.bounding_method).
+    /**
+    ```rust
+    # use ssbh_data::mesh_data::MeshData;
+    let mesh = MeshData::new(1, 10, Vec::new());
+    assert!(mesh.layout_hints.is_none());
+    ```
+     */
+    pub fn new(major_version: u16, minor_version: u16, objects: Vec<MeshObjectData>) -> Self {
+        Self {
+            major_version,
+            minor_version,
+            objects,
+            layout_hints: None,
+            bounding_method: BoundingMethod::default(),
+        }
+    }
 }
 
 impl TryFrom<MeshData> for Mesh {
@@ -516,10 +681,14 @@ impl TryFrom<&Mesh> for MeshData {
 
     fn try_from(mesh: &Mesh) -> Result<Self, Self::Error> {
         let (major_version, minor_version) = mesh.major_minor_version();
+        let objects = read_mesh_objects(mesh)?;
+        let layout_hints = read_layout_hints(mesh, &objects);
         Ok(Self {
             major_version,
             minor_version,
-            objects: read_mesh_objects(mesh)?,
+            objects,
+            layout_hints: Some(layout_hints),
+            bounding_method: BoundingMethod::default(),
         })
     }
 }
@@ -539,14 +708,11 @@ let object = MeshObjectData {
     name: "triangle".to_string(),
     vertex_indices: vec![0, 1, 2],
     positions: vec![
-        AttributeData {
-            name: "Position0".into(),
-            data: VectorData::Vector3(vec![
+        AttributeData::new("Position0", VectorData::Vector3(vec![
                 [-1.0, 1.0, 0.0],
                 [-1.0, -1.0, 0.0],
                 [1.0, -1.0, 0.0]
-            ])
-        }
+            ]))
     ],
     ..MeshObjectData::default()
 };
@@ -581,12 +747,38 @@ pub struct MeshObjectData {
 }
 
 /// Data corresponding to a named vertex attribute such as `"Position0"` or `"colorSet1"`.
+///
+/// Use [AttributeData::new] instead of a struct literal to use the defaults for any fields added in later releases.
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[derive(Debug, Clone)]
 pub struct AttributeData {
     pub name: String,
     pub data: VectorData,
+    /// The data type used when saving or [None] to infer the data type from the data and attribute usage.
+    /// Reading sets the original data type, so saving unmodified attributes preserves the buffer layout.
+    /// Data types that aren't supported by the mesh version or don't match the component count of
+    /// [data](#structfield.data) use the inferred data type instead.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub data_type: Option<AttributeDataType>,
+}
+
+impl AttributeData {
+    /// Creates a new [AttributeData] with an inferred [data_type](#structfield.data_type).
+    /**
+    ```rust
+    # use ssbh_data::mesh_data::{AttributeData, VectorData};
+    let attribute = AttributeData::new("map1", VectorData::Vector2(vec![[0.0, 1.0]]));
+    assert_eq!(None, attribute.data_type);
+    ```
+     */
+    pub fn new<S: Into<String>>(name: S, data: VectorData) -> Self {
+        Self {
+            name: name.into(),
+            data,
+            data_type: None,
+        }
+    }
 }
 
 impl MeshObjectData {
@@ -619,6 +811,28 @@ impl MeshObjectData {
             Err(error::Error::AttributeDataLengthMismatch)
         }
     }
+
+    /// Iterates over the attributes for all usages.
+    pub(crate) fn attributes(&self) -> impl Iterator<Item = &AttributeData> {
+        self.positions
+            .iter()
+            .chain(self.normals.iter())
+            .chain(self.binormals.iter())
+            .chain(self.tangents.iter())
+            .chain(self.texture_coordinates.iter())
+            .chain(self.color_sets.iter())
+    }
+
+    /// Iterates mutably over the attributes for all usages.
+    pub(crate) fn attributes_mut(&mut self) -> impl Iterator<Item = &mut AttributeData> {
+        self.positions
+            .iter_mut()
+            .chain(self.normals.iter_mut())
+            .chain(self.binormals.iter_mut())
+            .chain(self.tangents.iter_mut())
+            .chain(self.texture_coordinates.iter_mut())
+            .chain(self.color_sets.iter_mut())
+    }
 }
 
 fn read_mesh_objects(mesh: &Mesh) -> Result<Vec<MeshObjectData>, Box<dyn Error>> {
@@ -629,48 +843,61 @@ fn read_mesh_objects(mesh: &Mesh) -> Result<Vec<MeshObjectData>, Box<dyn Error>>
     }
 }
 
+fn read_layout_hints(mesh: &Mesh, objects: &[MeshObjectData]) -> MeshLayoutHints {
+    match mesh {
+        Mesh::V8(mesh) => MeshLayoutHints::from_mesh_inner(mesh, objects),
+        Mesh::V9(mesh) => MeshLayoutHints::from_mesh_inner(mesh, objects),
+        Mesh::V10(mesh) => MeshLayoutHints::from_mesh_inner(mesh, objects),
+    }
+}
+
 fn read_mesh_objects_inner<A: Attribute, W: Weight>(
     mesh: &MeshInner<A, W>,
 ) -> Result<Vec<MeshObjectData>, Box<dyn Error>> {
-    let mut mesh_objects = Vec::new();
-    for mesh_object in &mesh.objects.elements {
-        let name = mesh_object.name.to_string_lossy();
-
-        let indices = read_vertex_indices(&mesh.index_buffer.elements, mesh_object)?;
-        let positions = read_attributes(mesh, mesh_object, AttributeUsage::Position)?;
-        let normals = read_attributes(mesh, mesh_object, AttributeUsage::Normal)?;
-        let tangents = read_attributes(mesh, mesh_object, AttributeUsage::Tangent)?;
-        let binormals = read_attributes(mesh, mesh_object, AttributeUsage::Binormal)?;
-        let texture_coordinates =
-            read_attributes(mesh, mesh_object, AttributeUsage::TextureCoordinate)?;
-        let color_sets = read_attributes(mesh, mesh_object, AttributeUsage::ColorSet)?;
-        let bone_influences =
-            read_rigging_data(&mesh.rigging_buffers.elements, &name, mesh_object.subindex)?;
-
-        let data = MeshObjectData {
-            name,
-            subindex: mesh_object.subindex,
-            parent_bone_name: mesh_object
-                .parent_bone_name
-                .to_str()
-                .unwrap_or("")
-                .to_string(),
-            vertex_indices: indices,
-            positions,
-            normals,
-            tangents,
-            binormals,
-            texture_coordinates,
-            color_sets,
-            bone_influences,
-            sort_bias: mesh_object.sort_bias,
-            disable_depth_test: mesh_object.depth_flags.disable_depth_test != 0,
-            disable_depth_write: mesh_object.depth_flags.disable_depth_write != 0,
-        };
+    mesh.objects
+        .elements
+        .iter()
+        .map(|mesh_object| read_mesh_object(mesh, mesh_object))
+        .collect()
+}
 
-        mesh_objects.push(data);
-    }
-    Ok(mesh_objects)
+fn read_mesh_object<A: Attribute, W: Weight>(
+    mesh: &MeshInner<A, W>,
+    mesh_object: &MeshObject<A>,
+) -> Result<MeshObjectData, Box<dyn Error>> {
+    let name = mesh_object.name.to_string_lossy();
+
+    let indices = read_vertex_indices(&mesh.index_buffer.elements, mesh_object)?;
+    let positions = read_attributes(mesh, mesh_object, AttributeUsage::Position)?;
+    let normals = read_attributes(mesh, mesh_object, AttributeUsage::Normal)?;
+    let tangents = read_attributes(mesh, mesh_object, AttributeUsage::Tangent)?;
+    let binormals = read_attributes(mesh, mesh_object, AttributeUsage::Binormal)?;
+    let texture_coordinates =
+        read_attributes(mesh, mesh_object, AttributeUsage::TextureCoordinate)?;
+    let color_sets = read_attributes(mesh, mesh_object, AttributeUsage::ColorSet)?;
+    let bone_influences =
+        read_rigging_data(&mesh.rigging_buffers.elements, &name, mesh_object.subindex)?;
+
+    Ok(MeshObjectData {
+        name,
+        subindex: mesh_object.subindex,
+        parent_bone_name: mesh_object
+            .parent_bone_name
+            .to_str()
+            .unwrap_or("")
+            .to_string(),
+        vertex_indices: indices,
+        positions,
+        normals,
+        tangents,
+        binormals,
+        texture_coordinates,
+        color_sets,
+        bone_influences,
+        sort_bias: mesh_object.sort_bias,
+        disable_depth_test: mesh_object.depth_flags.disable_depth_test != 0,
+        disable_depth_write: mesh_object.depth_flags.disable_depth_write != 0,
+    })
 }
 
 fn create_mesh(data: &MeshData) -> Result<Mesh, error::Error> {
@@ -686,20 +913,41 @@ fn create_mesh(data: &MeshData) -> Result<Mesh, error::Error> {
         })
         .collect();
 
+    // Hash the objects once to find the unmodified objects.
+    let unmodified = UnmodifiedObjects::new(data.layout_hints.as_ref(), &data.objects);
+
     match (data.major_version, data.minor_version) {
         (1, 10) => Ok(Mesh::V10(create_mesh_inner(
             &all_positions,
-            create_mesh_objects(&data.objects, create_attributes_v10)?,
+            create_mesh_objects(
+                &data.objects,
+                &unmodified,
+                data.bounding_method,
+                create_attributes_v10,
+            )?,
+            &unmodified,
             data,
         )?)),
         (1, 8) => Ok(Mesh::V8(create_mesh_inner(
             &all_positions,
-            create_mesh_objects(&data.objects, create_attributes_v8)?,
+            create_mesh_objects(
+                &data.objects,
+                &unmodified,
+                data.bounding_method,
+                create_attributes_v8,
+            )?,
+            &unmodified,
             data,
         )?)),
         (1, 9) => Ok(Mesh::V9(create_mesh_inner(
             &all_positions,
-            create_mesh_objects(&data.objects, create_attributes_v9)?,
+            create_mesh_objects(
+                &data.objects,
+                &unmodified,
+                data.bounding_method,
+                create_attributes_v9,
+            )?,
+            &unmodified,
             data,
         )?)),
         _ => Err(error::Error::UnsupportedVersion {
@@ -712,12 +960,20 @@ fn create_mesh(data: &MeshData) -> Result<Mesh, error::Error> {
 fn create_mesh_inner<A: Attribute, W: Weight>(
     all_positions: &[glam::Vec3A],
     mesh_vertex_data: MeshVertexData<A>,
+    unmodified: &UnmodifiedObjects,
     data: &MeshData,
 ) -> Result<MeshInner<A, W>, error::Error> {
+    // Mesh bounding info can only be preserved if the objects are unchanged.
+    let hints = data.layout_hints.as_ref();
+    let bounding_info = match hints.filter(|_| unmodified.all_unmodified()) {
+        Some(hints) => hints.bounding_info,
+        None => bounding::calculate_bounding_info(all_positions, data.bounding_method),
+    };
+
     Ok(MeshInner {
-        model_name: "".into(),
-        bounding_info: calculate_bounding_info(all_positions),
-        unk1: 0,
+        model_name: hints.map(|h| h.model_name.as_str()).unwrap_or("").into(),
+        bounding_info,
+        unk1: hints.map(|h| h.unk1).unwrap_or(0),
         objects: mesh_vertex_data.mesh_objects.into(),
         // There are always at least 4 buffer entries even if only 2 are used.
         buffer_sizes: mesh_vertex_data
@@ -734,7 +990,7 @@ fn create_mesh_inner<A: Attribute, W: Weight>(
             .map(SsbhByteBuffer::from_vec)
             .collect(),
         index_buffer: mesh_vertex_data.index_buffer.into(),
-        rigging_buffers: create_rigging_buffers(&data.objects)?.into(),
+        rigging_buffers: create_rigging_buffers(&data.objects, unmodified)?.into(),
     })
 }
 
@@ -776,17 +1032,25 @@ fn calculate_max_influences(influences: &[BoneInfluence], vertex_index_count: us
 
 fn create_rigging_buffers<W: Weight>(
     object_data: &[MeshObjectData],
+    unmodified: &UnmodifiedObjects,
 ) -> Result<Vec<RiggingGroup<W>>, error::Error> {
     let mut rigging_buffers = Vec::new();
 
-    for mesh_object in object_data {
-        // TODO: unk1 is sometimes set to 0 for singlebound mesh objects, which isn't currently preserved.
-        let flags = RiggingFlags {
-            max_influences: calculate_max_influences(
-                &mesh_object.bone_influences,
-                mesh_object.vertex_indices.len(),
-            ) as u8,
-            unk1: 1,
+    for (i, mesh_object) in object_data.iter().enumerate() {
+        let flags = match unmodified.get(i) {
+            // Unmodified objects may not have had a rigging group.
+            Some(hints) => match hints.rigging_flags {
+                Some(flags) => flags,
+                None => continue,
+            },
+            // TODO: unk1 is sometimes set to 0 for singlebound mesh objects.
+            None => RiggingFlags {
+                max_influences: calculate_max_influences(
+                    &mesh_object.bone_influences,
+                    mesh_object.vertex_indices.len(),
+                ) as u8,
+                unk1: 1,
+            },
         };
 
         let mut buffers = Vec::new();
@@ -895,6 +1159,8 @@ enum VertexIndices {
 
 fn create_mesh_objects<A: Attribute, F: Fn(&MeshObjectData) -> MeshAttributes<A> + Copy>(
     mesh_object_data: &[MeshObjectData],
+    unmodified: &UnmodifiedObjects,
+    bounding_method: BoundingMethod,
     create_attributes: F,
 ) -> Result<MeshVertexData<A>, error::Error> {
     let mut mesh_objects = Vec::new();
@@ -911,15 +1177,25 @@ fn create_mesh_objects<A: Attribute, F: Fn(&MeshObjectData) -> MeshAttributes<A>
     // Don't just use the buffer position since different mesh versions handle this differently.
     let mut vertex_buffer2_offset = 0u64;
 
-    for data in mesh_object_data {
-        let mesh_object = create_mesh_object(
+    let unmodified_prefix_len = unmodified.prefix_len();
+
+    for (i, data) in mesh_object_data.iter().enumerate() {
+        let buffer2_offset = vertex_buffer2_offset;
+        let mut mesh_object = create_mesh_object(
             data,
             &mut [&mut buffer0, &mut buffer1, &mut buffer2, &mut buffer3],
             &mut vertex_buffer2_offset,
             &mut index_buffer,
+            unmodified.get(i),
+            bounding_method,
             create_attributes,
         )?;
 
+        // Objects after a rebuilt or moved object can't reuse their original buffer2 offset.
+        if i >= unmodified_prefix_len {
+            mesh_object.vertex_buffer2_offset = buffer2_offset as u32;
+        }
+
         mesh_objects.push(mesh_object);
     }
 
@@ -940,6 +1216,8 @@ fn create_mesh_object<A: Attribute, F: Fn(&MeshObjectData) -> MeshAttributes<A>>
     buffers: &mut [&mut Cursor<Vec<u8>>; 4],
     vertex_buffer2_offset: &mut u64,
     index_buffer: &mut Cursor<Vec<u8>>,
+    layout_hints: Option<&MeshObjectLayoutHints>,
+    bounding_method: BoundingMethod,
     create_attributes: F,
 ) -> Result<MeshObject<A>, error::Error> {
     if data.vertex_indices.len() % 3 != 0 {
@@ -961,7 +1239,13 @@ fn create_mesh_object<A: Attribute, F: Fn(&MeshObjectData) -> MeshAttributes<A>>
         }
     }
 
-    let vertex_indices = convert_indices(&data.vertex_indices);
+    let vertex_indices = match layout_hints.map(|h| h.draw_element_type) {
+        // Preserve indices that could have used a smaller type.
+        Some(DrawElementType::UnsignedInt) => {
+            VertexIndices::UnsignedInt(data.vertex_indices.clone())
+        }
+        _ => convert_indices(&data.vertex_indices),
+    };
 
     let draw_element_type = match vertex_indices {
         VertexIndices::UnsignedInt(_) => DrawElementType::UnsignedInt,
@@ -972,35 +1256,73 @@ fn create_mesh_object<A: Attribute, F: Fn(&MeshObjectData) -> MeshAttributes<A>>
     let vertex_buffer1_offset = buffers[1].position();
     let vertex_buffer3_offset = buffers[3].position();
 
-    // TODO: This is pretty convoluted.
-    let MeshAttributes {
-        buffer_info,
-        attributes,
-        use_buffer2,
-    } = create_attributes(data);
-
-    let stride0 = buffer_info[0].0;
-    let stride1 = buffer_info[1].0;
-    let stride2 = buffer_info[2].0;
-    let stride3 = buffer_info[3].0;
-
-    // TODO: Version 1.10 sets the offset for buffer2 but sets stride to 0 and doesn't write to the buffer.
-    write_attributes(
-        &buffer_info,
-        buffers,
-        &[
-            vertex_buffer0_offset,
-            vertex_buffer1_offset,
-            *vertex_buffer2_offset,
-            vertex_buffer3_offset,
-        ],
-    )?;
+    // Use the original layout if possible to preserve the buffer data for unmodified objects.
+    let (attributes, strides, object_buffer2_offset) = match layout_hints
+        .and_then(|h| Some((h, A::from_layouts(&h.attributes)?)))
+        .filter(|(_, attributes)| has_layout_data(data, attributes))
+    {
+        Some((hints, attributes)) => {
+            write_attributes_with_layout(
+                data,
+                attributes,
+                &hints.strides,
+                buffers,
+                &[
+                    vertex_buffer0_offset,
+                    vertex_buffer1_offset,
+                    *vertex_buffer2_offset,
+                    vertex_buffer3_offset,
+                ],
+                vertex_count,
+            )?;
 
-    // Just write dummy data to buffer2 to match in game meshes for v1.8 and v.1.9.
-    // Mesh v1.10 calculates offsets for this buffer but zeros stride and writes no data.
-    if use_buffer2 {
-        buffers[2].write_all(&vec![0u8; stride2 as usize * vertex_count])?;
-    }
+            // Mesh v1.10 doesn't use buffer2 but still sets an offset.
+            let buffer2_offset = if hints.strides[2] == 0 {
+                hints.vertex_buffer2_offset as u64
+            } else {
+                *vertex_buffer2_offset
+            };
+            (attributes.to_vec().into(), hints.strides, buffer2_offset)
+        }
+        None => {
+            // TODO: This is pretty convoluted.
+            let MeshAttributes {
+                buffer_info,
+                attributes,
+                use_buffer2,
+            } = create_attributes(data);
+
+            let stride0 = buffer_info[0].0;
+            let stride1 = buffer_info[1].0;
+            let stride2 = buffer_info[2].0;
+            let stride3 = buffer_info[3].0;
+
+            // TODO: Version 1.10 sets the offset for buffer2 but sets stride to 0 and doesn't write to the buffer.
+            write_attributes(
+                &buffer_info,
+                buffers,
+                &[
+                    vertex_buffer0_offset,
+                    vertex_buffer1_offset,
+                    *vertex_buffer2_offset,
+                    vertex_buffer3_offset,
+                ],
+            )?;
+
+            // Just write dummy data to buffer2 to match in game meshes for v1.8 and v.1.9.
+            // Mesh v1.10 calculates offsets for this buffer but zeros stride and writes no data.
+            if use_buffer2 {
+                buffers[2].write_all(&vec![0u8; stride2 as usize * vertex_count])?;
+            }
+
+            let stride2 = if use_buffer2 { stride2 } else { 0 };
+            (
+                attributes,
+                [stride0, stride1, stride2, stride3],
+                *vertex_buffer2_offset,
+            )
+        }
+    };
 
     let positions = match data.positions.first() {
         Some(attribute) => attribute.data.to_glam_vec3a(),
@@ -1016,12 +1338,12 @@ fn create_mesh_object<A: Attribute, F: Fn(&MeshObjectData) -> MeshAttributes<A>>
         unk2: 3, // TODO: Does this mean triangle faces?
         vertex_buffer0_offset: vertex_buffer0_offset as u32,
         vertex_buffer1_offset: vertex_buffer1_offset as u32,
-        vertex_buffer2_offset: *vertex_buffer2_offset as u32,
+        vertex_buffer2_offset: object_buffer2_offset as u32,
         vertex_buffer3_offset: vertex_buffer3_offset as u32,
-        stride0,
-        stride1,
-        stride2: if use_buffer2 { stride2 } else { 0 },
-        stride3,
+        stride0: strides[0],
+        stride1: strides[1],
+        stride2: strides[2],
+        stride3: strides[3],
         index_buffer_offset: index_buffer.position() as u32,
         unk8: 4, // TODO: index stride?
         draw_element_type,
@@ -1035,18 +1357,102 @@ fn create_mesh_object<A: Attribute, F: Fn(&MeshObjectData) -> MeshAttributes<A>>
             disable_depth_write: if data.disable_depth_write { 1 } else { 0 },
             disable_depth_test: if data.disable_depth_test { 1 } else { 0 },
         },
-        bounding_info: calculate_bounding_info(&positions),
+        bounding_info: match layout_hints {
+            Some(hints) => hints.bounding_info,
+            None => bounding::calculate_bounding_info(&positions, bounding_method),
+        },
         attributes,
     };
 
     write_vertex_indices(&vertex_indices, index_buffer)?;
 
     // Assume stride2 is non zero for all versions.
+    let stride2 = if strides[2] != 0 {
+        strides[2]
+    } else {
+        UNUSED_BUFFER2_STRIDE
+    };
     *vertex_buffer2_offset += vertex_count as u64 * stride2 as u64;
 
     Ok(mesh_object)
 }
 
+// The buffer2 stride used for calculating offsets when the buffer is unused.
+const UNUSED_BUFFER2_STRIDE: u32 = 32;
+
+fn layout_attribute_data<'a, A: Attribute>(
+    data: &'a MeshObjectData,
+    attributes: &'a [A],
+) -> impl Iterator<Item = (&'a A, Option<&'a AttributeData>)> {
+    // Attributes are read in order for each usage.
+    let mut usage_counts = AHashMap::new();
+    attributes.iter().map(move |a| {
+        let usage_attributes = match a.usage() {
+            AttributeUsage::Position => &data.positions,
+            AttributeUsage::Normal => &data.normals,
+            AttributeUsage::Binormal => &data.binormals,
+            AttributeUsage::Tangent => &data.tangents,
+            AttributeUsage::TextureCoordinate => &data.texture_coordinates,
+            AttributeUsage::ColorSet => &data.color_sets,
+        };
+        let count = usage_counts.entry(a.usage()).or_insert(0);
+        let attribute_data = usage_attributes.get(*count);
+        *count += 1;
+        (a, attribute_data)
+    })
+}
+
+fn has_layout_data<A: Attribute>(data: &MeshObjectData, attributes: &[A]) -> bool {
+    layout_attribute_data(data, attributes).all(|(a, attribute_data)| {
+        attribute_data
+            .and_then(|d| VectorDataV10::from_data_type(&d.data, a.to_attribute().data_type))
+            .is_some()
+    })
+}
+
+fn write_attributes_with_layout<A: Attribute>(
+    data: &MeshObjectData,
+    attributes: &[A],
+    strides: &[u32; 4],
+    buffers: &mut [&mut Cursor<Vec<u8>>; 4],
+    offsets: &[u64; 4],
+    vertex_count: usize,
+) -> Result<(), error::Error> {
+    // Zero the entire region in case there are gaps between attributes.
+    for ((buffer, offset), stride) in buffers.iter_mut().zip(offsets).zip(strides) {
+        if *stride > 0 {
+            buffer.seek(SeekFrom::Start(*offset))?;
+            buffer.write_all(&vec![0u8; *stride as usize * vertex_count])?;
+        }
+    }
+
+    for (attribute, attribute_data) in layout_attribute_data(data, attributes) {
+        let attribute = attribute.to_attribute();
+        let index = attribute.index as usize;
+        // The data types are shared between versions, so the written bytes are the same.
+        if let (Some(vectors), Some(buffer), Some(offset), Some(stride)) = (
+            attribute_data
+                .and_then(|d| VectorDataV10::from_data_type(&d.data, attribute.data_type)),
+            buffers.get_mut(index),
+            offsets.get(index),
+            strides.get(index),
+        ) {
+            vectors.write(*buffer, offset + attribute.offset, *stride as u64)?;
+        }
+    }
+
+    // Move to the end of the data for the next object.
+    for ((buffer, offset), stride) in buffers.iter_mut().zip(offsets).zip(strides) {
+        if *stride > 0 {
+            buffer.seek(SeekFrom::Start(
+                offset + *stride as u64 * vertex_count as u64,
+            ))?;
+        }
+    }
+
+    Ok(())
+}
+
 fn write_vertex_indices(
     indices: &VertexIndices,
     index_buffer: &mut Cursor<Vec<u8>>,
@@ -1115,10 +1521,7 @@ fn transform_inner(data: &VectorData, transform: &[[f32; 4]; 4], w: f32) -> Vect
 # use ssbh_data::mesh_data::{VectorData, AttributeData, MeshObjectData, transform_points};
 # let mesh_object_data = MeshObjectData {
 #     name: "abc".into(),
-#     positions: vec![AttributeData {
-#         name: "Position0".into(),
-#         data: VectorData::Vector3(Vec::new())
-#     }],
+#     positions: vec![AttributeData::new("Position0", VectorData::Vector3(Vec::new()))],
 #     ..MeshObjectData::default()
 # };
 // A scaling matrix for x, y, and z.
@@ -1145,10 +1548,7 @@ pub fn transform_points(data: &VectorData, transform: &[[f32; 4]; 4]) -> VectorD
 # use ssbh_data::mesh_data::{VectorData, AttributeData, MeshObjectData, transform_vectors};
 # let mesh_object_data = MeshObjectData {
 #     name: "abc".into(),
-#     normals: vec![AttributeData {
-#         name: "Normal0".into(),
-#         data: VectorData::Vector3(Vec::new())
-#     }],
+#     normals: vec![AttributeData::new("Normal0", VectorData::Vector3(Vec::new()))],
 #     ..MeshObjectData::default()
 # };
 // A scaling matrix for x, y, and z.
@@ -1195,32 +1595,6 @@ pub fn calculate_tangents_vec4(
     Ok(tangents.iter().map(|t| t.to_array()).collect())
 }
 
-fn calculate_bounding_info(positions: &[geometry_tools::glam::Vec3A]) -> BoundingInfo {
-    // Calculate bounding info based on the current points.
-    let sphere = geometry_tools::bounding::calculate_bounding_sphere_from_points(positions);
-    let (aabb_min, aabb_max) = geometry_tools::bounding::calculate_aabb_from_points(positions);
-
-    // TODO: Compute a better oriented bounding box.
-    let obb_center = (aabb_min + aabb_max) / 2.0;
-    let obb_size = (aabb_max - aabb_min) / 2.0;
-
-    BoundingInfo {
-        bounding_sphere: BoundingSphere {
-            center: Vector3::new(sphere.x, sphere.y, sphere.z),
-            radius: sphere.w,
-        },
-        bounding_volume: BoundingVolume {
-            min: Vector3::new(aabb_min.x, aabb_min.y, aabb_min.z),
-            max: Vector3::new(aabb_max.x, aabb_max.y, aabb_max.z),
-        },
-        oriented_bounding_box: OrientedBoundingBox {
-            center: Vector3::new(obb_center.x, obb_center.y, obb_center.z),
-            transform: Matrix3x3::identity(),
-            size: Vector3::new(obb_size.x, obb_size.y, obb_size.z),
-        },
-    }
-}
-
 fn read_influences<W: Weight>(
     rigging_group: &RiggingGroup<W>,
 ) -> Result<Vec<BoneInfluence>, Box<dyn Error>> {
@@ -1246,7 +1620,7 @@ struct MeshAttribute {
     pub name: String,
     pub index: u64,
     pub offset: u64,
-    pub data_type: DataType,
+    pub data_type: AttributeDataType,
 }
 
 fn get_attributes<A: Attribute>(
@@ -1287,6 +1661,8 @@ pub fn read_data<R: Read + Seek, TIn: for<'a> BinRead<Args<'a> = ()>, TOut: From
 mod tests {
     use super::*;
     use hexlit::hex;
+    use ssbh_lib::formats::mesh::{BoundingInfo, BoundingSphere};
+    use ssbh_lib::Vector3;
 
     #[test]
     fn read_data_count0() {
@@ -1337,7 +1713,7 @@ mod tests {
 
         let attribute: MeshAttribute = attribute_v10.to_attribute();
         assert_eq!("name1", attribute.name);
-        assert_eq!(DataType::HalfFloat2, attribute.data_type);
+        assert_eq!(AttributeDataType::HalfFloat2, attribute.data_type);
         assert_eq!(2, attribute.index);
         assert_eq!(10, attribute.offset);
     }
@@ -1354,7 +1730,7 @@ mod tests {
 
         let attribute: MeshAttribute = attribute_v8.to_attribute();
         assert_eq!("Normal3", attribute.name);
-        assert_eq!(DataType::Float2, attribute.data_type);
+        assert_eq!(AttributeDataType::Float2, attribute.data_type);
         assert_eq!(1, attribute.index);
         assert_eq!(8, attribute.offset);
     }
@@ -1548,12 +1924,7 @@ mod tests {
 
     #[test]
     fn create_empty_mesh_1_10() {
-        let mesh = create_mesh(&MeshData {
-            major_version: 1,
-            minor_version: 10,
-            objects: Vec::new(),
-        })
-        .unwrap();
+        let mesh = create_mesh(&MeshData::new(1, 10, Vec::new())).unwrap();
         assert!(matches!(mesh,
             Mesh::V10(MeshInner { objects, rigging_buffers, index_buffer, .. })
             if objects.elements.is_empty() && rigging_buffers.elements.is_empty() && index_buffer.elements.is_empty()
@@ -1562,12 +1933,7 @@ mod tests {
 
     #[test]
     fn create_empty_mesh_1_8() {
-        let mesh = create_mesh(&MeshData {
-            major_version: 1,
-            minor_version: 8,
-            objects: Vec::new(),
-        })
-        .unwrap();
+        let mesh = create_mesh(&MeshData::new(1, 8, Vec::new())).unwrap();
 
         assert!(matches!(mesh,
             Mesh::V8(MeshInner { objects, rigging_buffers, index_buffer, .. })
@@ -1577,12 +1943,7 @@ mod tests {
 
     #[test]
     fn create_empty_mesh_v_1_9() {
-        let mesh = create_mesh(&MeshData {
-            major_version: 1,
-            minor_version: 9,
-            objects: Vec::new(),
-        })
-        .unwrap();
+        let mesh = create_mesh(&MeshData::new(1, 9, Vec::new())).unwrap();
 
         assert!(matches!(mesh,
             Mesh::V9(MeshInner { objects, rigging_buffers, index_buffer, .. })
@@ -1592,11 +1953,7 @@ mod tests {
 
     #[test]
     fn create_empty_mesh_invalid_version() {
-        let result = create_mesh(&MeshData {
-            major_version: 2,
-            minor_version: 301,
-            objects: Vec::new(),
-        });
+        let result = create_mesh(&MeshData::new(2, 301, Vec::new()));
 
         assert!(matches!(
             result,
@@ -1609,17 +1966,17 @@ mod tests {
 
     #[test]
     fn create_mesh_1_10() {
-        let mesh = create_mesh(&MeshData {
-            major_version: 1,
-            minor_version: 10,
-            objects: vec![
+        let mesh = create_mesh(&MeshData::new(
+            1,
+            10,
+            vec![
                 MeshObjectData {
                     name: "a".to_owned(),
                     subindex: 0,
-                    positions: vec![AttributeData {
-                        name: String::new(),
-                        data: VectorData::Vector3(vec![[0.0; 3]; 12]),
-                    }],
+                    positions: vec![AttributeData::new(
+                        String::new(),
+                        VectorData::Vector3(vec![[0.0; 3]; 12]),
+                    )],
                     bone_influences: vec![BoneInfluence {
                         bone_name: "a".to_owned(),
                         vertex_weights: vec![VertexWeight {
@@ -1632,10 +1989,10 @@ mod tests {
                 MeshObjectData {
                     name: "a".to_owned(),
                     subindex: 1,
-                    positions: vec![AttributeData {
-                        name: String::new(),
-                        data: VectorData::Vector3(vec![[0.0; 3]; 12]),
-                    }],
+                    positions: vec![AttributeData::new(
+                        String::new(),
+                        VectorData::Vector3(vec![[0.0; 3]; 12]),
+                    )],
                     bone_influences: vec![BoneInfluence {
                         bone_name: "b".to_owned(),
                         vertex_weights: vec![VertexWeight {
@@ -1646,7 +2003,7 @@ mod tests {
                     ..Default::default()
                 },
             ],
-        })
+        ))
         .unwrap();
 
         // Different mesh versions have different conventions for unused vertex buffers.
@@ -1679,16 +2036,310 @@ mod tests {
         ));
     }
 
+    fn mesh_with_custom_layout() -> Mesh {
+        // Use a layout that differs from the layout used when rebuilding objects.
+        let positions = [[1.0f32, 2.0, 3.0], [4.0, 5.0, 6.0], [7.0, 8.0, 9.0]];
+        let buffer0: Vec<u8> = positions
+            .iter()
+            .flat_map(|p| {
+                [0u8; 4]
+                    .into_iter()
+                    .chain(p.iter().flat_map(|f| f.to_le_bytes()))
+            })
+            .collect();
+        let buffer1: Vec<u8> = [[0.0f32, 1.0], [0.5, 0.25], [1.0, 0.0]]
+            .iter()
+            .flatten()
+            .flat_map(|f| f16::from_f32(*f).to_le_bytes())
+            .collect();
+        let index_buffer: Vec<u8> = [0u32, 1, 2].iter().flat_map(|i| i.to_le_bytes()).collect();
+
+        let bounding_info = BoundingInfo {
+            bounding_sphere: BoundingSphere {
+                center: Vector3::new(1.0, 2.0, 3.0),
+                radius: 4.0,
+            },
+            ..Default::default()
+        };
+
+        Mesh::V10(MeshInner {
+            model_name: "model".into(),
+            bounding_info,
+            unk1: 0,
+            objects: vec![MeshObject {
+                name: "a".into(),
+                subindex: 0,
+                parent_bone_name: "".into(),
+                vertex_count: 3,
+                vertex_index_count: 3,
+                unk2: 3,
+                vertex_buffer0_offset: 0,
+                vertex_buffer1_offset: 0,
+                vertex_buffer2_offset: 7,
+                vertex_buffer3_offset: 0,
+                stride0: 16,
+                stride1: 4,
+                stride2: 0,
+                stride3: 0,
+                index_buffer_offset: 0,
+                unk8: 4,
+                draw_element_type: DrawElementType::UnsignedInt,
+                use_vertex_skinning: 0,
+                sort_bias: 0,
+                depth_flags: DepthFlags {
+                    disable_depth_write: 0,
+                    disable_depth_test: 0,
+                },
+                bounding_info,
+                attributes: vec![
+                    AttributeV10 {
+                        usage: AttributeUsageV9::TextureCoordinate,
+                        data_type: AttributeDataTypeV10::HalfFloat2,
+                        buffer_index: 1,
+                        buffer_offset: 0,
+                        subindex: 0,
+                        name: "map1".into(),
+                        attribute_names: vec!["map1".into()].into(),
+                    },
+                    AttributeV10 {
+                        usage: AttributeUsageV9::Position,
+                        data_type: AttributeDataTypeV10::Float3,
+                        buffer_index: 0,
+                        buffer_offset: 4,
+                        subindex: 0,
+                        name: "Position0".into(),
+                        attribute_names: vec!["Position0".into()].into(),
+                    },
+                ]
+                .into(),
+            }]
+            .into(),
+            buffer_sizes: vec![48, 12, 0, 0].into(),
+            polygon_index_size: 12,
+            vertex_buffers: vec![
+                buffer0.into(),
+                buffer1.into(),
+                SsbhByteBuffer::new(),
+                SsbhByteBuffer::new(),
+            ]
+            .into(),
+            index_buffer: index_buffer.into(),
+            rigging_buffers: vec![RiggingGroup {
+                mesh_object_name: "a".into(),
+                mesh_object_subindex: 0,
+                flags: RiggingFlags {
+                    max_influences: 0,
+                    unk1: 0,
+                },
+                buffers: Vec::new().into(),
+            }]
+            .into(),
+        })
+    }
+
+    #[test]
+    fn read_create_mesh_unmodified_layout_hints() {
+        let mesh = mesh_with_custom_layout();
+        let data = MeshData::try_from(&mesh).unwrap();
+        assert_eq!(mesh, create_mesh(&data).unwrap());
+    }
+
+    #[test]
+    fn read_create_mesh_modified_layout_hints() {
+        let mesh = mesh_with_custom_layout();
+        let mut data = MeshData::try_from(&mesh).unwrap();
+        data.objects[0].positions[0].data = VectorData::Vector3(vec![[0.0; 3]; 3]);
+
+        // Modified objects should be rebuilt.
+        let new_mesh = create_mesh(&data).unwrap();
+        assert!(matches!(new_mesh,
+            Mesh::V10(MeshInner { model_name, objects, rigging_buffers, bounding_info, .. })
+            if model_name.to_str() == Some("model")
+            && bounding_info.bounding_sphere.radius == 0.0
+            && objects.elements[0].attributes.elements[0].usage == AttributeUsageV9::Position
+            && objects.elements[0].draw_element_type == DrawElementType::UnsignedShort
+            && objects.elements[0].stride0 == 12
+            && objects.elements[0].bounding_info.bounding_sphere.radius == 0.0
+            && rigging_buffers.elements[0].flags.unk1 == 1
+        ));
+    }
+
     #[test]
-    fn create_mesh_1_10_too_many_vertices() {
-        let mesh = create_mesh(&MeshData {
-            major_version: 1,
-            minor_version: 10,
-            objects: vec![MeshObjectData {
-                positions: vec![AttributeData {
-                    name: String::new(),
-                    data: VectorData::Vector3(vec![[0.0; 3]; 3]),
+    fn read_create_mesh_rebuilt_object_before_unmodified_object() {
+        let mesh = mesh_with_custom_layout();
+        let mut data = MeshData::try_from(&mesh).unwrap();
+        data.objects.insert(
+            0,
+            MeshObjectData {
+                name: "b".into(),
+                positions: vec![AttributeData::new(
+                    "Position0",
+                    VectorData::Vector3(vec![[0.0; 3]; 2]),
+                )],
+                ..Default::default()
+            },
+        );
+
+        // The unmodified object can't reuse its original buffer2 offset.
+        let new_mesh = create_mesh(&data).unwrap();
+        assert!(matches!(new_mesh,
+            Mesh::V10(MeshInner { objects, .. })
+            if objects.elements[0].vertex_buffer2_offset == 0
+            && objects.elements[1].vertex_buffer2_offset == 32 * 2
+            && objects.elements[1].stride0 == 16
+        ));
+    }
+
+    fn round_trip_mesh_data(minor_version: u16) -> MeshData {
+        let attribute = |name: &str, data| AttributeData::new(name, data);
+        let object = |name: &str, subindex| MeshObjectData {
+            name: name.into(),
+            subindex,
+            parent_bone_name: "Hip".into(),
+            vertex_indices: vec![0, 1, 2],
+            positions: vec![attribute(
+                "Position0",
+                VectorData::Vector3(vec![[0.0, 1.0, 2.0], [3.0, 4.0, 5.0], [6.0, 7.0, 8.0]]),
+            )],
+            normals: vec![attribute("Normal0", VectorData::Vector4(vec![[0.5; 4]; 3]))],
+            tangents: vec![attribute(
+                "Tangent0",
+                VectorData::Vector4(vec![[1.0; 4]; 3]),
+            )],
+            texture_coordinates: vec![attribute("map1", VectorData::Vector2(vec![[0.25; 2]; 3]))],
+            color_sets: vec![attribute(
+                "colorSet1",
+                VectorData::Vector4(vec![[0.5; 4]; 3]),
+            )],
+            bone_influences: vec![BoneInfluence {
+                bone_name: "Hip".into(),
+                vertex_weights: vec![VertexWeight {
+                    vertex_index: 1,
+                    vertex_weight: 1.0,
                 }],
+            }],
+            ..Default::default()
+        };
+
+        MeshData::new(
+            1,
+            minor_version,
+            vec![object("a", 0), object("a", 1), object("b", 0)],
+        )
+    }
+
+    #[test]
+    fn read_create_mesh_1_8_unmodified_layout_hints() {
+        let mesh = create_mesh(&round_trip_mesh_data(8)).unwrap();
+        assert!(matches!(mesh, Mesh::V8(_)));
+
+        let data = MeshData::try_from(&mesh).unwrap();
+        assert_eq!(mesh, create_mesh(&data).unwrap());
+    }
+
+    #[test]
+    fn read_create_mesh_1_9_unmodified_layout_hints() {
+        let mesh = create_mesh(&round_trip_mesh_data(9)).unwrap();
+        assert!(matches!(mesh, Mesh::V9(_)));
+
+        let data = MeshData::try_from(&mesh).unwrap();
+        assert_eq!(mesh, create_mesh(&data).unwrap());
+    }
+
+    #[test]
+    fn create_mesh_no_layout_hints() {
+        let mesh = mesh_with_custom_layout();
+        let mut data = MeshData::try_from(&mesh).unwrap();
+        data.layout_hints = None;
+
+        let new_mesh = create_mesh(&data).unwrap();
+        assert!(matches!(new_mesh,
+            Mesh::V10(MeshInner { model_name, objects, .. })
+            if model_name.to_str() == Some("")
+            && objects.elements[0].draw_element_type == DrawElementType::UnsignedShort
+        ));
+    }
+
+    #[test]
+    fn create_read_mesh_1_10_preserve_data_types() {
+        let data = MeshData::new(
+            1,
+            10,
+            vec![MeshObjectData {
+                name: "a".to_owned(),
+                texture_coordinates: vec![
+                    AttributeData {
+                        name: "map1".to_owned(),
+                        data: VectorData::Vector2(vec![[0.1, 0.2]]),
+                        data_type: Some(AttributeDataType::Float2),
+                    },
+                    AttributeData::new("bake1", VectorData::Vector2(vec![[0.5, 0.25]])),
+                ],
+                ..Default::default()
+            }],
+        );
+
+        let mesh = create_mesh(&data).unwrap();
+        let objects = read_mesh_objects(&mesh).unwrap();
+
+        // Float2 should not lose precision by converting to half precision.
+        let attributes = &objects[0].texture_coordinates;
+        assert_eq!(VectorData::Vector2(vec![[0.1, 0.2]]), attributes[0].data);
+        assert_eq!(Some(AttributeDataType::Float2), attributes[0].data_type);
+        assert_eq!(Some(AttributeDataType::HalfFloat2), attributes[1].data_type);
+    }
+
+    #[test]
+    fn create_read_mesh_changed_version_and_component_count() {
+        let data = MeshData::new(
+            1,
+            10,
+            vec![MeshObjectData {
+                name: "a".to_owned(),
+                texture_coordinates: vec![AttributeData::new(
+                    "map1",
+                    VectorData::Vector2(vec![[0.5, 0.25]]),
+                )],
+                ..Default::default()
+            }],
+        );
+        let mut data = MeshData {
+            objects: read_mesh_objects(&create_mesh(&data).unwrap()).unwrap(),
+            ..data
+        };
+        assert_eq!(
+            Some(AttributeDataType::HalfFloat2),
+            data.objects[0].texture_coordinates[0].data_type
+        );
+
+        // HalfFloat2 is not supported by version 1.8.
+        data.minor_version = 8;
+        let objects = read_mesh_objects(&create_mesh(&data).unwrap()).unwrap();
+        assert_eq!(
+            Some(AttributeDataType::Float2),
+            objects[0].texture_coordinates[0].data_type
+        );
+
+        // HalfFloat2 does not match the component count.
+        data.minor_version = 10;
+        data.objects[0].texture_coordinates[0].data = VectorData::Vector4(vec![[0.5; 4]]);
+        let objects = read_mesh_objects(&create_mesh(&data).unwrap()).unwrap();
+        assert_eq!(
+            Some(AttributeDataType::HalfFloat4),
+            objects[0].texture_coordinates[0].data_type
+        );
+    }
+
+    #[test]
+    fn create_mesh_1_10_too_many_vertices() {
+        let mesh = create_mesh(&MeshData::new(
+            1,
+            10,
+            vec![MeshObjectData {
+                positions: vec![AttributeData::new(
+                    String::new(),
+                    VectorData::Vector3(vec![[0.0; 3]; 3]),
+                )],
                 bone_influences: vec![BoneInfluence {
                     bone_name: "a".to_owned(),
                     vertex_weights: vec![VertexWeight {
@@ -1698,7 +2349,7 @@ mod tests {
                 }],
                 ..Default::default()
             }],
-        });
+        ));
 
         // TODO: Test version 1.8 and 1.9?
         assert!(matches!(
@@ -1714,10 +2365,10 @@ mod tests {
 
     #[test]
     fn create_mesh_1_10_duplicate_subindices() {
-        let mesh = create_mesh(&MeshData {
-            major_version: 1,
-            minor_version: 10,
-            objects: vec![
+        let mesh = create_mesh(&MeshData::new(
+            1,
+            10,
+            vec![
                 MeshObjectData {
                     name: "a".to_owned(),
                     subindex: 1,
@@ -1734,7 +2385,7 @@ mod tests {
                     ..Default::default()
                 },
             ],
-        });
+        ));
 
         // TODO: Test version 1.8 and 1.9?
         assert!(matches!(
@@ -1746,17 +2397,17 @@ mod tests {
 
     #[test]
     fn create_mesh_1_8() {
-        let mesh = create_mesh(&MeshData {
-            major_version: 1,
-            minor_version: 8,
-            objects: vec![
+        let mesh = create_mesh(&MeshData::new(
+            1,
+            8,
+            vec![
                 MeshObjectData {
                     name: "a".to_owned(),
                     subindex: 0,
-                    positions: vec![AttributeData {
-                        name: String::new(),
-                        data: VectorData::Vector3(vec![[0.0; 3]; 12]),
-                    }],
+                    positions: vec![AttributeData::new(
+                        String::new(),
+                        VectorData::Vector3(vec![[0.0; 3]; 12]),
+                    )],
                     bone_influences: vec![BoneInfluence {
                         bone_name: "a".to_owned(),
                         vertex_weights: vec![VertexWeight {
@@ -1769,10 +2420,10 @@ mod tests {
                 MeshObjectData {
                     name: "b".to_owned(),
                     subindex: 0,
-                    positions: vec![AttributeData {
-                        name: String::new(),
-                        data: VectorData::Vector3(vec![[0.0; 3]; 12]),
-                    }],
+                    positions: vec![AttributeData::new(
+                        String::new(),
+                        VectorData::Vector3(vec![[0.0; 3]; 12]),
+                    )],
                     bone_influences: vec![BoneInfluence {
                         bone_name: "b".to_owned(),
                         vertex_weights: vec![VertexWeight {
@@ -1783,7 +2434,7 @@ mod tests {
                     ..Default::default()
                 },
             ],
-        })
+        ))
         .unwrap();
 
         // Different mesh versions have different conventions for unused vertex buffers.
@@ -1817,17 +2468,17 @@ mod tests {
 
     #[test]
     fn create_mesh_v_1_9() {
-        let mesh = create_mesh(&MeshData {
-            major_version: 1,
-            minor_version: 9,
-            objects: vec![
+        let mesh = create_mesh(&MeshData::new(
+            1,
+            9,
+            vec![
                 MeshObjectData {
                     name: "a".to_owned(),
                     subindex: 0,
-                    positions: vec![AttributeData {
-                        name: String::new(),
-                        data: VectorData::Vector3(vec![[0.0; 3]; 12]),
-                    }],
+                    positions: vec![AttributeData::new(
+                        String::new(),
+                        VectorData::Vector3(vec![[0.0; 3]; 12]),
+                    )],
                     bone_influences: vec![BoneInfluence {
                         bone_name: "a".to_owned(),
                         vertex_weights: vec![VertexWeight {
@@ -1840,10 +2491,10 @@ mod tests {
                 MeshObjectData {
                     name: "a".to_owned(),
                     subindex: 1,
-                    positions: vec![AttributeData {
-                        name: String::new(),
-                        data: VectorData::Vector3(vec![[0.0; 3]; 12]),
-                    }],
+                    positions: vec![AttributeData::new(
+                        String::new(),
+                        VectorData::Vector3(vec![[0.0; 3]; 12]),
+                    )],
                     bone_influences: vec![BoneInfluence {
                         bone_name: "b".to_owned(),
                         vertex_weights: vec![VertexWeight {
@@ -1854,7 +2505,7 @@ mod tests {
                     ..Default::default()
                 },
             ],
-        })
+        ))
         .unwrap();
 
         // Different mesh versions have different conventions for unused vertex buffers.
@@ -1980,7 +2631,7 @@ mod tests {
                     name: String::new(),
                     index: 0,
                     offset: 1,
-                    data_type: DataType::Byte4,
+                    data_type: AttributeDataType::Byte4,
                 },
                 &mesh_object
             )
@@ -1994,7 +2645,7 @@ mod tests {
                     name: String::new(),
                     index: 1,
                     offset: 1,
-                    data_type: DataType::Byte4,
+                    data_type: AttributeDataType::Byte4,
                 },
                 &mesh_object
             )
@@ -2008,7 +2659,7 @@ mod tests {
                     name: String::new(),
                     index: 2,
                     offset: 1,
-                    data_type: DataType::Byte4,
+                    data_type: AttributeDataType::Byte4,
                 },
                 &mesh_object
             )
@@ -2022,7 +2673,7 @@ mod tests {
                     name: String::new(),
                     index: 3,
                     offset: 1,
-                    data_type: DataType::Byte4,
+                    data_type: AttributeDataType::Byte4,
                 },
                 &mesh_object
             )
@@ -2035,7 +2686,7 @@ mod tests {
                 name: String::new(),
                 index: 4,
                 offset: 0,
-                data_type: DataType::Byte4,
+                data_type: AttributeDataType::Byte4,
             },
             &mesh_object,
         );
@@ -2064,6 +2715,8 @@ mod tests {
             ],
             &mut 0,
             &mut Cursor::new(Vec::new()),
+            None,
+            BoundingMethod::default(),
             create_attributes_v10,
         )
         .unwrap();
@@ -2079,14 +2732,14 @@ mod tests {
         // The vertex count can't be determined since 1 != 2.
         let result = create_mesh_object(
             &MeshObjectData {
-                positions: vec![AttributeData {
-                    name: String::new(),
-                    data: VectorData::Vector2(vec![[0.0, 0.0], [0.0, 0.0]]),
-                }],
-                tangents: vec![AttributeData {
-                    name: String::new(),
-                    data: VectorData::Vector2(vec![[0.0, 0.0]]),
-                }],
+                positions: vec![AttributeData::new(
+                    String::new(),
+                    VectorData::Vector2(vec![[0.0, 0.0], [0.0, 0.0]]),
+                )],
+                tangents: vec![AttributeData::new(
+                    String::new(),
+                    VectorData::Vector2(vec![[0.0, 0.0]]),
+                )],
                 ..MeshObjectData::default()
             },
             &mut [
@@ -2097,6 +2750,8 @@ mod tests {
             ],
             &mut 0,
             &mut Cursor::new(Vec::new()),
+            None,
+            BoundingMethod::default(),
             create_attributes_v10,
         );
 
@@ -2111,14 +2766,14 @@ mod tests {
         create_mesh_object(
             &MeshObjectData {
                 vertex_indices: vec![0, 1, 1],
-                positions: vec![AttributeData {
-                    name: String::new(),
-                    data: VectorData::Vector2(vec![[0.0, 0.0], [0.0, 0.0]]),
-                }],
-                tangents: vec![AttributeData {
-                    name: String::new(),
-                    data: VectorData::Vector2(vec![[0.0, 0.0], [0.0, 0.0]]),
-                }],
+                positions: vec![AttributeData::new(
+                    String::new(),
+                    VectorData::Vector2(vec![[0.0, 0.0], [0.0, 0.0]]),
+                )],
+                tangents: vec![AttributeData::new(
+                    String::new(),
+                    VectorData::Vector2(vec![[0.0, 0.0], [0.0, 0.0]]),
+                )],
                 ..MeshObjectData::default()
             },
             &mut [
@@ -2129,6 +2784,8 @@ mod tests {
             ],
             &mut 0,
             &mut Cursor::new(Vec::new()),
+            None,
+            BoundingMethod::default(),
             create_attributes_v10,
         )
         .unwrap();
@@ -2140,14 +2797,14 @@ mod tests {
         let result = create_mesh_object(
             &MeshObjectData {
                 vertex_indices: vec![0, 2, 1, 0, 2, 1, 0, 0],
-                positions: vec![AttributeData {
-                    name: String::new(),
-                    data: VectorData::Vector2(vec![[0.0, 0.0], [0.0, 0.0]]),
-                }],
-                tangents: vec![AttributeData {
-                    name: String::new(),
-                    data: VectorData::Vector2(vec![[0.0, 0.0], [0.0, 0.0]]),
-                }],
+                positions: vec![AttributeData::new(
+                    String::new(),
+                    VectorData::Vector2(vec![[0.0, 0.0], [0.0, 0.0]]),
+                )],
+                tangents: vec![AttributeData::new(
+                    String::new(),
+                    VectorData::Vector2(vec![[0.0, 0.0], [0.0, 0.0]]),
+                )],
                 ..MeshObjectData::default()
             },
             &mut [
@@ -2158,6 +2815,8 @@ mod tests {
             ],
             &mut 0,
             &mut Cursor::new(Vec::new()),
+            None,
+            BoundingMethod::default(),
             create_attributes_v10,
         );
 
@@ -2175,14 +2834,14 @@ mod tests {
         let result = create_mesh_object(
             &MeshObjectData {
                 vertex_indices: vec![0, 2, 1],
-                positions: vec![AttributeData {
-                    name: String::new(),
-                    data: VectorData::Vector2(vec![[0.0, 0.0], [0.0, 0.0]]),
-                }],
-                tangents: vec![AttributeData {
-                    name: String::new(),
-                    data: VectorData::Vector2(vec![[0.0, 0.0], [0.0, 0.0]]),
-                }],
+                positions: vec![AttributeData::new(
+                    String::new(),
+                    VectorData::Vector2(vec![[0.0, 0.0], [0.0, 0.0]]),
+                )],
+                tangents: vec![AttributeData::new(
+                    String::new(),
+                    VectorData::Vector2(vec![[0.0, 0.0], [0.0, 0.0]]),
+                )],
                 ..MeshObjectData::default()
             },
             &mut [
@@ -2193,6 +2852,8 @@ mod tests {
             ],
             &mut 0,
             &mut Cursor::new(Vec::new()),
+            None,
+            BoundingMethod::default(),
             create_attributes_v10,
         );
 
@@ -2221,6 +2882,8 @@ mod tests {
             ],
             &mut 0,
             &mut Cursor::new(Vec::new()),
+            None,
+            BoundingMethod::default(),
             create_attributes_v10,
         );
 
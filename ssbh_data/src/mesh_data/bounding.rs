@@ -0,0 +1,352 @@
+use geometry_tools::glam::{DVec3, Vec3A, Vec4};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use ssbh_lib::formats::mesh::{BoundingInfo, BoundingSphere, BoundingVolume, OrientedBoundingBox};
+use ssbh_lib::{Matrix3x3, Vector3};
+
+/// The algorithm for calculating bounding information when saving.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BoundingMethod {
+    /// An efficient approximate bounding sphere that often overestimates the radius
+    /// and an oriented bounding box aligned to the coordinate axes.
+    /// This is the default and matches the bounding information from previous releases.
+    #[default]
+    Fast,
+    /// The minimal bounding sphere and the smaller of the axis aligned box
+    /// and the box aligned to the principal axes of the points.
+    /// Tighter bounds improve culling and shadows in game but take longer to calculate.
+    Tight,
+}
+
+/// Calculates the bounding sphere for `points` with the center in xyz and the radius in w.
+pub(crate) fn calculate_bounding_sphere(points: &[Vec3A], method: BoundingMethod) -> Vec4 {
+    match method {
+        BoundingMethod::Fast => {
+            geometry_tools::bounding::calculate_bounding_sphere_from_points(points)
+        }
+        BoundingMethod::Tight => minimal_bounding_sphere(points),
+    }
+}
+
+pub(crate) fn calculate_bounding_info(positions: &[Vec3A], method: BoundingMethod) -> BoundingInfo {
+    let sphere = calculate_bounding_sphere(positions, method);
+    let (aabb_min, aabb_max) = geometry_tools::bounding::calculate_aabb_from_points(positions);
+
+    let aabb_obb = (
+        (aabb_min + aabb_max) / 2.0,
+        [Vec3A::X, Vec3A::Y, Vec3A::Z],
+        (aabb_max - aabb_min) / 2.0,
+    );
+    let (obb_center, obb_axes, obb_size) = match method {
+        BoundingMethod::Fast => aabb_obb,
+        BoundingMethod::Tight => {
+            // The principal axes aren't always optimal, so use whichever box is smaller.
+            let pca_obb = principal_axes_obb(positions);
+            if volume(pca_obb.2) < volume(aabb_obb.2) {
+                pca_obb
+            } else {
+                aabb_obb
+            }
+        }
+    };
+
+    BoundingInfo {
+        bounding_sphere: BoundingSphere {
+            center: Vector3::new(sphere.x, sphere.y, sphere.z),
+            radius: sphere.w,
+        },
+        bounding_volume: BoundingVolume {
+            min: Vector3::new(aabb_min.x, aabb_min.y, aabb_min.z),
+            max: Vector3::new(aabb_max.x, aabb_max.y, aabb_max.z),
+        },
+        oriented_bounding_box: OrientedBoundingBox {
+            center: Vector3::new(obb_center.x, obb_center.y, obb_center.z),
+            transform: Matrix3x3 {
+                col1: Vector3::new(obb_axes[0].x, obb_axes[0].y, obb_axes[0].z),
+                col2: Vector3::new(obb_axes[1].x, obb_axes[1].y, obb_axes[1].z),
+                col3: Vector3::new(obb_axes[2].x, obb_axes[2].y, obb_axes[2].z),
+            },
+            size: Vector3::new(obb_size.x, obb_size.y, obb_size.z),
+        },
+    }
+}
+
+fn volume(half_extents: Vec3A) -> f32 {
+    half_extents.x * half_extents.y * half_extents.z
+}
+
+/// Calculates the smallest sphere containing `points` using Welzl's algorithm.
+/// The center is in xyz and the radius is in w.
+pub(crate) fn minimal_bounding_sphere(points: &[Vec3A]) -> Vec4 {
+    if points.is_empty() {
+        return Vec4::ZERO;
+    }
+
+    let mut points: Vec<DVec3> = points.iter().map(|p| p.as_dvec3()).collect();
+
+    // The expected linear running time relies on a random point order.
+    // Use a fixed seed to make the results deterministic.
+    let mut state = 0x2545_f491_4f6c_dd1du64;
+    for i in (1..points.len()).rev() {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        points.swap(i, (state % (i as u64 + 1)) as usize);
+    }
+
+    // An iterative version of the algorithm avoids deep recursion for large meshes.
+    let mut sphere = (points[0], 0.0);
+    for i in 1..points.len() {
+        if contains(sphere, points[i]) {
+            continue;
+        }
+        sphere = (points[i], 0.0);
+        for j in 0..i {
+            if contains(sphere, points[j]) {
+                continue;
+            }
+            sphere = sphere_from_2(points[i], points[j]);
+            for k in 0..j {
+                if contains(sphere, points[k]) {
+                    continue;
+                }
+                sphere = sphere_from_3(points[i], points[j], points[k]);
+                for l in 0..k {
+                    if !contains(sphere, points[l]) {
+                        sphere = sphere_from_4(points[i], points[j], points[k], points[l]);
+                    }
+                }
+            }
+        }
+    }
+
+    // Make sure all points are contained after converting to single precision.
+    let center = sphere.0.as_vec3a();
+    let radius = points
+        .iter()
+        .map(|p| p.as_vec3a().distance(center))
+        .fold(0.0f32, f32::max);
+    center.extend(radius)
+}
+
+fn contains((center, radius): (DVec3, f64), point: DVec3) -> bool {
+    point.distance(center) <= radius + 1e-7 * radius.max(1.0)
+}
+
+fn sphere_from_2(a: DVec3, b: DVec3) -> (DVec3, f64) {
+    ((a + b) / 2.0, a.distance(b) / 2.0)
+}
+
+fn sphere_from_3(a: DVec3, b: DVec3, c: DVec3) -> (DVec3, f64) {
+    let ab = b - a;
+    let ac = c - a;
+    let n = ab.cross(ac);
+    let denom = 2.0 * n.length_squared();
+    if denom <= f64::EPSILON * ab.length_squared() * ac.length_squared() {
+        // Collinear points are bounded by the sphere for the farthest pair.
+        return [
+            sphere_from_2(a, b),
+            sphere_from_2(a, c),
+            sphere_from_2(b, c),
+        ]
+        .into_iter()
+        .max_by(|s1, s2| s1.1.total_cmp(&s2.1))
+        .unwrap();
+    }
+
+    let offset = (n.cross(ab) * ac.length_squared() + ac.cross(n) * ab.length_squared()) / denom;
+    (a + offset, offset.length())
+}
+
+fn sphere_from_4(a: DVec3, b: DVec3, c: DVec3, d: DVec3) -> (DVec3, f64) {
+    let u = b - a;
+    let v = c - a;
+    let w = d - a;
+    let det = 2.0 * u.dot(v.cross(w));
+    if det.abs() <= f64::EPSILON * u.length() * v.length() * w.length() {
+        // Coplanar points are bounded by the smallest sphere through 3 points containing the fourth.
+        let points = [a, b, c, d];
+        return [
+            (sphere_from_3(a, b, c), d),
+            (sphere_from_3(a, b, d), c),
+            (sphere_from_3(a, c, d), b),
+            (sphere_from_3(b, c, d), a),
+        ]
+        .into_iter()
+        .filter(|(s, p)| contains(*s, *p))
+        .map(|(s, _)| s)
+        .min_by(|s1, s2| s1.1.total_cmp(&s2.1))
+        .unwrap_or_else(|| {
+            let center = (a + b + c + d) / 4.0;
+            let radius = points
+                .iter()
+                .map(|p| p.distance(center))
+                .fold(0.0, f64::max);
+            (center, radius)
+        });
+    }
+
+    let offset = (v.cross(w) * u.length_squared()
+        + w.cross(u) * v.length_squared()
+        + u.cross(v) * w.length_squared())
+        / det;
+    (a + offset, offset.length())
+}
+
+/// Calculates the (center, axes, half extents) of the box aligned to the principal axes of `points`.
+fn principal_axes_obb(points: &[Vec3A]) -> (Vec3A, [Vec3A; 3], Vec3A) {
+    if points.is_empty() {
+        return (Vec3A::ZERO, [Vec3A::X, Vec3A::Y, Vec3A::Z], Vec3A::ZERO);
+    }
+
+    let mean = points.iter().map(|p| p.as_dvec3()).sum::<DVec3>() / points.len() as f64;
+    let mut covariance = [[0.0f64; 3]; 3];
+    for p in points {
+        let d = p.as_dvec3() - mean;
+        for (i, row) in covariance.iter_mut().enumerate() {
+            for (j, value) in row.iter_mut().enumerate() {
+                *value += d[i] * d[j];
+            }
+        }
+    }
+
+    let [x, y, _] = symmetric_eigenvectors(covariance);
+    // Use a right handed basis to represent a rotation.
+    let axes = [x, y, x.cross(y)].map(|a| a.normalize_or_zero().as_vec3a());
+
+    let mut min = Vec3A::splat(f32::INFINITY);
+    let mut max = Vec3A::splat(f32::NEG_INFINITY);
+    for p in points {
+        let local = Vec3A::new(axes[0].dot(*p), axes[1].dot(*p), axes[2].dot(*p));
+        min = min.min(local);
+        max = max.max(local);
+    }
+
+    let local_center = (min + max) / 2.0;
+    let center = axes[0] * local_center.x + axes[1] * local_center.y + axes[2] * local_center.z;
+    (center, axes, (max - min) / 2.0)
+}
+
+/// Calculates the eigenvectors of a symmetric matrix using the Jacobi eigenvalue algorithm.
+fn symmetric_eigenvectors(mut a: [[f64; 3]; 3]) -> [DVec3; 3] {
+    let mut v = [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]];
+    let scale = a.iter().flatten().map(|x| x.abs()).fold(0.0, f64::max);
+
+    for _ in 0..32 {
+        // Eliminate the largest off diagonal element.
+        let (p, q) = [(0, 1), (0, 2), (1, 2)]
+            .into_iter()
+            .max_by(|(p1, q1), (p2, q2)| a[*p1][*q1].abs().total_cmp(&a[*p2][*q2].abs()))
+            .unwrap();
+        if a[p][q].abs() <= 1e-12 * scale {
+            break;
+        }
+
+        let theta = (a[q][q] - a[p][p]) / (2.0 * a[p][q]);
+        let t = theta.signum() / (theta.abs() + (theta * theta + 1.0).sqrt());
+        let c = 1.0 / (t * t + 1.0).sqrt();
+        let s = t * c;
+
+        for row in a.iter_mut().chain(v.iter_mut()) {
+            let (kp, kq) = (row[p], row[q]);
+            row[p] = c * kp - s * kq;
+            row[q] = s * kp + c * kq;
+        }
+        let (row_p, row_q) = (a[p], a[q]);
+        for k in 0..3 {
+            a[p][k] = c * row_p[k] - s * row_q[k];
+            a[q][k] = s * row_p[k] + c * row_q[k];
+        }
+    }
+
+    [0, 1, 2].map(|i| DVec3::new(v[0][i], v[1][i], v[2][i]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn random_points(count: usize) -> Vec<Vec3A> {
+        let mut state = 12345u32;
+        let mut random = || {
+            state = state.wrapping_mul(1664525).wrapping_add(1013904223);
+            (state >> 8) as f32 / (1 << 24) as f32 * 10.0 - 5.0
+        };
+        (0..count)
+            .map(|_| Vec3A::new(random(), random() * 0.5, random() * 0.1))
+            .collect()
+    }
+
+    #[test]
+    fn minimal_bounding_sphere_empty() {
+        assert_eq!(Vec4::ZERO, minimal_bounding_sphere(&[]));
+    }
+
+    #[test]
+    fn minimal_bounding_sphere_two_points() {
+        assert_eq!(
+            Vec4::new(1.0, 0.0, 0.0, 1.0),
+            minimal_bounding_sphere(&[Vec3A::ZERO, Vec3A::new(2.0, 0.0, 0.0)])
+        );
+    }
+
+    #[test]
+    fn minimal_bounding_sphere_cube() {
+        let points: Vec<_> = (0..8)
+            .map(|i| {
+                Vec3A::new(
+                    if i & 1 == 0 { -1.0 } else { 1.0 },
+                    if i & 2 == 0 { -1.0 } else { 1.0 },
+                    if i & 4 == 0 { -1.0 } else { 1.0 },
+                )
+            })
+            .chain(std::iter::once(Vec3A::ZERO))
+            .collect();
+
+        let sphere = minimal_bounding_sphere(&points);
+        assert!(sphere.truncate().length() < 0.0001);
+        assert!((sphere.w - 3.0f32.sqrt()).abs() < 0.0001);
+    }
+
+    #[test]
+    fn minimal_bounding_sphere_contains_points() {
+        let points = random_points(1000);
+        let fast = geometry_tools::bounding::calculate_bounding_sphere_from_points(&points);
+        let sphere = minimal_bounding_sphere(&points);
+
+        assert!(sphere.w <= fast.w);
+        for p in points {
+            assert!(p.distance(sphere.truncate().into()) <= sphere.w);
+        }
+    }
+
+    #[test]
+    fn bounding_info_tight_rotated_box() {
+        // A long thin box rotated 45 degrees around the z-axis.
+        let points: Vec<_> = random_points(200)
+            .into_iter()
+            .map(|p| Vec3A::new(p.x - p.y * 0.1, p.x + p.y * 0.1, p.z))
+            .collect();
+
+        let fast = calculate_bounding_info(&points, BoundingMethod::Fast);
+        let tight = calculate_bounding_info(&points, BoundingMethod::Tight);
+
+        let size = |obb: &OrientedBoundingBox| obb.size.x * obb.size.y * obb.size.z;
+        assert!(size(&tight.oriented_bounding_box) < size(&fast.oriented_bounding_box) * 0.5);
+        assert!(tight.bounding_sphere.radius <= fast.bounding_sphere.radius);
+
+        // Check that the box contains all points.
+        let obb = tight.oriented_bounding_box;
+        let axes = [obb.transform.col1, obb.transform.col2, obb.transform.col3]
+            .map(|c| Vec3A::new(c.x, c.y, c.z));
+        let center = Vec3A::new(obb.center.x, obb.center.y, obb.center.z);
+        let size = [obb.size.x, obb.size.y, obb.size.z];
+        for p in points {
+            for (axis, size) in axes.iter().zip(size) {
+                assert!(axis.dot(p - center).abs() <= size + 0.0001);
+            }
+        }
+    }
+}
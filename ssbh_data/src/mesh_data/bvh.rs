@@ -0,0 +1,549 @@
+use geometry_tools::glam::Vec3A;
+
+const MAX_LEAF_SIZE: usize = 4;
+
+#[derive(Debug, Clone)]
+struct Node {
+    min: Vec3A,
+    max: Vec3A,
+    /// The index of the first primitive for leaves or the left child for inner nodes.
+    /// The right child is always stored after the left child.
+    first: usize,
+    /// The number of primitives for leaves or 0 for inner nodes.
+    count: usize,
+}
+
+/// A bounding volume hierarchy over primitives with axis aligned bounding boxes.
+#[derive(Debug, Clone)]
+pub(crate) struct Bvh {
+    nodes: Vec<Node>,
+    indices: Vec<usize>,
+}
+
+impl Bvh {
+    /// Builds the hierarchy from the (min, max) bounds of each primitive.
+    pub(crate) fn new(bounds: &[(Vec3A, Vec3A)]) -> Self {
+        let mut bvh = Self {
+            nodes: Vec::new(),
+            indices: (0..bounds.len()).collect(),
+        };
+
+        if !bounds.is_empty() {
+            let centroids: Vec<_> = bounds
+                .iter()
+                .map(|(min, max)| (*min + *max) * 0.5)
+                .collect();
+            bvh.nodes.push(empty_node());
+            bvh.build(0, 0, bounds.len(), bounds, &centroids);
+        }
+
+        bvh
+    }
+
+    fn build(
+        &mut self,
+        node: usize,
+        first: usize,
+        count: usize,
+        bounds: &[(Vec3A, Vec3A)],
+        centroids: &[Vec3A],
+    ) {
+        let indices = &mut self.indices[first..first + count];
+        let (min, max) = indices.iter().fold(
+            (Vec3A::splat(f32::INFINITY), Vec3A::splat(f32::NEG_INFINITY)),
+            |(min, max), i| (min.min(bounds[*i].0), max.max(bounds[*i].1)),
+        );
+        self.nodes[node].min = min;
+        self.nodes[node].max = max;
+
+        if count <= MAX_LEAF_SIZE {
+            self.nodes[node].first = first;
+            self.nodes[node].count = count;
+            return;
+        }
+
+        // Split at the median centroid along the axis with the largest extent.
+        let (centroid_min, centroid_max) = indices.iter().fold(
+            (Vec3A::splat(f32::INFINITY), Vec3A::splat(f32::NEG_INFINITY)),
+            |(min, max), i| (min.min(centroids[*i]), max.max(centroids[*i])),
+        );
+        let extent = centroid_max - centroid_min;
+        let axis = if extent.x >= extent.y && extent.x >= extent.z {
+            0
+        } else if extent.y >= extent.z {
+            1
+        } else {
+            2
+        };
+
+        let left_count = count / 2;
+        indices.select_nth_unstable_by(left_count, |a, b| {
+            centroids[*a][axis].total_cmp(&centroids[*b][axis])
+        });
+
+        let left = self.nodes.len();
+        self.nodes.push(empty_node());
+        self.nodes.push(empty_node());
+        self.nodes[node].first = left;
+        self.nodes[node].count = 0;
+
+        self.build(left, first, left_count, bounds, centroids);
+        self.build(
+            left + 1,
+            first + left_count,
+            count - left_count,
+            bounds,
+            centroids,
+        );
+    }
+
+    /// Finds the primitive with the smallest squared distance to `point`.
+    /// `distance_squared` calculates the distance and any additional data for a primitive
+    /// or returns [None] to skip the primitive.
+    pub(crate) fn closest<T>(
+        &self,
+        point: Vec3A,
+        mut distance_squared: impl FnMut(usize) -> Option<(f32, T)>,
+    ) -> Option<(usize, f32, T)> {
+        let mut closest: Option<(usize, f32, T)> = None;
+        let mut stack = Vec::new();
+        if !self.nodes.is_empty() {
+            stack.push(0);
+        }
+
+        while let Some(n) = stack.pop() {
+            let node = &self.nodes[n];
+            let max_distance = closest.as_ref().map(|c| c.1).unwrap_or(f32::INFINITY);
+            if aabb_distance_squared(point, node.min, node.max) > max_distance {
+                continue;
+            }
+
+            if node.count > 0 {
+                for i in &self.indices[node.first..node.first + node.count] {
+                    if let Some((distance, value)) = distance_squared(*i) {
+                        if closest.as_ref().map(|c| distance < c.1).unwrap_or(true) {
+                            closest = Some((*i, distance, value));
+                        }
+                    }
+                }
+            } else {
+                // Visit the closer child first to prune more nodes.
+                let left = &self.nodes[node.first];
+                let right = &self.nodes[node.first + 1];
+                if aabb_distance_squared(point, left.min, left.max)
+                    < aabb_distance_squared(point, right.min, right.max)
+                {
+                    stack.push(node.first + 1);
+                    stack.push(node.first);
+                } else {
+                    stack.push(node.first);
+                    stack.push(node.first + 1);
+                }
+            }
+        }
+
+        closest
+    }
+
+    /// Finds the primitive with the smallest distance along the ray from `origin` in `direction`.
+    /// `intersect` calculates the distance and any additional data for a primitive
+    /// or returns [None] if the ray misses the primitive.
+    pub(crate) fn ray<T>(
+        &self,
+        origin: Vec3A,
+        direction: Vec3A,
+        max_distance: f32,
+        mut intersect: impl FnMut(usize) -> Option<(f32, T)>,
+    ) -> Option<(usize, f32, T)> {
+        let inverse_direction = direction.recip();
+
+        let mut closest: Option<(usize, f32, T)> = None;
+        let mut stack = Vec::new();
+        if !self.nodes.is_empty() {
+            stack.push(0);
+        }
+
+        while let Some(n) = stack.pop() {
+            let node = &self.nodes[n];
+            let max_distance = closest.as_ref().map(|c| c.1).unwrap_or(max_distance);
+            match ray_aabb_distance(origin, inverse_direction, node.min, node.max) {
+                Some(distance) if distance <= max_distance => (),
+                _ => continue,
+            }
+
+            if node.count > 0 {
+                for i in &self.indices[node.first..node.first + node.count] {
+                    if let Some((distance, value)) = intersect(*i) {
+                        if distance <= max_distance
+                            && closest.as_ref().map(|c| distance < c.1).unwrap_or(true)
+                        {
+                            closest = Some((*i, distance, value));
+                        }
+                    }
+                }
+            } else {
+                stack.push(node.first);
+                stack.push(node.first + 1);
+            }
+        }
+
+        closest
+    }
+
+    /// Calls `visit` for each primitive in a leaf whose bounds overlap the box from `min` to `max`.
+    /// The primitive bounds themselves are not tested.
+    pub(crate) fn overlapping(&self, min: Vec3A, max: Vec3A, mut visit: impl FnMut(usize)) {
+        let mut stack = Vec::new();
+        if !self.nodes.is_empty() {
+            stack.push(0);
+        }
+
+        while let Some(n) = stack.pop() {
+            let node = &self.nodes[n];
+            if node.min.cmpgt(max).any() || node.max.cmplt(min).any() {
+                continue;
+            }
+
+            if node.count > 0 {
+                for i in &self.indices[node.first..node.first + node.count] {
+                    visit(*i);
+                }
+            } else {
+                stack.push(node.first);
+                stack.push(node.first + 1);
+            }
+        }
+    }
+}
+
+fn empty_node() -> Node {
+    Node {
+        min: Vec3A::ZERO,
+        max: Vec3A::ZERO,
+        first: 0,
+        count: 0,
+    }
+}
+
+fn aabb_distance_squared(point: Vec3A, min: Vec3A, max: Vec3A) -> f32 {
+    (point.max(min).min(max) - point).length_squared()
+}
+
+/// The distance along the ray to the box or 0.0 if the origin is inside the box.
+/// See "An Efficient and Robust Ray-Box Intersection Algorithm" by Williams et al.
+fn ray_aabb_distance(
+    origin: Vec3A,
+    inverse_direction: Vec3A,
+    min: Vec3A,
+    max: Vec3A,
+) -> Option<f32> {
+    let mut near = 0.0f32;
+    let mut far = f32::INFINITY;
+    for i in 0..3 {
+        if inverse_direction[i].is_infinite() {
+            // Rays parallel to the slab only intersect if the origin is inside the slab.
+            // This avoids 0.0 * inf = NaN for origins on the slab boundary.
+            if origin[i] < min[i] || origin[i] > max[i] {
+                return None;
+            }
+        } else {
+            let t0 = (min[i] - origin[i]) * inverse_direction[i];
+            let t1 = (max[i] - origin[i]) * inverse_direction[i];
+            near = near.max(t0.min(t1));
+            far = far.min(t0.max(t1));
+        }
+    }
+    (near <= far).then_some(near)
+}
+
+/// The distance along the ray to the triangle `[a, b, c]` and the barycentric coordinates of the hit.
+/// Both sides of the triangle are considered.
+/// See "Fast, Minimum Storage Ray/Triangle Intersection" by Möller and Trumbore.
+pub(crate) fn ray_triangle(
+    origin: Vec3A,
+    direction: Vec3A,
+    [a, b, c]: [Vec3A; 3],
+) -> Option<(f32, [f32; 3])> {
+    let ab = b - a;
+    let ac = c - a;
+    let p = direction.cross(ac);
+    let determinant = ab.dot(p);
+    if determinant.abs() < f32::EPSILON * ab.length() * ac.length() * direction.length() {
+        return None;
+    }
+    let inverse_determinant = determinant.recip();
+
+    let ao = origin - a;
+    let u = ao.dot(p) * inverse_determinant;
+    if !(0.0..=1.0).contains(&u) {
+        return None;
+    }
+
+    let q = ao.cross(ab);
+    let v = direction.dot(q) * inverse_determinant;
+    if v < 0.0 || u + v > 1.0 {
+        return None;
+    }
+
+    let t = ac.dot(q) * inverse_determinant;
+    (t >= 0.0).then_some((t, [1.0 - u - v, u, v]))
+}
+
+/// Tests if the triangle `[a, b, c]` overlaps the box from `min` to `max` using the separating axis theorem.
+/// See "Fast 3D Triangle-Box Overlap Testing" by Tomas Akenine-Möller.
+pub(crate) fn triangle_overlaps_aabb(triangle: [Vec3A; 3], min: Vec3A, max: Vec3A) -> bool {
+    let center = (min + max) * 0.5;
+    let half_size = (max - min) * 0.5;
+    let [a, b, c] = triangle.map(|v| v - center);
+    let edges = [b - a, c - b, a - c];
+
+    let separated = |axis: Vec3A| {
+        let (pa, pb, pc) = (a.dot(axis), b.dot(axis), c.dot(axis));
+        let radius = half_size.dot(axis.abs());
+        pa.min(pb).min(pc) > radius || pa.max(pb).max(pc) < -radius
+    };
+
+    // The face normals of the box.
+    if [Vec3A::X, Vec3A::Y, Vec3A::Z].into_iter().any(separated) {
+        return false;
+    }
+
+    // The normal of the triangle.
+    if separated(edges[0].cross(edges[1])) {
+        return false;
+    }
+
+    // The cross products of the box and triangle edges.
+    !edges
+        .iter()
+        .flat_map(|e| [Vec3A::X.cross(*e), Vec3A::Y.cross(*e), Vec3A::Z.cross(*e)])
+        .any(separated)
+}
+
+/// The (min, max) bounds of a triangle.
+pub(crate) fn triangle_bounds([a, b, c]: [Vec3A; 3]) -> (Vec3A, Vec3A) {
+    (a.min(b).min(c), a.max(b).max(c))
+}
+
+/// Finds the closest point on the triangle `[a, b, c]` to `point` and its barycentric coordinates.
+/// See "Real-Time Collision Detection" by Christer Ericson.
+pub(crate) fn closest_point_triangle(point: Vec3A, [a, b, c]: [Vec3A; 3]) -> (Vec3A, [f32; 3]) {
+    let ab = b - a;
+    let ac = c - a;
+
+    let ap = point - a;
+    let d1 = ab.dot(ap);
+    let d2 = ac.dot(ap);
+    if d1 <= 0.0 && d2 <= 0.0 {
+        return (a, [1.0, 0.0, 0.0]);
+    }
+
+    let bp = point - b;
+    let d3 = ab.dot(bp);
+    let d4 = ac.dot(bp);
+    if d3 >= 0.0 && d4 <= d3 {
+        return (b, [0.0, 1.0, 0.0]);
+    }
+
+    let vc = d1 * d4 - d3 * d2;
+    if vc <= 0.0 && d1 >= 0.0 && d3 <= 0.0 {
+        let v = d1 / (d1 - d3);
+        return (a + ab * v, [1.0 - v, v, 0.0]);
+    }
+
+    let cp = point - c;
+    let d5 = ab.dot(cp);
+    let d6 = ac.dot(cp);
+    if d6 >= 0.0 && d5 <= d6 {
+        return (c, [0.0, 0.0, 1.0]);
+    }
+
+    let vb = d5 * d2 - d1 * d6;
+    if vb <= 0.0 && d2 >= 0.0 && d6 <= 0.0 {
+        let w = d2 / (d2 - d6);
+        return (a + ac * w, [1.0 - w, 0.0, w]);
+    }
+
+    let va = d3 * d6 - d5 * d4;
+    if va <= 0.0 && (d4 - d3) >= 0.0 && (d5 - d6) >= 0.0 {
+        let w = (d4 - d3) / ((d4 - d3) + (d5 - d6));
+        return (b + (c - b) * w, [0.0, 1.0 - w, w]);
+    }
+
+    let denom = 1.0 / (va + vb + vc);
+    let v = vb * denom;
+    let w = vc * denom;
+    if v.is_finite() && w.is_finite() {
+        (a + ab * v + ac * w, [1.0 - v - w, v, w])
+    } else {
+        // Degenerate triangles have no interior, so use the closest corner.
+        [
+            (a, [1.0, 0.0, 0.0]),
+            (b, [0.0, 1.0, 0.0]),
+            (c, [0.0, 0.0, 1.0]),
+        ]
+        .into_iter()
+        .min_by(|(p1, _), (p2, _)| {
+            p1.distance_squared(point)
+                .total_cmp(&p2.distance_squared(point))
+        })
+        .unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn closest_point_matches_brute_force() {
+        // Generate a deterministic point cloud with a simple linear congruential generator.
+        let mut state = 12345u32;
+        let mut random = || {
+            state = state.wrapping_mul(1664525).wrapping_add(1013904223);
+            (state >> 8) as f32 / (1 << 24) as f32 * 10.0 - 5.0
+        };
+        let points: Vec<_> = (0..500)
+            .map(|_| Vec3A::new(random(), random(), random()))
+            .collect();
+        let queries: Vec<_> = (0..50)
+            .map(|_| Vec3A::new(random(), random(), random()))
+            .collect();
+
+        let bvh = Bvh::new(&points.iter().map(|p| (*p, *p)).collect::<Vec<_>>());
+        for query in queries {
+            let (i, distance, _) = bvh
+                .closest(query, |i| Some((points[i].distance_squared(query), ())))
+                .unwrap();
+            let expected = points
+                .iter()
+                .map(|p| p.distance_squared(query))
+                .min_by(|a, b| a.total_cmp(b))
+                .unwrap();
+            assert_eq!(expected, distance);
+            assert_eq!(expected, points[i].distance_squared(query));
+        }
+    }
+
+    #[test]
+    fn closest_empty() {
+        let bvh = Bvh::new(&[]);
+        assert!(bvh.closest(Vec3A::ZERO, |_| Some((0.0, ()))).is_none());
+    }
+
+    #[test]
+    fn closest_point_triangle_regions() {
+        let triangle = [Vec3A::ZERO, Vec3A::X, Vec3A::Y];
+        assert_eq!(
+            (Vec3A::new(0.25, 0.25, 0.0), [0.5, 0.25, 0.25]),
+            closest_point_triangle(Vec3A::new(0.25, 0.25, 1.0), triangle)
+        );
+        assert_eq!(
+            (Vec3A::new(0.5, 0.0, 0.0), [0.5, 0.5, 0.0]),
+            closest_point_triangle(Vec3A::new(0.5, -1.0, 0.0), triangle)
+        );
+        assert_eq!(
+            (Vec3A::X, [0.0, 1.0, 0.0]),
+            closest_point_triangle(Vec3A::new(2.0, -1.0, 0.0), triangle)
+        );
+    }
+
+    #[test]
+    fn ray_matches_brute_force() {
+        let mut state = 6789u32;
+        let mut random = || {
+            state = state.wrapping_mul(1664525).wrapping_add(1013904223);
+            (state >> 8) as f32 / (1 << 24) as f32 * 10.0 - 5.0
+        };
+        let triangles: Vec<[Vec3A; 3]> = (0..200)
+            .map(|_| {
+                let center = Vec3A::new(random(), random(), random());
+                [0, 1, 2].map(|_| center + Vec3A::new(random(), random(), random()) * 0.2)
+            })
+            .collect();
+
+        let bvh = Bvh::new(
+            &triangles
+                .iter()
+                .map(|t| triangle_bounds(*t))
+                .collect::<Vec<_>>(),
+        );
+        for _ in 0..50 {
+            let origin = Vec3A::new(random(), random(), random()) * 2.0;
+            let direction = -origin + Vec3A::new(random(), random(), random()) * 0.1;
+
+            let actual = bvh.ray(origin, direction, f32::INFINITY, |i| {
+                ray_triangle(origin, direction, triangles[i])
+            });
+            let expected = triangles
+                .iter()
+                .filter_map(|t| ray_triangle(origin, direction, *t))
+                .map(|(t, _)| t)
+                .min_by(|a, b| a.total_cmp(b));
+            assert_eq!(expected, actual.map(|(_, t, _)| t));
+        }
+    }
+
+    #[test]
+    fn ray_aabb_axis_aligned_on_boundary() {
+        let min = Vec3A::ZERO;
+        let max = Vec3A::new(1.0, 1.0, 0.0);
+        let direction = -Vec3A::Z;
+        let inverse_direction = direction.recip();
+
+        // The origin lies on the min and max faces for the parallel axes.
+        for origin in [
+            Vec3A::new(0.0, 0.5, 5.0),
+            Vec3A::new(1.0, 0.5, 5.0),
+            Vec3A::new(0.0, 0.0, 5.0),
+        ] {
+            assert_eq!(
+                Some(5.0),
+                ray_aabb_distance(origin, inverse_direction, min, max)
+            );
+        }
+        assert_eq!(
+            None,
+            ray_aabb_distance(Vec3A::new(1.5, 0.5, 5.0), inverse_direction, min, max)
+        );
+    }
+
+    #[test]
+    fn ray_triangle_hit_and_miss() {
+        let triangle = [Vec3A::ZERO, Vec3A::X, Vec3A::Y];
+        let (t, barycentrics) =
+            ray_triangle(Vec3A::new(0.25, 0.5, 2.0), -Vec3A::Z, triangle).unwrap();
+        assert_eq!(2.0, t);
+        assert_eq!([0.25, 0.25, 0.5], barycentrics);
+
+        // Both sides are considered.
+        assert!(ray_triangle(Vec3A::new(0.25, 0.5, -2.0), Vec3A::Z, triangle).is_some());
+
+        // Pointing away or outside the triangle.
+        assert!(ray_triangle(Vec3A::new(0.25, 0.5, 2.0), Vec3A::Z, triangle).is_none());
+        assert!(ray_triangle(Vec3A::new(1.0, 1.0, 2.0), -Vec3A::Z, triangle).is_none());
+        // Parallel to the triangle.
+        assert!(ray_triangle(Vec3A::new(-1.0, 0.25, 0.0), Vec3A::X, triangle).is_none());
+    }
+
+    #[test]
+    fn triangle_aabb_overlap() {
+        let triangle = [Vec3A::ZERO, Vec3A::X, Vec3A::Y];
+        assert!(triangle_overlaps_aabb(
+            triangle,
+            Vec3A::splat(-0.1),
+            Vec3A::splat(0.1)
+        ));
+        // The bounds overlap but the triangle does not.
+        assert!(!triangle_overlaps_aabb(
+            triangle,
+            Vec3A::new(0.7, 0.7, -0.1),
+            Vec3A::new(0.9, 0.9, 0.1)
+        ));
+        assert!(!triangle_overlaps_aabb(
+            triangle,
+            Vec3A::new(0.0, 0.0, 0.5),
+            Vec3A::splat(1.0)
+        ));
+    }
+}
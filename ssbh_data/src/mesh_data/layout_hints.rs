@@ -0,0 +1,343 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use ssbh_lib::formats::mesh::{
+    AttributeV10, AttributeV8, AttributeV9, BoundingInfo, DrawElementType, MeshInner, RiggingFlags,
+};
+
+use super::{Attribute, AttributeData, AttributeDataType, MeshObjectData, VectorData, Weight};
+
+/// Layout information from the original [Mesh](super::Mesh) used to preserve unmodified data when saving.
+///
+/// Objects are rebuilt using the default layout if their data has changed since reading.
+/// Mesh level values like [bounding_info](#structfield.bounding_info) are only preserved
+/// if all objects are unmodified and in their original order.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[derive(Debug, Clone, PartialEq)]
+pub struct MeshLayoutHints {
+    pub model_name: String,
+    pub bounding_info: BoundingInfo,
+    pub unk1: u32,
+    /// The hints for each object in the original order.
+    pub objects: Vec<MeshObjectLayoutHints>,
+}
+
+/// Layout information from the original [MeshObject](ssbh_lib::formats::mesh::MeshObject) for a [MeshObjectData].
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[derive(Debug, Clone, PartialEq)]
+pub struct MeshObjectLayoutHints {
+    pub name: String,
+    pub subindex: u64,
+    /// A hash of the [MeshObjectData] when it was read to detect modifications.
+    /// The hash uses 64-bit FNV-1a and is stable between releases and platforms.
+    pub data_hash: u64,
+    /// The attributes with their original order, buffer assignment, offsets, and data types.
+    pub attributes: AttributeLayouts,
+    /// The strides for each of the 4 vertex buffers.
+    pub strides: [u32; 4],
+    pub vertex_buffer2_offset: u32,
+    pub draw_element_type: DrawElementType,
+    pub bounding_info: BoundingInfo,
+    /// The flags for the object's rigging group or [None] if the object had no rigging group.
+    pub rigging_flags: Option<RiggingFlags>,
+}
+
+/// The original attributes for each supported mesh version.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[derive(Debug, Clone, PartialEq)]
+pub enum AttributeLayouts {
+    V8(Vec<AttributeV8>),
+    V9(Vec<AttributeV9>),
+    V10(Vec<AttributeV10>),
+}
+
+impl MeshLayoutHints {
+    pub(super) fn from_mesh_inner<A: Attribute, W: Weight>(
+        mesh: &MeshInner<A, W>,
+        objects: &[MeshObjectData],
+    ) -> Self {
+        Self {
+            model_name: mesh.model_name.to_string_lossy(),
+            bounding_info: mesh.bounding_info,
+            unk1: mesh.unk1,
+            objects: mesh
+                .objects
+                .elements
+                .iter()
+                .zip(objects)
+                .map(|(o, data)| MeshObjectLayoutHints {
+                    name: data.name.clone(),
+                    subindex: data.subindex,
+                    data_hash: hash_mesh_object(data),
+                    attributes: A::to_layouts(&o.attributes.elements),
+                    strides: [o.stride0, o.stride1, o.stride2, o.stride3],
+                    vertex_buffer2_offset: o.vertex_buffer2_offset,
+                    draw_element_type: o.draw_element_type,
+                    bounding_info: o.bounding_info,
+                    rigging_flags: mesh
+                        .rigging_buffers
+                        .elements
+                        .iter()
+                        .find(|r| {
+                            r.mesh_object_name.to_str() == Some(&data.name)
+                                && r.mesh_object_subindex == data.subindex
+                        })
+                        .map(|r| r.flags),
+                })
+                .collect(),
+        }
+    }
+}
+
+/// The hints for each object that has not been modified since reading.
+/// Each object is hashed at most once, so this should be reused for all the objects in a mesh.
+pub(super) struct UnmodifiedObjects<'a> {
+    hints: Option<&'a MeshLayoutHints>,
+    /// The index in the hints for each object or [None] if the object is new or modified.
+    indices: Vec<Option<usize>>,
+}
+
+impl<'a> UnmodifiedObjects<'a> {
+    pub(super) fn new(hints: Option<&'a MeshLayoutHints>, objects: &[MeshObjectData]) -> Self {
+        let indices = match hints {
+            Some(hints) => {
+                let mut index_by_name = HashMap::new();
+                for (i, o) in hints.objects.iter().enumerate() {
+                    index_by_name
+                        .entry((o.name.as_str(), o.subindex))
+                        .or_insert(i);
+                }
+
+                objects
+                    .iter()
+                    .map(|data| {
+                        index_by_name
+                            .get(&(data.name.as_str(), data.subindex))
+                            .copied()
+                            .filter(|i| hints.objects[*i].data_hash == hash_mesh_object(data))
+                    })
+                    .collect()
+            }
+            None => vec![None; objects.len()],
+        };
+
+        Self { hints, indices }
+    }
+
+    /// The hints for the object at `index` if the object has not been modified since reading.
+    pub(super) fn get(&self, index: usize) -> Option<&'a MeshObjectLayoutHints> {
+        let hints = self.hints?;
+        self.indices[index].map(|i| &hints.objects[i])
+    }
+
+    /// Returns `true` if the objects are unmodified and in their original order.
+    pub(super) fn all_unmodified(&self) -> bool {
+        self.hints.map(|h| h.objects.len()) == Some(self.indices.len())
+            && self.prefix_len() == self.indices.len()
+    }
+
+    /// Returns the number of leading objects that are unmodified and in their original position.
+    pub(super) fn prefix_len(&self) -> usize {
+        self.indices
+            .iter()
+            .enumerate()
+            .take_while(|(i, index)| **index == Some(*i))
+            .count()
+    }
+}
+
+fn hash_mesh_object(data: &MeshObjectData) -> u64 {
+    let mut hasher = Fnv1a::new();
+
+    hasher.write_str(&data.name);
+    hasher.write_u64(data.subindex);
+    hasher.write_str(&data.parent_bone_name);
+    hasher.write_u32(data.sort_bias as u32);
+    hasher.write_bool(data.disable_depth_write);
+    hasher.write_bool(data.disable_depth_test);
+    hasher.write_len(data.vertex_indices.len());
+    for index in &data.vertex_indices {
+        hasher.write_u32(*index);
+    }
+
+    for attributes in [
+        &data.positions,
+        &data.normals,
+        &data.binormals,
+        &data.tangents,
+        &data.texture_coordinates,
+        &data.color_sets,
+    ] {
+        hasher.write_len(attributes.len());
+        for attribute in attributes {
+            hash_attribute(attribute, &mut hasher);
+        }
+    }
+
+    hasher.write_len(data.bone_influences.len());
+    for influence in &data.bone_influences {
+        hasher.write_str(&influence.bone_name);
+        hasher.write_len(influence.vertex_weights.len());
+        for weight in &influence.vertex_weights {
+            hasher.write_u32(weight.vertex_index);
+            hasher.write_u32(weight.vertex_weight.to_bits());
+        }
+    }
+
+    hasher.finish()
+}
+
+fn hash_attribute(attribute: &AttributeData, hasher: &mut Fnv1a) {
+    hasher.write_str(&attribute.name);
+    // Use fixed values instead of the derived Hash to keep the hash stable.
+    hasher.write_u32(match attribute.data_type {
+        None => 0,
+        Some(AttributeDataType::Float2) => 1,
+        Some(AttributeDataType::Float3) => 2,
+        Some(AttributeDataType::Float4) => 3,
+        Some(AttributeDataType::HalfFloat2) => 4,
+        Some(AttributeDataType::HalfFloat4) => 5,
+        Some(AttributeDataType::Byte4) => 6,
+    });
+
+    // Floats don't implement Hash, so hash the bits instead.
+    match &attribute.data {
+        VectorData::Vector2(v) => hash_floats(2, v.iter().flatten(), hasher),
+        VectorData::Vector3(v) => hash_floats(3, v.iter().flatten(), hasher),
+        VectorData::Vector4(v) => hash_floats(4, v.iter().flatten(), hasher),
+    }
+}
+
+fn hash_floats<'a>(
+    component_count: u32,
+    values: impl Iterator<Item = &'a f32>,
+    hasher: &mut Fnv1a,
+) {
+    hasher.write_u32(component_count);
+    for value in values {
+        hasher.write_u32(value.to_bits());
+    }
+}
+
+/// The 64-bit FNV-1a hash of little-endian values.
+/// Unlike [std::collections::hash_map::DefaultHasher] and the [std::hash::Hash] impls,
+/// the result does not depend on the Rust version or platform.
+struct Fnv1a(u64);
+
+impl Fnv1a {
+    fn new() -> Self {
+        Self(0xcbf29ce484222325)
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= *byte as u64;
+            self.0 = self.0.wrapping_mul(0x100000001b3);
+        }
+    }
+
+    fn write_bool(&mut self, value: bool) {
+        self.write(&[value as u8]);
+    }
+
+    fn write_u32(&mut self, value: u32) {
+        self.write(&value.to_le_bytes());
+    }
+
+    fn write_u64(&mut self, value: u64) {
+        self.write(&value.to_le_bytes());
+    }
+
+    fn write_len(&mut self, len: usize) {
+        self.write_u64(len as u64);
+    }
+
+    fn write_str(&mut self, value: &str) {
+        self.write_len(value.len());
+        self.write(value.as_bytes());
+    }
+
+    fn finish(&self) -> u64 {
+        self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fnv1a_reference_values() {
+        let hash = |bytes: &[u8]| {
+            let mut hasher = Fnv1a::new();
+            hasher.write(bytes);
+            hasher.finish()
+        };
+        assert_eq!(0xcbf29ce484222325, hash(b""));
+        assert_eq!(0xaf63dc4c8601ec8c, hash(b"a"));
+        assert_eq!(0x85944171f73967e8, hash(b"foobar"));
+    }
+
+    #[test]
+    fn hash_mesh_object_stable() {
+        // The hash is serialized, so changing it invalidates existing layout hints.
+        let data = MeshObjectData {
+            name: "a".into(),
+            vertex_indices: vec![0, 1, 2],
+            positions: vec![AttributeData::new(
+                "Position0",
+                VectorData::Vector3(vec![[0.0, 1.0, 2.0]; 3]),
+            )],
+            ..Default::default()
+        };
+        assert_eq!(0x1b6276504c2384f6, hash_mesh_object(&data));
+    }
+
+    #[test]
+    fn unmodified_objects_reordered() {
+        let object = |name: &str| MeshObjectData {
+            name: name.into(),
+            ..Default::default()
+        };
+        let hint = |data: &MeshObjectData| MeshObjectLayoutHints {
+            name: data.name.clone(),
+            subindex: data.subindex,
+            data_hash: hash_mesh_object(data),
+            attributes: AttributeLayouts::V10(Vec::new()),
+            strides: [0; 4],
+            vertex_buffer2_offset: 0,
+            draw_element_type: DrawElementType::UnsignedShort,
+            bounding_info: BoundingInfo::default(),
+            rigging_flags: None,
+        };
+        let hints = MeshLayoutHints {
+            model_name: String::new(),
+            bounding_info: BoundingInfo::default(),
+            unk1: 0,
+            objects: vec![hint(&object("a")), hint(&object("b"))],
+        };
+
+        let objects = vec![object("a"), object("b")];
+        let unmodified = UnmodifiedObjects::new(Some(&hints), &objects);
+        assert!(unmodified.all_unmodified());
+        assert_eq!(2, unmodified.prefix_len());
+
+        let mut modified = object("a");
+        modified.vertex_indices = vec![0, 1, 2];
+        let objects = vec![object("b"), modified, object("c")];
+        let unmodified = UnmodifiedObjects::new(Some(&hints), &objects);
+        assert!(!unmodified.all_unmodified());
+        assert_eq!(0, unmodified.prefix_len());
+        assert_eq!(Some(&hints.objects[1]), unmodified.get(0));
+        assert_eq!(None, unmodified.get(1));
+        assert_eq!(None, unmodified.get(2));
+
+        let unmodified = UnmodifiedObjects::new(None, &objects);
+        assert!(!unmodified.all_unmodified());
+        assert_eq!(None, unmodified.get(0));
+    }
+}
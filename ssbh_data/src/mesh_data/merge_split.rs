@@ -0,0 +1,478 @@
+use itertools::Itertools;
+
+use super::{
+    error, find, position_ids, union, AttributeData, BoneInfluence, MeshObjectData, VectorData,
+    VertexWeight,
+};
+
+impl MeshObjectData {
+    /// Combines `objects` into a single object.
+    ///
+    /// The name, subindex, parent bone, and render settings are copied from the first object.
+    /// Attributes are matched by name for each usage.
+    /// Objects without an attribute use a default value for each of their vertices
+    /// like `(0.0, 0.0, 1.0)` for normals or `(0.5, 0.5, 0.5, 0.5)` for color sets,
+    /// which is the neutral value for Smash Ultimate's doubled vertex colors.
+    /// Attributes with different component counts use the largest count.
+    /// The [vertex_indices](#structfield.vertex_indices) and [bone_influences](#structfield.bone_influences)
+    /// are offset to use the combined vertices.
+    /**
+    ```rust
+    # use ssbh_data::mesh_data::{AttributeData, MeshObjectData, VectorData};
+    let position = |data| AttributeData::new("Position0", VectorData::Vector3(data));
+    let a = MeshObjectData {
+        name: "body".into(),
+        vertex_indices: vec![0, 1, 2],
+        positions: vec![position(vec![[0.0; 3], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]])],
+        ..Default::default()
+    };
+    let b = MeshObjectData {
+        name: "hat".into(),
+        vertex_indices: vec![0, 1, 2],
+        positions: vec![position(vec![[0.0; 3], [0.0, 0.0, 1.0], [0.0, 1.0, 0.0]])],
+        ..Default::default()
+    };
+
+    let merged = MeshObjectData::merge(&[a, b])?;
+    assert_eq!("body", merged.name);
+    assert_eq!(vec![0, 1, 2, 3, 4, 5], merged.vertex_indices);
+    # Ok::<(), Box<dyn std::error::Error>>(())
+    ```
+     */
+    pub fn merge(objects: &[MeshObjectData]) -> Result<MeshObjectData, error::Error> {
+        let vertex_counts = objects
+            .iter()
+            .map(|o| o.validated_vertex_count())
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut vertex_indices = Vec::new();
+        let mut weights_by_bone: Vec<(&str, Vec<VertexWeight>)> = Vec::new();
+        let mut offset = 0;
+        for (object, vertex_count) in objects.iter().zip(&vertex_counts) {
+            vertex_indices.extend(object.vertex_indices.iter().map(|i| i + offset));
+            for influence in &object.bone_influences {
+                let weights = influence.vertex_weights.iter().map(|w| VertexWeight {
+                    vertex_index: w.vertex_index + offset,
+                    vertex_weight: w.vertex_weight,
+                });
+                match weights_by_bone
+                    .iter_mut()
+                    .find(|(name, _)| *name == influence.bone_name)
+                {
+                    Some((_, bone_weights)) => bone_weights.extend(weights),
+                    None => weights_by_bone.push((&influence.bone_name, weights.collect())),
+                }
+            }
+            offset += *vertex_count as u32;
+        }
+
+        let first = objects.first().cloned().unwrap_or_default();
+        Ok(MeshObjectData {
+            vertex_indices,
+            positions: merge_attributes(objects, &vertex_counts, |o| &o.positions, [0.0; 4]),
+            normals: merge_attributes(
+                objects,
+                &vertex_counts,
+                |o| &o.normals,
+                [0.0, 0.0, 1.0, 0.0],
+            ),
+            binormals: merge_attributes(
+                objects,
+                &vertex_counts,
+                |o| &o.binormals,
+                [0.0, 1.0, 0.0, 0.0],
+            ),
+            tangents: merge_attributes(
+                objects,
+                &vertex_counts,
+                |o| &o.tangents,
+                [1.0, 0.0, 0.0, 1.0],
+            ),
+            texture_coordinates: merge_attributes(
+                objects,
+                &vertex_counts,
+                |o| &o.texture_coordinates,
+                [0.0; 4],
+            ),
+            color_sets: merge_attributes(objects, &vertex_counts, |o| &o.color_sets, [0.5; 4]),
+            bone_influences: weights_by_bone
+                .into_iter()
+                .map(|(bone_name, vertex_weights)| BoneInfluence {
+                    bone_name: bone_name.to_string(),
+                    vertex_weights,
+                })
+                .collect(),
+            ..first
+        })
+    }
+
+    /// Creates a new object with the triangles at `triangle_indices`
+    /// and only the vertices and weights used by those triangles.
+    /// The triangle at index `i` uses the vertex indices `3*i`, `3*i+1`, and `3*i+2`.
+    pub fn extract_triangles(
+        &self,
+        triangle_indices: &[usize],
+    ) -> Result<MeshObjectData, error::Error> {
+        let triangles = self.triangles()?;
+        let vertex_indices = triangle_indices
+            .iter()
+            .map(|t| {
+                triangles
+                    .get(*t)
+                    .ok_or(error::Error::TriangleIndexOutOfRange {
+                        triangle_index: *t,
+                        triangle_count: triangles.len(),
+                    })
+            })
+            .flatten_ok()
+            .map_ok(|v| *v as u32)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut object = self.clone();
+        object.vertex_indices = vertex_indices;
+        object.remove_unused_vertices()?;
+
+        // Separated parts often only use a few of the original bones.
+        object
+            .bone_influences
+            .retain(|i| !i.vertex_weights.is_empty());
+        Ok(object)
+    }
+
+    /// Splits the triangles into an object with the triangles where `predicate` returns `true`
+    /// and an object with the remaining triangles.
+    /// The `predicate` is called with the vertex indices for each triangle.
+    /**
+    ```rust no_run
+    # use ssbh_data::mesh_data::MeshObjectData;
+    # let object = MeshObjectData::default();
+    // Separate an accessory above a certain height into its own object.
+    let positions = object.positions[0].data.to_vec4_with_w(1.0);
+    let (hat, body) =
+        object.split_triangles(|t| t.iter().all(|v| positions[*v as usize][1] > 15.0))?;
+    # Ok::<(), Box<dyn std::error::Error>>(())
+    ```
+     */
+    pub fn split_triangles(
+        &self,
+        mut predicate: impl FnMut(&[u32; 3]) -> bool,
+    ) -> Result<(MeshObjectData, MeshObjectData), error::Error> {
+        let triangles = self.triangles()?;
+        let (selected, remaining): (Vec<_>, Vec<_>) =
+            (0..triangles.len()).partition(|i| predicate(&triangles[*i].map(|v| v as u32)));
+
+        Ok((
+            self.extract_triangles(&selected)?,
+            self.extract_triangles(&remaining)?,
+        ))
+    }
+
+    /// Splits the object into an object for each group of connected triangles.
+    /// The objects are sorted by their first triangle.
+    ///
+    /// Triangles are connected if they share a vertex or a vertex with the same position,
+    /// so pieces with UV seams or hard edges are not split apart.
+    pub fn split_connected_components(&self) -> Result<Vec<MeshObjectData>, error::Error> {
+        let triangles = self.triangles()?;
+
+        // Vertices with the same position are treated as the same vertex.
+        let vertex_keys = match self.positions.first() {
+            Some(attribute) => position_ids(&attribute.data),
+            None => (0..self.vertex_count()?).collect(),
+        };
+
+        let mut parents: Vec<_> = (0..vertex_keys.len()).collect();
+        for t in &triangles {
+            for v in &t[1..] {
+                union(&mut parents, vertex_keys[t[0]], vertex_keys[*v]);
+            }
+        }
+
+        let components = triangles
+            .iter()
+            .enumerate()
+            .map(|(i, t)| (find(&mut parents, vertex_keys[t[0]]), i))
+            .into_group_map();
+
+        components
+            .into_values()
+            .sorted_by_key(|triangles| triangles[0])
+            .map(|triangles| self.extract_triangles(&triangles))
+            .collect()
+    }
+}
+
+fn merge_attributes(
+    objects: &[MeshObjectData],
+    vertex_counts: &[usize],
+    attributes: impl Fn(&MeshObjectData) -> &Vec<AttributeData>,
+    default: [f32; 4],
+) -> Vec<AttributeData> {
+    let names: Vec<_> = objects
+        .iter()
+        .flat_map(|o| attributes(o).iter().map(|a| a.name.as_str()))
+        .unique()
+        .collect();
+
+    names
+        .into_iter()
+        .map(|name| {
+            let matching: Vec<_> = objects
+                .iter()
+                .map(|o| attributes(o).iter().find(|a| a.name == name))
+                .collect();
+
+            let component_count = matching
+                .iter()
+                .flatten()
+                .map(|a| component_count(&a.data))
+                .max()
+                .unwrap_or(4);
+
+            let mut values = Vec::new();
+            for (attribute, vertex_count) in matching.iter().zip(vertex_counts) {
+                match attribute {
+                    Some(a) => values.extend((0..a.data.len()).map(|i| {
+                        let mut value = default;
+                        for (v, c) in value.iter_mut().zip(a.data.element(i)) {
+                            *v = *c;
+                        }
+                        value
+                    })),
+                    None => values.extend(std::iter::repeat_n(default, *vertex_count)),
+                }
+            }
+
+            AttributeData {
+                name: name.to_string(),
+                data: match component_count {
+                    2 => VectorData::Vector2(values.iter().map(|[x, y, _, _]| [*x, *y]).collect()),
+                    3 => VectorData::Vector3(
+                        values.iter().map(|[x, y, z, _]| [*x, *y, *z]).collect(),
+                    ),
+                    _ => VectorData::Vector4(values),
+                },
+                // Use the first data type with the right component count.
+                data_type: matching
+                    .iter()
+                    .flatten()
+                    .find(|a| component_count == self::component_count(&a.data))
+                    .and_then(|a| a.data_type),
+            }
+        })
+        .collect()
+}
+
+fn component_count(data: &VectorData) -> usize {
+    match data {
+        VectorData::Vector2(_) => 2,
+        VectorData::Vector3(_) => 3,
+        VectorData::Vector4(_) => 4,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn attribute(name: &str, data: VectorData) -> AttributeData {
+        AttributeData::new(name, data)
+    }
+
+    fn influence(bone_name: &str, vertex_indices: &[u32]) -> BoneInfluence {
+        BoneInfluence {
+            bone_name: bone_name.into(),
+            vertex_weights: vertex_indices
+                .iter()
+                .map(|i| VertexWeight {
+                    vertex_index: *i,
+                    vertex_weight: 1.0,
+                })
+                .collect(),
+        }
+    }
+
+    fn data(attributes: &[AttributeData]) -> Vec<(&str, &VectorData)> {
+        attributes
+            .iter()
+            .map(|a| (a.name.as_str(), &a.data))
+            .collect()
+    }
+
+    fn weights(object: &MeshObjectData) -> Vec<(&str, Vec<u32>)> {
+        object
+            .bone_influences
+            .iter()
+            .map(|i| {
+                (
+                    i.bone_name.as_str(),
+                    i.vertex_weights.iter().map(|w| w.vertex_index).collect(),
+                )
+            })
+            .collect()
+    }
+
+    /// Two separate triangles with a UV seam on the first triangle's shared position.
+    fn two_islands() -> MeshObjectData {
+        MeshObjectData {
+            name: "a".into(),
+            vertex_indices: vec![0, 1, 2, 3, 1, 4, 5, 6, 7],
+            positions: vec![attribute(
+                "Position0",
+                VectorData::Vector3(vec![
+                    [0.0, 0.0, 0.0],
+                    [1.0, 0.0, 0.0],
+                    [0.0, 1.0, 0.0],
+                    [0.0, 1.0, 0.0],
+                    [1.0, 1.0, 0.0],
+                    [5.0, 0.0, 0.0],
+                    [6.0, 0.0, 0.0],
+                    [5.0, 1.0, 0.0],
+                ]),
+            )],
+            bone_influences: vec![influence("A", &[0, 1, 2, 3, 4]), influence("B", &[5, 6, 7])],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn merge_pads_missing_attributes() {
+        let a = MeshObjectData {
+            name: "a".into(),
+            subindex: 1,
+            vertex_indices: vec![0, 1, 0],
+            positions: vec![attribute(
+                "Position0",
+                VectorData::Vector3(vec![[0.0; 3], [1.0; 3]]),
+            )],
+            color_sets: vec![attribute(
+                "colorSet1",
+                VectorData::Vector4(vec![[1.0; 4], [1.0; 4]]),
+            )],
+            bone_influences: vec![influence("A", &[0, 1])],
+            ..Default::default()
+        };
+        let b = MeshObjectData {
+            name: "b".into(),
+            vertex_indices: vec![0, 0, 0],
+            positions: vec![attribute(
+                "Position0",
+                VectorData::Vector4(vec![[2.0, 2.0, 2.0, 0.0]]),
+            )],
+            texture_coordinates: vec![attribute("map1", VectorData::Vector2(vec![[0.5, 0.5]]))],
+            bone_influences: vec![influence("B", &[0]), influence("A", &[0])],
+            ..Default::default()
+        };
+
+        let merged = MeshObjectData::merge(&[a, b]).unwrap();
+        assert_eq!("a", merged.name);
+        assert_eq!(1, merged.subindex);
+        assert_eq!(vec![0, 1, 0, 2, 2, 2], merged.vertex_indices);
+        assert_eq!(
+            vec![(
+                "Position0",
+                &VectorData::Vector4(vec![
+                    [0.0, 0.0, 0.0, 0.0],
+                    [1.0, 1.0, 1.0, 0.0],
+                    [2.0, 2.0, 2.0, 0.0]
+                ])
+            )],
+            data(&merged.positions)
+        );
+        assert_eq!(
+            vec![(
+                "map1",
+                &VectorData::Vector2(vec![[0.0, 0.0], [0.0, 0.0], [0.5, 0.5]])
+            )],
+            data(&merged.texture_coordinates)
+        );
+        assert_eq!(
+            vec![(
+                "colorSet1",
+                &VectorData::Vector4(vec![[1.0; 4], [1.0; 4], [0.5; 4]])
+            )],
+            data(&merged.color_sets)
+        );
+        assert_eq!(vec![("A", vec![0, 1, 2]), ("B", vec![2])], weights(&merged));
+        assert_eq!(3, merged.vertex_count().unwrap());
+    }
+
+    #[test]
+    fn merge_empty() {
+        let merged = MeshObjectData::merge(&[]).unwrap();
+        assert!(merged.vertex_indices.is_empty());
+        assert!(merged.positions.is_empty());
+    }
+
+    #[test]
+    fn extract_triangles_removes_unused_vertices() {
+        let object = two_islands().extract_triangles(&[2]).unwrap();
+        assert_eq!(vec![0, 1, 2], object.vertex_indices);
+        assert_eq!(
+            VectorData::Vector3(vec![[5.0, 0.0, 0.0], [6.0, 0.0, 0.0], [5.0, 1.0, 0.0]]),
+            object.positions[0].data
+        );
+        assert_eq!(vec![("B", vec![0, 1, 2])], weights(&object));
+    }
+
+    #[test]
+    fn extract_triangles_out_of_range() {
+        let result = two_islands().extract_triangles(&[3]);
+        assert!(matches!(
+            result,
+            Err(error::Error::TriangleIndexOutOfRange {
+                triangle_index: 3,
+                triangle_count: 3
+            })
+        ));
+    }
+
+    #[test]
+    fn split_triangles_predicate() {
+        let object = two_islands();
+        let (selected, remaining) = object.split_triangles(|t| t.contains(&1)).unwrap();
+        assert_eq!(vec![0, 1, 2, 3, 1, 4], selected.vertex_indices);
+        assert_eq!(vec![("A", vec![0, 1, 2, 3, 4])], weights(&selected));
+        assert_eq!(vec![0, 1, 2], remaining.vertex_indices);
+        assert_eq!(vec![("B", vec![0, 1, 2])], weights(&remaining));
+    }
+
+    #[test]
+    fn split_connected_components_across_seams() {
+        let components = two_islands().split_connected_components().unwrap();
+        assert_eq!(2, components.len());
+        assert_eq!(vec![0, 1, 2, 3, 1, 4], components[0].vertex_indices);
+        assert_eq!(5, components[0].vertex_count().unwrap());
+        assert_eq!(vec![0, 1, 2], components[1].vertex_indices);
+        assert_eq!(3, components[1].vertex_count().unwrap());
+
+        // Merging the components again should preserve the vertices and triangles.
+        let merged = MeshObjectData::merge(&components).unwrap();
+        assert_eq!(two_islands().vertex_indices, merged.vertex_indices);
+        assert_eq!(data(&two_islands().positions), data(&merged.positions));
+    }
+
+    #[test]
+    fn split_connected_components_negative_zero() {
+        // The seam vertices only differ in the sign of zero.
+        let object = MeshObjectData {
+            name: "a".into(),
+            vertex_indices: vec![0, 1, 2, 3, 4, 5],
+            positions: vec![attribute(
+                "Position0",
+                VectorData::Vector3(vec![
+                    [0.0, 0.0, 0.0],
+                    [1.0, 0.0, 0.0],
+                    [0.0, 1.0, 0.0],
+                    [-0.0, 0.0, -0.0],
+                    [0.0, -1.0, 0.0],
+                    [-1.0, 0.0, 0.0],
+                ]),
+            )],
+            ..Default::default()
+        };
+        let components = object.split_connected_components().unwrap();
+        assert_eq!(1, components.len());
+        assert_eq!(vec![0, 1, 2, 3, 4, 5], components[0].vertex_indices);
+    }
+}
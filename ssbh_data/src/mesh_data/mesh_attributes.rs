@@ -1,6 +1,7 @@
 use super::vector_data::*;
 use super::{
-    AttributeData, AttributeDataTypeV10Ext, AttributeDataTypeV8Ext, MeshObjectData, VectorData,
+    AttributeData, AttributeDataType, AttributeDataTypeV10Ext, AttributeDataTypeV8Ext,
+    MeshObjectData, VectorData,
 };
 use binrw::io::{Seek, Write};
 use itertools::Itertools;
@@ -139,80 +140,136 @@ pub fn create_attributes_v10(data: &MeshObjectData) -> MeshAttributes<AttributeV
     )
 }
 
-fn get_attributes<U: Copy, V, F: Fn(&VectorData) -> V>(
+fn get_attributes<
+    U: Copy,
+    V,
+    F: Fn(&VectorData) -> V,
+    F2: Fn(&VectorData, AttributeDataType) -> Option<V>,
+>(
     attributes: &[AttributeData],
     usage: U,
-    f: F,
+    infer_data: F,
+    convert_data: F2,
 ) -> impl Iterator<Item = (&str, usize, U, V)> {
     // Assign the appropriate name, usage, and subindex.
     // This avoids having to keep attributes grouped by usage.
-    attributes
-        .iter()
-        .enumerate()
-        .map(move |(i, a)| (a.name.as_str(), i, usage, f(&a.data)))
+    attributes.iter().enumerate().map(move |(i, a)| {
+        // Use the specified data type if possible to preserve the original buffer layout.
+        // Data types not supported by this version or the data's component count use the inferred type instead.
+        let data = a
+            .data_type
+            .and_then(|data_type| convert_data(&a.data, data_type))
+            .unwrap_or_else(|| infer_data(&a.data));
+        (a.name.as_str(), i, usage, data)
+    })
 }
 
 fn get_positions_v10(
     attributes: &[AttributeData],
     usage: AttributeUsageV9,
 ) -> impl Iterator<Item = (&str, usize, AttributeUsageV9, VectorDataV10)> {
-    get_attributes(attributes, usage, VectorDataV10::from_positions)
+    get_attributes(
+        attributes,
+        usage,
+        VectorDataV10::from_positions,
+        VectorDataV10::from_data_type,
+    )
 }
 
 fn get_vectors_v10(
     attributes: &[AttributeData],
     usage: AttributeUsageV9,
 ) -> impl Iterator<Item = (&str, usize, AttributeUsageV9, VectorDataV10)> {
-    get_attributes(attributes, usage, VectorDataV10::from_vectors)
+    get_attributes(
+        attributes,
+        usage,
+        VectorDataV10::from_vectors,
+        VectorDataV10::from_data_type,
+    )
 }
 
 fn get_colors_v10(
     attributes: &[AttributeData],
     usage: AttributeUsageV9,
 ) -> impl Iterator<Item = (&str, usize, AttributeUsageV9, VectorDataV10)> {
-    get_attributes(attributes, usage, VectorDataV10::from_colors)
+    get_attributes(
+        attributes,
+        usage,
+        VectorDataV10::from_colors,
+        VectorDataV10::from_data_type,
+    )
 }
 
 fn get_positions_v9(
     attributes: &[AttributeData],
     usage: AttributeUsageV9,
 ) -> impl Iterator<Item = (&str, usize, AttributeUsageV9, VectorDataV8)> {
-    get_attributes(attributes, usage, VectorDataV8::from_positions)
+    get_attributes(
+        attributes,
+        usage,
+        VectorDataV8::from_positions,
+        VectorDataV8::from_data_type,
+    )
 }
 
 fn get_vectors_v9(
     attributes: &[AttributeData],
     usage: AttributeUsageV9,
 ) -> impl Iterator<Item = (&str, usize, AttributeUsageV9, VectorDataV8)> {
-    get_attributes(attributes, usage, VectorDataV8::from_vectors)
+    get_attributes(
+        attributes,
+        usage,
+        VectorDataV8::from_vectors,
+        VectorDataV8::from_data_type,
+    )
 }
 
 fn get_colors_v9(
     attributes: &[AttributeData],
     usage: AttributeUsageV9,
 ) -> impl Iterator<Item = (&str, usize, AttributeUsageV9, VectorDataV8)> {
-    get_attributes(attributes, usage, VectorDataV8::from_colors)
+    get_attributes(
+        attributes,
+        usage,
+        VectorDataV8::from_colors,
+        VectorDataV8::from_data_type,
+    )
 }
 
 fn get_positions_v8(
     attributes: &[AttributeData],
     usage: AttributeUsageV8,
 ) -> impl Iterator<Item = (&str, usize, AttributeUsageV8, VectorDataV8)> {
-    get_attributes(attributes, usage, VectorDataV8::from_positions)
+    get_attributes(
+        attributes,
+        usage,
+        VectorDataV8::from_positions,
+        VectorDataV8::from_data_type,
+    )
 }
 
 fn get_vectors_v8(
     attributes: &[AttributeData],
     usage: AttributeUsageV8,
 ) -> impl Iterator<Item = (&str, usize, AttributeUsageV8, VectorDataV8)> {
-    get_attributes(attributes, usage, VectorDataV8::from_vectors)
+    get_attributes(
+        attributes,
+        usage,
+        VectorDataV8::from_vectors,
+        VectorDataV8::from_data_type,
+    )
 }
 
 fn get_colors_v8(
     attributes: &[AttributeData],
     usage: AttributeUsageV8,
 ) -> impl Iterator<Item = (&str, usize, AttributeUsageV8, VectorDataV8)> {
-    get_attributes(attributes, usage, VectorDataV8::from_colors)
+    get_attributes(
+        attributes,
+        usage,
+        VectorDataV8::from_colors,
+        VectorDataV8::from_data_type,
+    )
 }
 
 fn create_buffer_attributes<
@@ -518,37 +575,16 @@ mod tests {
     fn create_attributes_mesh_v1_8() {
         let data = MeshObjectData {
             name: "name".into(),
-            positions: vec![AttributeData {
-                name: "p0".into(),
-                data: VectorData::Vector3(Vec::new()),
-            }],
-            normals: vec![AttributeData {
-                name: "n0".into(),
-                data: VectorData::Vector3(Vec::new()),
-            }],
-            tangents: vec![AttributeData {
-                name: "t0".into(),
-                data: VectorData::Vector4(Vec::new()),
-            }],
+            positions: vec![AttributeData::new("p0", VectorData::Vector3(Vec::new()))],
+            normals: vec![AttributeData::new("n0", VectorData::Vector3(Vec::new()))],
+            tangents: vec![AttributeData::new("t0", VectorData::Vector4(Vec::new()))],
             texture_coordinates: vec![
-                AttributeData {
-                    name: "firstUv".into(),
-                    data: VectorData::Vector2(Vec::new()),
-                },
-                AttributeData {
-                    name: "secondUv".into(),
-                    data: VectorData::Vector2(Vec::new()),
-                },
+                AttributeData::new("firstUv", VectorData::Vector2(Vec::new())),
+                AttributeData::new("secondUv", VectorData::Vector2(Vec::new())),
             ],
             color_sets: vec![
-                AttributeData {
-                    name: "color1".into(),
-                    data: VectorData::Vector4(Vec::new()),
-                },
-                AttributeData {
-                    name: "color2".into(),
-                    data: VectorData::Vector4(Vec::new()),
-                },
+                AttributeData::new("color1", VectorData::Vector4(Vec::new())),
+                AttributeData::new("color2", VectorData::Vector4(Vec::new())),
             ],
             ..MeshObjectData::default()
         };
@@ -651,47 +687,20 @@ mod tests {
     fn create_attributes_mesh_v1_9() {
         let data = MeshObjectData {
             name: "name".into(),
-            positions: vec![AttributeData {
-                name: "p0".into(),
-                data: VectorData::Vector3(Vec::new()),
-            }],
-            normals: vec![AttributeData {
-                name: "n0".into(),
-                data: VectorData::Vector3(Vec::new()),
-            }],
+            positions: vec![AttributeData::new("p0", VectorData::Vector3(Vec::new()))],
+            normals: vec![AttributeData::new("n0", VectorData::Vector3(Vec::new()))],
             binormals: vec![
-                AttributeData {
-                    name: "b1".into(),
-                    data: VectorData::Vector3(Vec::new()),
-                },
-                AttributeData {
-                    name: "b2".into(),
-                    data: VectorData::Vector3(Vec::new()),
-                },
+                AttributeData::new("b1", VectorData::Vector3(Vec::new())),
+                AttributeData::new("b2", VectorData::Vector3(Vec::new())),
             ],
-            tangents: vec![AttributeData {
-                name: "t0".into(),
-                data: VectorData::Vector4(Vec::new()),
-            }],
+            tangents: vec![AttributeData::new("t0", VectorData::Vector4(Vec::new()))],
             texture_coordinates: vec![
-                AttributeData {
-                    name: "firstUv".into(),
-                    data: VectorData::Vector2(Vec::new()),
-                },
-                AttributeData {
-                    name: "secondUv".into(),
-                    data: VectorData::Vector2(Vec::new()),
-                },
+                AttributeData::new("firstUv", VectorData::Vector2(Vec::new())),
+                AttributeData::new("secondUv", VectorData::Vector2(Vec::new())),
             ],
             color_sets: vec![
-                AttributeData {
-                    name: "color1".into(),
-                    data: VectorData::Vector4(Vec::new()),
-                },
-                AttributeData {
-                    name: "color2".into(),
-                    data: VectorData::Vector4(Vec::new()),
-                },
+                AttributeData::new("color1", VectorData::Vector4(Vec::new())),
+                AttributeData::new("color2", VectorData::Vector4(Vec::new())),
             ],
             ..MeshObjectData::default()
         };
@@ -839,47 +848,20 @@ mod tests {
             subindex: 0,
             parent_bone_name: "".into(),
             vertex_indices: Vec::new(),
-            positions: vec![AttributeData {
-                name: "p0".into(),
-                data: VectorData::Vector3(Vec::new()),
-            }],
-            normals: vec![AttributeData {
-                name: "n0".into(),
-                data: VectorData::Vector3(Vec::new()),
-            }],
+            positions: vec![AttributeData::new("p0", VectorData::Vector3(Vec::new()))],
+            normals: vec![AttributeData::new("n0", VectorData::Vector3(Vec::new()))],
             binormals: vec![
-                AttributeData {
-                    name: "b1".into(),
-                    data: VectorData::Vector3(Vec::new()),
-                },
-                AttributeData {
-                    name: "b2".into(),
-                    data: VectorData::Vector3(Vec::new()),
-                },
+                AttributeData::new("b1", VectorData::Vector3(Vec::new())),
+                AttributeData::new("b2", VectorData::Vector3(Vec::new())),
             ],
-            tangents: vec![AttributeData {
-                name: "t0".into(),
-                data: VectorData::Vector4(Vec::new()),
-            }],
+            tangents: vec![AttributeData::new("t0", VectorData::Vector4(Vec::new()))],
             texture_coordinates: vec![
-                AttributeData {
-                    name: "firstUv".into(),
-                    data: VectorData::Vector2(Vec::new()),
-                },
-                AttributeData {
-                    name: "secondUv".into(),
-                    data: VectorData::Vector2(Vec::new()),
-                },
+                AttributeData::new("firstUv", VectorData::Vector2(Vec::new())),
+                AttributeData::new("secondUv", VectorData::Vector2(Vec::new())),
             ],
             color_sets: vec![
-                AttributeData {
-                    name: "color1".into(),
-                    data: VectorData::Vector4(Vec::new()),
-                },
-                AttributeData {
-                    name: "color2".into(),
-                    data: VectorData::Vector4(Vec::new()),
-                },
+                AttributeData::new("color1", VectorData::Vector4(Vec::new())),
+                AttributeData::new("color2", VectorData::Vector4(Vec::new())),
             ],
             bone_influences: Vec::new(),
             sort_bias: 0,
@@ -1160,4 +1142,82 @@ mod tests {
             )
         );
     }
+
+    #[test]
+    fn create_attributes_v10_pinned_data_types() {
+        let data = MeshObjectData {
+            name: "name".into(),
+            texture_coordinates: vec![AttributeData {
+                name: "map1".into(),
+                data: VectorData::Vector2(vec![[0.0, 1.0]]),
+                data_type: Some(AttributeDataType::Float2),
+            }],
+            color_sets: vec![AttributeData {
+                name: "colorSet1".into(),
+                data: VectorData::Vector4(vec![[0.0, 1.0, 2.0, 3.0]]),
+                data_type: Some(AttributeDataType::HalfFloat4),
+            }],
+            ..Default::default()
+        };
+
+        let MeshAttributes {
+            buffer_info,
+            attributes,
+            ..
+        } = create_attributes_v10(&data);
+
+        // The inferred types would be HalfFloat2 and Byte4.
+        assert_eq!(16, buffer_info[1].0);
+        assert_eq!(
+            vec![
+                AttributeDataTypeV10::Float2,
+                AttributeDataTypeV10::HalfFloat4
+            ],
+            attributes
+                .elements
+                .iter()
+                .map(|a| a.data_type)
+                .collect_vec()
+        );
+    }
+
+    #[test]
+    fn create_attributes_v10_pinned_data_type_component_count() {
+        let data = MeshObjectData {
+            name: "name".into(),
+            normals: vec![AttributeData {
+                name: "Normal0".into(),
+                data: VectorData::Vector3(Vec::new()),
+                data_type: Some(AttributeDataType::HalfFloat4),
+            }],
+            ..Default::default()
+        };
+
+        // The data type doesn't match the component count, so the inferred type is used.
+        let MeshAttributes { attributes, .. } = create_attributes_v10(&data);
+        assert_eq!(
+            AttributeDataTypeV10::Float3,
+            attributes.elements[0].data_type
+        );
+    }
+
+    #[test]
+    fn create_attributes_v8_unsupported_data_type() {
+        let data = MeshObjectData {
+            name: "name".into(),
+            texture_coordinates: vec![AttributeData {
+                name: "map1".into(),
+                data: VectorData::Vector2(Vec::new()),
+                data_type: Some(AttributeDataType::HalfFloat2),
+            }],
+            ..Default::default()
+        };
+
+        // Version 1.8 does not support HalfFloat2, so the inferred type is used.
+        let MeshAttributes { attributes, .. } = create_attributes_v8(&data);
+        assert_eq!(
+            AttributeDataTypeV8::Float2,
+            attributes.elements[0].data_type
+        );
+    }
 }
@@ -0,0 +1,578 @@
+use std::collections::HashMap;
+
+use super::{error, float_key, BoneInfluence, MeshObjectData, VertexWeight};
+
+// Parameters for the triangle reordering algorithm described in
+// "Linear-Speed Vertex Cache Optimisation" by Tom Forsyth.
+const CACHE_SIZE: usize = 32;
+const LAST_TRIANGLE_SCORE: f32 = 0.75;
+const CACHE_DECAY_POWER: f32 = 1.5;
+const VALENCE_BOOST_SCALE: f32 = 2.0;
+const VALENCE_BOOST_POWER: f32 = 0.5;
+
+impl MeshObjectData {
+    /// Merges vertices whose attribute values and bone influences are all within `tolerance`
+    /// and updates [vertex_indices](#structfield.vertex_indices) to reference the remaining vertices.
+    /// Returns the number of vertices removed.
+    ///
+    /// Vertices are only merged if they have influences from the same bones.
+    /// Every [AttributeData](super::AttributeData) is compared,
+    /// so vertices along UV seams or hard edges are preserved.
+    /// A `tolerance` of `0.0` only merges exact duplicates.
+    ///
+    /// Welding an unindexed mesh with many duplicate vertices can reduce the vertex count enough
+    /// for the vertex indices to be stored using [u16] when saving.
+    /**
+    ```rust
+    # use ssbh_data::mesh_data::{AttributeData, MeshObjectData, VectorData};
+    let mut object = MeshObjectData {
+        vertex_indices: vec![0, 1, 2, 2, 1, 3],
+        positions: vec![AttributeData::new("Position0", VectorData::Vector3(vec![
+                [0.0, 0.0, 0.0],
+                [1.0, 0.0, 0.0],
+                [0.0, 1.0, 0.0],
+                [0.0, 1.00001, 0.0],
+            ]))],
+        ..Default::default()
+    };
+
+    assert_eq!(1, object.weld_vertices(0.001)?);
+    assert_eq!(vec![0, 1, 2, 2, 1, 2], object.vertex_indices);
+    assert_eq!(3, object.vertex_count()?);
+    # Ok::<(), Box<dyn std::error::Error>>(())
+    ```
+     */
+    pub fn weld_vertices(&mut self, tolerance: f32) -> Result<usize, error::Error> {
+        let vertex_count = self.validated_vertex_count()?;
+        let influences = vertex_influences(&self.bone_influences, vertex_count);
+
+        // Only compare vertices in the same or neighboring grid cells.
+        // The cells are at least as large as the tolerance,
+        // so vertices within the tolerance always have adjacent cells.
+        // Exact duplicates have the same key, so a tolerance of 0.0 only checks the same cell.
+        let key_attribute = self.positions.first().or_else(|| self.attributes().next());
+        let cell = |vertex: usize| {
+            let mut cell = [0i64; 3];
+            if let Some(attribute) = key_attribute {
+                for (c, value) in cell.iter_mut().zip(attribute.data.element(vertex)) {
+                    *c = if tolerance > 0.0 {
+                        (value / tolerance).floor() as i64
+                    } else {
+                        float_key(*value) as i64
+                    };
+                }
+            }
+            cell
+        };
+        let offset = if tolerance > 0.0 { 1 } else { 0 };
+
+        let attributes: Vec<_> = self.attributes().map(|a| &a.data).collect();
+        let vertices_equal = |a: usize, b: usize| {
+            attributes.iter().all(|data| {
+                data.element(a)
+                    .iter()
+                    .zip(data.element(b))
+                    .all(|(x, y)| (x - y).abs() <= tolerance)
+            }) && influences[a].len() == influences[b].len()
+                && influences[a].iter().zip(&influences[b]).all(
+                    |((bone_a, weight_a), (bone_b, weight_b))| {
+                        bone_a == bone_b && (weight_a - weight_b).abs() <= tolerance
+                    },
+                )
+        };
+
+        let mut cells: HashMap<[i64; 3], Vec<usize>> = HashMap::new();
+        let mut new_to_old = Vec::new();
+        let mut old_to_new = vec![None; vertex_count];
+        for vertex in 0..vertex_count {
+            let [x, y, z] = cell(vertex);
+            let neighbors = (-offset..=offset).flat_map(|dx| {
+                (-offset..=offset)
+                    .flat_map(move |dy| (-offset..=offset).map(move |dz| [x + dx, y + dy, z + dz]))
+            });
+
+            let representative = neighbors
+                .filter_map(|neighbor| cells.get(&neighbor))
+                .flatten()
+                .find(|r| vertices_equal(vertex, **r))
+                .copied();
+
+            match representative {
+                Some(r) => old_to_new[vertex] = old_to_new[r],
+                None => {
+                    old_to_new[vertex] = Some(new_to_old.len() as u32);
+                    new_to_old.push(vertex);
+                    cells.entry([x, y, z]).or_default().push(vertex);
+                }
+            }
+        }
+
+        self.remap_vertices(&new_to_old, &old_to_new);
+        Ok(vertex_count - new_to_old.len())
+    }
+
+    /// Removes vertices not referenced by any of the [vertex_indices](#structfield.vertex_indices)
+    /// while preserving the order of the remaining vertices.
+    /// Returns the number of vertices removed.
+    pub fn remove_unused_vertices(&mut self) -> Result<usize, error::Error> {
+        let vertex_count = self.validated_vertex_count()?;
+
+        let mut is_used = vec![false; vertex_count];
+        for i in &self.vertex_indices {
+            is_used[*i as usize] = true;
+        }
+
+        let mut new_to_old = Vec::new();
+        let mut old_to_new = vec![None; vertex_count];
+        for (vertex, _) in is_used.iter().enumerate().filter(|(_, used)| **used) {
+            old_to_new[vertex] = Some(new_to_old.len() as u32);
+            new_to_old.push(vertex);
+        }
+
+        self.remap_vertices(&new_to_old, &old_to_new);
+        Ok(vertex_count - new_to_old.len())
+    }
+
+    /// Reorders the triangles in [vertex_indices](#structfield.vertex_indices)
+    /// to improve the hit rate of the post transform vertex cache when rendering.
+    /// The vertex data and the set of triangles are unchanged.
+    pub fn optimize_vertex_cache(&mut self) -> Result<(), error::Error> {
+        let vertex_count = self.validated_vertex_count()?;
+        let triangles = self.triangles()?;
+        self.vertex_indices = optimize_triangle_order(&triangles, vertex_count);
+        Ok(())
+    }
+
+    /// Reorders the vertex data to match the order vertices are first referenced
+    /// by [vertex_indices](#structfield.vertex_indices) to improve memory locality when rendering.
+    /// Unused vertices are moved to the end.
+    /// This should be applied after [optimize_vertex_cache](#method.optimize_vertex_cache).
+    pub fn optimize_vertex_fetch(&mut self) -> Result<(), error::Error> {
+        let vertex_count = self.validated_vertex_count()?;
+
+        let mut new_to_old = Vec::with_capacity(vertex_count);
+        let mut old_to_new = vec![None; vertex_count];
+        let unused: Vec<_> = (0..vertex_count as u32).collect();
+        for i in self.vertex_indices.iter().chain(&unused) {
+            let vertex = *i as usize;
+            if old_to_new[vertex].is_none() {
+                old_to_new[vertex] = Some(new_to_old.len() as u32);
+                new_to_old.push(vertex);
+            }
+        }
+
+        self.remap_vertices(&new_to_old, &old_to_new);
+        Ok(())
+    }
+
+    /// Calculates the vertex count and checks that all vertex indices are in range.
+    pub(crate) fn validated_vertex_count(&self) -> Result<usize, error::Error> {
+        let vertex_count = self.vertex_count()?;
+        match self
+            .vertex_indices
+            .iter()
+            .find(|i| **i as usize >= vertex_count)
+        {
+            Some(i) => Err(error::Error::VertexIndexOutOfRange {
+                vertex_index: *i as usize,
+                vertex_count,
+            }),
+            None => Ok(vertex_count),
+        }
+    }
+
+    /// Groups [vertex_indices](#structfield.vertex_indices) into triangles
+    /// after checking that all vertex indices are in range.
+    pub(crate) fn triangles(&self) -> Result<Vec<[usize; 3]>, error::Error> {
+        self.validated_vertex_count()?;
+        if !self.vertex_indices.len().is_multiple_of(3) {
+            return Err(error::Error::NonTriangulatedFaces {
+                vertex_index_count: self.vertex_indices.len(),
+            });
+        }
+
+        Ok(self
+            .vertex_indices
+            .chunks_exact(3)
+            .map(|t| [t[0] as usize, t[1] as usize, t[2] as usize])
+            .collect())
+    }
+
+    /// Rebuilds the vertices so that new vertex `i` is old vertex `new_to_old[i]`.
+    /// Indices and weights for old vertices without a new vertex are removed.
+    fn remap_vertices(&mut self, new_to_old: &[usize], old_to_new: &[Option<u32>]) {
+        for attribute in self.attributes_mut() {
+            attribute.data = attribute.data.select(new_to_old);
+        }
+
+        self.vertex_indices = self
+            .vertex_indices
+            .iter()
+            .filter_map(|i| old_to_new[*i as usize])
+            .collect();
+
+        // Merged vertices have matching weights, so only keep the weights of the vertex that was kept.
+        for influence in &mut self.bone_influences {
+            influence.vertex_weights = influence
+                .vertex_weights
+                .iter()
+                .filter_map(|w| {
+                    let new = old_to_new.get(w.vertex_index as usize).copied().flatten()?;
+                    (new_to_old[new as usize] == w.vertex_index as usize).then_some(VertexWeight {
+                        vertex_index: new,
+                        vertex_weight: w.vertex_weight,
+                    })
+                })
+                .collect();
+        }
+    }
+}
+
+/// The (bone name, weight) pairs for each vertex sorted by bone name.
+fn vertex_influences(influences: &[BoneInfluence], vertex_count: usize) -> Vec<Vec<(&str, f32)>> {
+    let mut vertex_influences = vec![Vec::new(); vertex_count];
+    for influence in influences {
+        for weight in &influence.vertex_weights {
+            if let Some(v) = vertex_influences.get_mut(weight.vertex_index as usize) {
+                v.push((influence.bone_name.as_str(), weight.vertex_weight));
+            }
+        }
+    }
+
+    for v in &mut vertex_influences {
+        v.sort_by(|a, b| a.0.cmp(b.0));
+    }
+    vertex_influences
+}
+
+fn vertex_score(cache_position: Option<usize>, remaining_triangles: usize) -> f32 {
+    if remaining_triangles == 0 {
+        return -1.0;
+    }
+
+    let cache_score = match cache_position {
+        // The most recent triangle's vertices are scored equally regardless of order.
+        Some(p) if p < 3 => LAST_TRIANGLE_SCORE,
+        Some(p) => (1.0 - (p - 3) as f32 / (CACHE_SIZE - 3) as f32).powf(CACHE_DECAY_POWER),
+        None => 0.0,
+    };
+
+    // Prioritize vertices with few remaining triangles to avoid leaving isolated triangles.
+    let valence_boost =
+        VALENCE_BOOST_SCALE * (remaining_triangles as f32).powf(-VALENCE_BOOST_POWER);
+    cache_score + valence_boost
+}
+
+fn optimize_triangle_order(triangles: &[[usize; 3]], vertex_count: usize) -> Vec<u32> {
+    let mut vertex_triangles = vec![Vec::new(); vertex_count];
+    for (t, triangle) in triangles.iter().enumerate() {
+        for v in triangle {
+            vertex_triangles[*v].push(t);
+        }
+    }
+
+    let mut cache_positions = vec![None; vertex_count];
+    let mut vertex_scores: Vec<_> = vertex_triangles
+        .iter()
+        .map(|t| vertex_score(None, t.len()))
+        .collect();
+    let triangle_score =
+        |t: &[usize; 3], scores: &[f32]| -> f32 { t.iter().map(|v| scores[*v]).sum() };
+    let mut triangle_scores: Vec<_> = triangles
+        .iter()
+        .map(|t| triangle_score(t, &vertex_scores))
+        .collect();
+
+    let mut is_added = vec![false; triangles.len()];
+    let mut cache: Vec<usize> = Vec::with_capacity(CACHE_SIZE + 3);
+    let mut result = Vec::with_capacity(triangles.len() * 3);
+    let mut next_unadded = 0;
+
+    let mut best_triangle = (0..triangles.len()).max_by(|a, b| {
+        triangle_scores[*a]
+            .partial_cmp(&triangle_scores[*b])
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+
+    while let Some(best) = best_triangle {
+        is_added[best] = true;
+        let triangle = triangles[best];
+        result.extend(triangle.iter().map(|v| *v as u32));
+
+        for v in triangle {
+            vertex_triangles[v].retain(|t| *t != best);
+        }
+
+        // Move the triangle's vertices to the front of the cache.
+        let mut new_cache: Vec<usize> = Vec::with_capacity(CACHE_SIZE + 3);
+        for v in triangle.iter().chain(&cache) {
+            if !new_cache.contains(v) {
+                new_cache.push(*v);
+            }
+        }
+        let evicted = new_cache.split_off(new_cache.len().min(CACHE_SIZE));
+        for v in &evicted {
+            cache_positions[*v] = None;
+        }
+        for (i, v) in new_cache.iter().enumerate() {
+            cache_positions[*v] = Some(i);
+        }
+        cache = new_cache;
+
+        for v in cache.iter().chain(&evicted) {
+            vertex_scores[*v] = vertex_score(cache_positions[*v], vertex_triangles[*v].len());
+        }
+        for v in cache.iter().chain(&evicted) {
+            for t in &vertex_triangles[*v] {
+                triangle_scores[*t] = triangle_score(&triangles[*t], &vertex_scores);
+            }
+        }
+
+        // Only search triangles using cached vertices to keep the algorithm linear.
+        best_triangle = cache
+            .iter()
+            .flat_map(|v| vertex_triangles[*v].iter().copied())
+            .max_by(|a, b| {
+                triangle_scores[*a]
+                    .partial_cmp(&triangle_scores[*b])
+                    .unwrap_or(std::cmp::Ordering::Equal)
+            })
+            .or_else(|| {
+                while next_unadded < triangles.len() && is_added[next_unadded] {
+                    next_unadded += 1;
+                }
+                (next_unadded < triangles.len()).then_some(next_unadded)
+            });
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mesh_data::{AttributeData, VectorData};
+
+    fn position(data: Vec<[f32; 3]>) -> Vec<AttributeData> {
+        vec![AttributeData::new("Position0", VectorData::Vector3(data))]
+    }
+
+    fn sorted_triangles(vertex_indices: &[u32]) -> Vec<[u32; 3]> {
+        let mut triangles: Vec<_> = vertex_indices
+            .chunks_exact(3)
+            .map(|t| [t[0], t[1], t[2]])
+            .collect();
+        triangles.sort();
+        triangles
+    }
+
+    #[test]
+    fn weld_vertices_unindexed_quad() {
+        let mut object = MeshObjectData {
+            vertex_indices: vec![0, 1, 2, 3, 4, 5],
+            positions: position(vec![
+                [0.0, 0.0, 0.0],
+                [1.0, 0.0, 0.0],
+                [0.0, 1.0, 0.0],
+                [0.0, 1.0, 0.0],
+                [1.0, 0.0, 0.0],
+                [1.0, 1.0, 0.0],
+            ]),
+            texture_coordinates: vec![AttributeData::new(
+                "map1",
+                VectorData::Vector2(vec![
+                    [0.0, 0.0],
+                    [1.0, 0.0],
+                    [0.0, 1.0],
+                    [0.0, 1.0],
+                    [1.0, 0.0],
+                    [1.0, 1.0],
+                ]),
+            )],
+            bone_influences: vec![BoneInfluence {
+                bone_name: "a".into(),
+                vertex_weights: (0..6)
+                    .map(|i| VertexWeight {
+                        vertex_index: i,
+                        vertex_weight: 1.0,
+                    })
+                    .collect(),
+            }],
+            ..Default::default()
+        };
+
+        assert_eq!(2, object.weld_vertices(0.0).unwrap());
+        assert_eq!(vec![0, 1, 2, 2, 1, 3], object.vertex_indices);
+        assert_eq!(
+            VectorData::Vector2(vec![[0.0, 0.0], [1.0, 0.0], [0.0, 1.0], [1.0, 1.0]]),
+            object.texture_coordinates[0].data
+        );
+        assert_eq!(
+            vec![0, 1, 2, 3],
+            object.bone_influences[0]
+                .vertex_weights
+                .iter()
+                .map(|w| w.vertex_index)
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn weld_vertices_preserve_seams_and_influences() {
+        let mut object = MeshObjectData {
+            vertex_indices: vec![0, 1, 2],
+            positions: position(vec![[0.0, 0.0, 0.0], [0.0, 0.0, 0.0], [0.0, 0.0, 0.0]]),
+            texture_coordinates: vec![AttributeData::new(
+                "map1",
+                VectorData::Vector2(vec![[0.0, 0.0], [0.5, 0.0], [0.0, 0.0]]),
+            )],
+            bone_influences: vec![BoneInfluence {
+                bone_name: "a".into(),
+                vertex_weights: vec![VertexWeight {
+                    vertex_index: 2,
+                    vertex_weight: 1.0,
+                }],
+            }],
+            ..Default::default()
+        };
+
+        assert_eq!(0, object.weld_vertices(0.1).unwrap());
+        assert_eq!(vec![0, 1, 2], object.vertex_indices);
+    }
+
+    #[test]
+    fn weld_vertices_tolerance_across_cells() {
+        let mut object = MeshObjectData {
+            vertex_indices: vec![0, 1],
+            positions: position(vec![[0.999, 0.0, 0.0], [1.001, 0.0, 0.0]]),
+            ..Default::default()
+        };
+
+        assert_eq!(1, object.weld_vertices(0.01).unwrap());
+        assert_eq!(vec![0, 0], object.vertex_indices);
+    }
+
+    #[test]
+    fn weld_vertices_zero_tolerance() {
+        let mut object = MeshObjectData {
+            vertex_indices: vec![0, 1, 2, 3, 4],
+            positions: position(vec![
+                [1.0, 0.0, 0.0],
+                [1.0000001, 0.0, 0.0],
+                [-0.0, 0.0, 0.0],
+                [0.0, 0.0, 0.0],
+                [1.0, 0.0, 0.0],
+            ]),
+            ..Default::default()
+        };
+
+        assert_eq!(2, object.weld_vertices(0.0).unwrap());
+        assert_eq!(vec![0, 1, 2, 2, 0], object.vertex_indices);
+    }
+
+    #[test]
+    fn weld_vertices_invalid_index() {
+        let mut object = MeshObjectData {
+            vertex_indices: vec![0, 1, 2],
+            positions: position(vec![[0.0; 3]; 2]),
+            ..Default::default()
+        };
+
+        assert!(matches!(
+            object.weld_vertices(0.0),
+            Err(error::Error::VertexIndexOutOfRange {
+                vertex_index: 2,
+                vertex_count: 2
+            })
+        ));
+    }
+
+    #[test]
+    fn remove_unused_vertices() {
+        let mut object = MeshObjectData {
+            vertex_indices: vec![3, 1, 3],
+            positions: position(vec![
+                [0.0, 0.0, 0.0],
+                [1.0, 1.0, 1.0],
+                [2.0, 2.0, 2.0],
+                [3.0, 3.0, 3.0],
+            ]),
+            bone_influences: vec![BoneInfluence {
+                bone_name: "a".into(),
+                vertex_weights: vec![
+                    VertexWeight {
+                        vertex_index: 0,
+                        vertex_weight: 1.0,
+                    },
+                    VertexWeight {
+                        vertex_index: 3,
+                        vertex_weight: 0.5,
+                    },
+                ],
+            }],
+            ..Default::default()
+        };
+
+        assert_eq!(2, object.remove_unused_vertices().unwrap());
+        assert_eq!(vec![1, 0, 1], object.vertex_indices);
+        assert_eq!(
+            VectorData::Vector3(vec![[1.0, 1.0, 1.0], [3.0, 3.0, 3.0]]),
+            object.positions[0].data
+        );
+        let weights = &object.bone_influences[0].vertex_weights;
+        assert_eq!(1, weights.len());
+        assert_eq!(1, weights[0].vertex_index);
+        assert_eq!(0.5, weights[0].vertex_weight);
+    }
+
+    #[test]
+    fn optimize_vertex_cache_preserves_triangles() {
+        // A triangle strip with shuffled triangles.
+        let vertex_indices = vec![
+            4, 5, 6, 0, 1, 2, 6, 7, 8, 2, 3, 4, 1, 2, 3, 5, 6, 7, 3, 4, 5,
+        ];
+        let mut object = MeshObjectData {
+            vertex_indices: vertex_indices.clone(),
+            positions: position(vec![[0.0; 3]; 9]),
+            ..Default::default()
+        };
+
+        object.optimize_vertex_cache().unwrap();
+        assert_eq!(
+            sorted_triangles(&vertex_indices),
+            sorted_triangles(&object.vertex_indices)
+        );
+    }
+
+    #[test]
+    fn optimize_vertex_cache_non_triangles() {
+        let mut object = MeshObjectData {
+            vertex_indices: vec![0, 1],
+            positions: position(vec![[0.0; 3]; 2]),
+            ..Default::default()
+        };
+
+        assert!(matches!(
+            object.optimize_vertex_cache(),
+            Err(error::Error::NonTriangulatedFaces {
+                vertex_index_count: 2
+            })
+        ));
+    }
+
+    #[test]
+    fn optimize_vertex_fetch_first_use_order() {
+        let mut object = MeshObjectData {
+            vertex_indices: vec![2, 0, 2],
+            positions: position(vec![[0.0; 3], [1.0; 3], [2.0; 3]]),
+            ..Default::default()
+        };
+
+        object.optimize_vertex_fetch().unwrap();
+        assert_eq!(vec![0, 1, 0], object.vertex_indices);
+        assert_eq!(
+            VectorData::Vector3(vec![[2.0; 3], [0.0; 3], [1.0; 3]]),
+            object.positions[0].data
+        );
+    }
+}
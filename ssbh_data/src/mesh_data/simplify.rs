@@ -0,0 +1,838 @@
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap};
+
+use geometry_tools::glam::{DVec3, Vec3A};
+use itertools::Itertools;
+
+use super::{error, MeshObjectData, SkinWeights};
+
+/// The weight for the planes that keep borders and attribute seams in place.
+const BOUNDARY_WEIGHT: f64 = 10.0;
+
+/// Settings for [MeshObjectData::simplify].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SimplifyOptions {
+    /// Stop simplifying once the object has at most this many triangles.
+    pub target_triangle_count: usize,
+    /// Stop simplifying before any edge collapse that moves the surface farther than this distance
+    /// or [None] to only use [target_triangle_count](#structfield.target_triangle_count).
+    /// The error is approximate and tends to overestimate the actual distance.
+    pub max_error: Option<f32>,
+    /// The [subindex](struct.MeshObjectData.html#structfield.subindex) of the simplified object.
+    /// This should not be used by any other object with the same name.
+    pub subindex: u64,
+}
+
+impl MeshObjectData {
+    /// Creates a simplified copy of this object with fewer triangles
+    /// using quadric error metric edge collapses.
+    ///
+    /// Borders and seams where vertices with the same position have different attribute values
+    /// like UV seams, hard normals, or vertex color boundaries are preserved.
+    /// Attributes and [bone_influences](#structfield.bone_influences) are interpolated along each collapsed edge.
+    /// The result keeps the same name and uses the [subindex](struct.SimplifyOptions.html#structfield.subindex)
+    /// from `options`, so it can be added to the same [MeshData](super::MeshData) as a level of detail.
+    /**
+    ```rust no_run
+    # use ssbh_data::prelude::*;
+    # use ssbh_data::mesh_data::SimplifyOptions;
+    # let mut mesh = MeshData::from_file("model.numshb")?;
+    let object = &mesh.objects[0];
+    let subindex = mesh
+        .objects
+        .iter()
+        .filter(|o| o.name == object.name)
+        .map(|o| o.subindex + 1)
+        .max()
+        .unwrap_or(0);
+    let low = object.simplify(&SimplifyOptions {
+        target_triangle_count: object.vertex_indices.len() / 3 / 4,
+        max_error: None,
+        subindex,
+    })?;
+    mesh.objects.push(low);
+    # Ok::<(), Box<dyn std::error::Error>>(())
+    ```
+     */
+    pub fn simplify(&self, options: &SimplifyOptions) -> Result<MeshObjectData, error::Error> {
+        // Vertices that are exact duplicates should not be treated as seams.
+        let mut object = self.clone();
+        object.weld_vertices(0.0)?;
+
+        let triangles = object.triangles()?;
+        let positions = object.required_positions()?;
+
+        let bone_names: Vec<_> = object
+            .bone_influences
+            .iter()
+            .map(|i| i.bone_name.as_str())
+            .unique()
+            .collect();
+        let mut skin_weights = SkinWeights::from_bone_influences(
+            &object.bone_influences,
+            &bone_names,
+            positions.len(),
+        )?;
+
+        let mut simplifier = Simplifier::new(&positions, triangles);
+        let max_error = options.max_error.map(|e| e as f64 * e as f64);
+        simplifier.simplify(
+            options.target_triangle_count,
+            max_error,
+            |keep, remove, t| interpolate_vertex(&mut object, &mut skin_weights, keep, remove, t),
+        );
+
+        // Update the positions last since they are shared by vertices with the same position.
+        if let Some(attribute) = object.positions.first_mut() {
+            for (vertex, group) in simplifier.wedge_group.iter().enumerate() {
+                let position = simplifier.positions[*group];
+                let element = attribute.data.element_mut(vertex);
+                for (c, value) in element.iter_mut().zip(position.to_array()) {
+                    *c = value as f32;
+                }
+            }
+        }
+
+        object.vertex_indices = simplifier
+            .triangles
+            .iter()
+            .zip(&simplifier.is_alive)
+            .filter(|(_, alive)| **alive)
+            .flat_map(|(t, _)| t.map(|v| v as u32))
+            .collect();
+        object.bone_influences = skin_weights.to_bone_influences();
+        object.remove_unused_vertices()?;
+        object.subindex = options.subindex;
+
+        Ok(object)
+    }
+}
+
+/// Moves the attributes and weights of vertex `keep` a fraction `t` of the way to vertex `remove`.
+fn interpolate_vertex(
+    object: &mut MeshObjectData,
+    skin_weights: &mut SkinWeights,
+    keep: usize,
+    remove: usize,
+    t: f32,
+) {
+    if t <= 0.0 {
+        return;
+    }
+
+    for attribute in object.attributes_mut() {
+        let target = attribute.data.element(remove).to_vec();
+        for (value, target) in attribute.data.element_mut(keep).iter_mut().zip(target) {
+            *value += (target - *value) * t;
+        }
+    }
+
+    // Normal vectors should stay normalized and preserve the tangent sign in w.
+    for attribute in object
+        .normals
+        .iter_mut()
+        .chain(object.binormals.iter_mut())
+        .chain(object.tangents.iter_mut())
+    {
+        let element = attribute.data.element_mut(keep);
+        if element.len() >= 3 {
+            let normalized = Vec3A::new(element[0], element[1], element[2]).normalize_or_zero();
+            element[..3].copy_from_slice(&normalized.to_array());
+        }
+    }
+
+    let target = skin_weights.vertex_weights[remove].clone();
+    let weights = &mut skin_weights.vertex_weights[keep];
+    for (_, weight) in weights.iter_mut() {
+        *weight *= 1.0 - t;
+    }
+    for (bone_index, weight) in target {
+        match weights.iter_mut().find(|(i, _)| *i == bone_index) {
+            Some((_, total)) => *total += weight * t,
+            None => weights.push((bone_index, weight * t)),
+        }
+    }
+    weights.retain(|(_, w)| *w > 0.0);
+}
+
+/// The sum of squared distances to a set of planes stored as a symmetric 4x4 matrix.
+#[derive(Debug, Clone, Copy, Default)]
+struct Quadric {
+    /// The upper triangle of the 3x3 matrix in the order xx, xy, xz, yy, yz, zz.
+    a: [f64; 6],
+    b: DVec3,
+    c: f64,
+}
+
+impl Quadric {
+    /// The quadric for the plane with unit `normal` that contains `point`.
+    fn from_plane(normal: DVec3, point: DVec3, weight: f64) -> Self {
+        let d = -normal.dot(point);
+        let n = normal;
+        Self {
+            a: [
+                n.x * n.x,
+                n.x * n.y,
+                n.x * n.z,
+                n.y * n.y,
+                n.y * n.z,
+                n.z * n.z,
+            ]
+            .map(|v| v * weight),
+            b: n * d * weight,
+            c: d * d * weight,
+        }
+    }
+
+    fn add(&self, other: &Self) -> Self {
+        let mut a = self.a;
+        for (a, b) in a.iter_mut().zip(other.a) {
+            *a += b;
+        }
+        Self {
+            a,
+            b: self.b + other.b,
+            c: self.c + other.c,
+        }
+    }
+
+    fn mul(&self, p: DVec3) -> DVec3 {
+        let [xx, xy, xz, yy, yz, zz] = self.a;
+        DVec3::new(
+            xx * p.x + xy * p.y + xz * p.z,
+            xy * p.x + yy * p.y + yz * p.z,
+            xz * p.x + yz * p.y + zz * p.z,
+        )
+    }
+
+    fn error(&self, p: DVec3) -> f64 {
+        (p.dot(self.mul(p)) + 2.0 * self.b.dot(p) + self.c).max(0.0)
+    }
+}
+
+/// An edge collapse that merges the vertices with position `remove` into the vertices with position `keep`.
+#[derive(Debug)]
+struct Collapse {
+    cost: f64,
+    keep: usize,
+    remove: usize,
+    /// The new position as a fraction of the distance from `keep` to `remove`.
+    t: f64,
+    /// The (keep, remove) vertices that are merged.
+    pairs: Vec<(usize, usize)>,
+    versions: (u32, u32),
+}
+
+impl PartialEq for Collapse {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Collapse {}
+
+impl PartialOrd for Collapse {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Collapse {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Reverse the order to pop the lowest cost from the max heap first.
+        other.cost.total_cmp(&self.cost)
+    }
+}
+
+/// Edge collapse state for vertices grouped by position.
+/// Vertices with the same position but different attributes are collapsed together.
+struct Simplifier {
+    positions: Vec<DVec3>,
+    quadrics: Vec<Quadric>,
+    /// Positions on a border or seam that can only move along border and seam edges.
+    is_constrained: Vec<bool>,
+    versions: Vec<u32>,
+    group_vertices: Vec<Vec<usize>>,
+    group_triangles: Vec<Vec<usize>>,
+    wedge_group: Vec<usize>,
+    triangles: Vec<[usize; 3]>,
+    is_alive: Vec<bool>,
+    triangle_count: usize,
+}
+
+impl Simplifier {
+    fn new(vertex_positions: &[Vec3A], triangles: Vec<[usize; 3]>) -> Self {
+        let mut group_indices = HashMap::new();
+        let mut positions = Vec::new();
+        let mut group_vertices = Vec::new();
+        let wedge_group: Vec<_> = vertex_positions
+            .iter()
+            .enumerate()
+            .map(|(vertex, p)| {
+                let group = *group_indices
+                    .entry(p.to_array().map(f32::to_bits))
+                    .or_insert_with(|| {
+                        positions.push(p.as_dvec3());
+                        group_vertices.push(Vec::new());
+                        positions.len() - 1
+                    });
+                group_vertices[group].push(vertex);
+                group
+            })
+            .collect();
+
+        // Degenerate triangles can't be collapsed and are removed.
+        let is_alive: Vec<_> = triangles
+            .iter()
+            .map(|t| t.map(|v| wedge_group[v]).iter().all_unique())
+            .collect();
+
+        let mut group_triangles = vec![Vec::new(); positions.len()];
+        let mut edge_triangles: HashMap<(usize, usize), Vec<usize>> = HashMap::new();
+        let mut quadrics = vec![Quadric::default(); positions.len()];
+        for (i, triangle) in triangles.iter().enumerate().filter(|(i, _)| is_alive[*i]) {
+            let groups = triangle.map(|v| wedge_group[v]);
+            for (j, g) in groups.iter().enumerate() {
+                group_triangles[*g].push(i);
+                let next = groups[(j + 1) % 3];
+                edge_triangles
+                    .entry((*g.min(&next), *g.max(&next)))
+                    .or_default()
+                    .push(i);
+            }
+
+            if let Some(normal) = triangle_normal(groups.map(|g| positions[g])) {
+                let quadric = Quadric::from_plane(normal, positions[groups[0]], 1.0);
+                for g in groups {
+                    quadrics[g] = quadrics[g].add(&quadric);
+                }
+            }
+        }
+
+        let mut is_constrained: Vec<_> = group_vertices.iter().map(|v| v.len() > 1).collect();
+
+        // Add planes perpendicular to the faces along borders and seams to keep their shape.
+        for ((a, b), edge_triangles) in &edge_triangles {
+            let is_seam = edge_triangles
+                .iter()
+                .map(|t| vertex_pair(&triangles[*t], &wedge_group, *a, *b))
+                .unique()
+                .count()
+                > 1;
+            if edge_triangles.len() != 2 || is_seam {
+                is_constrained[*a] = true;
+                is_constrained[*b] = true;
+
+                for t in edge_triangles {
+                    let groups = triangles[*t].map(|v| wedge_group[v]);
+                    if let Some(normal) = triangle_normal(groups.map(|g| positions[g])) {
+                        let edge = positions[*b] - positions[*a];
+                        let plane_normal = edge.cross(normal).normalize_or_zero();
+                        let quadric =
+                            Quadric::from_plane(plane_normal, positions[*a], BOUNDARY_WEIGHT);
+                        quadrics[*a] = quadrics[*a].add(&quadric);
+                        quadrics[*b] = quadrics[*b].add(&quadric);
+                    }
+                }
+            }
+        }
+
+        Self {
+            versions: vec![0; positions.len()],
+            triangle_count: is_alive.iter().filter(|a| **a).count(),
+            positions,
+            quadrics,
+            is_constrained,
+            group_vertices,
+            group_triangles,
+            wedge_group,
+            triangles,
+            is_alive,
+        }
+    }
+
+    fn simplify(
+        &mut self,
+        target_triangle_count: usize,
+        max_error: Option<f64>,
+        mut interpolate_vertex: impl FnMut(usize, usize, f32),
+    ) {
+        let mut heap = BinaryHeap::new();
+        let edges: Vec<_> = (0..self.positions.len())
+            .flat_map(|g| {
+                self.neighbors(g)
+                    .into_iter()
+                    .filter(move |n| g < *n)
+                    .map(move |n| (g, n))
+            })
+            .collect();
+        for (a, b) in edges {
+            heap.extend(self.evaluate(a, b));
+        }
+
+        while self.triangle_count > target_triangle_count {
+            let Some(collapse) = heap.pop() else {
+                break;
+            };
+            if collapse.versions != (self.versions[collapse.keep], self.versions[collapse.remove]) {
+                continue;
+            }
+            if max_error.map(|e| collapse.cost > e).unwrap_or(false) {
+                break;
+            }
+
+            for (keep, remove) in &collapse.pairs {
+                interpolate_vertex(*keep, *remove, collapse.t as f32);
+            }
+            self.apply(&collapse);
+
+            // Moving a position affects the costs and flipped triangles for neighboring edges.
+            let neighbors = self.neighbors(collapse.keep);
+            for g in neighbors.iter().chain(std::iter::once(&collapse.keep)) {
+                self.versions[*g] += 1;
+            }
+            for g in neighbors.iter().chain(std::iter::once(&collapse.keep)) {
+                for n in self.neighbors(*g) {
+                    heap.extend(self.evaluate(*g, n));
+                }
+            }
+        }
+    }
+
+    fn neighbors(&self, group: usize) -> Vec<usize> {
+        self.group_triangles[group]
+            .iter()
+            .flat_map(|t| self.triangles[*t])
+            .map(|v| self.wedge_group[v])
+            .filter(|g| *g != group)
+            .unique()
+            .collect()
+    }
+
+    fn evaluate(&self, a: usize, b: usize) -> Option<Collapse> {
+        let shared: Vec<_> = self.group_triangles[a]
+            .iter()
+            .copied()
+            .filter(|t| self.triangles[*t].iter().any(|v| self.wedge_group[*v] == b))
+            .collect();
+
+        // Each vertex should merge with exactly one vertex on the other side of the edge.
+        let pairs: Vec<_> = shared
+            .iter()
+            .map(|t| vertex_pair(&self.triangles[*t], &self.wedge_group, a, b))
+            .unique()
+            .collect();
+        if pairs.is_empty()
+            || !pairs.iter().map(|p| p.0).all_unique()
+            || !pairs.iter().map(|p| p.1).all_unique()
+        {
+            return None;
+        }
+
+        // Collapsing edges without this "link condition" creates non manifold geometry.
+        let opposite: Vec<_> = shared
+            .iter()
+            .flat_map(|t| self.triangles[*t])
+            .map(|v| self.wedge_group[v])
+            .filter(|g| *g != a && *g != b)
+            .collect();
+        let b_neighbors = self.neighbors(b);
+        if self
+            .neighbors(a)
+            .iter()
+            .any(|n| *n != b && b_neighbors.contains(n) && !opposite.contains(n))
+        {
+            return None;
+        }
+
+        let covers = |group: usize, index: fn(&(usize, usize)) -> usize| {
+            self.group_vertices[group]
+                .iter()
+                .all(|v| pairs.iter().any(|p| index(p) == *v))
+        };
+        let is_constrained_edge = shared.len() != 2 || pairs.len() > 1;
+
+        // Constrained positions only move along constrained edges.
+        // Free positions collapse onto constrained positions to keep borders and seams in place.
+        let (keep, remove, pairs, is_fixed) = match (self.is_constrained[a], self.is_constrained[b])
+        {
+            (false, false) => (a, b, pairs, false),
+            (true, false) => (a, b, pairs, true),
+            (false, true) => (b, a, pairs.iter().map(|(x, y)| (*y, *x)).collect(), true),
+            (true, true) => {
+                if is_constrained_edge && covers(a, |p| p.0) && covers(b, |p| p.1) {
+                    (a, b, pairs, false)
+                } else {
+                    return None;
+                }
+            }
+        };
+        if !self.group_vertices[remove]
+            .iter()
+            .all(|v| pairs.iter().any(|p| p.1 == *v))
+        {
+            return None;
+        }
+
+        let quadric = self.quadrics[keep].add(&self.quadrics[remove]);
+        let start = self.positions[keep];
+        let direction = self.positions[remove] - start;
+        let t = if is_fixed {
+            0.0
+        } else {
+            // Find the point on the edge with the lowest error.
+            let denominator = direction.dot(quadric.mul(direction));
+            let t = -(direction.dot(quadric.mul(start)) + quadric.b.dot(direction)) / denominator;
+            if t.is_finite() {
+                t.clamp(0.0, 1.0)
+            } else {
+                [0.0, 1.0, 0.5]
+                    .into_iter()
+                    .min_by(|x, y| {
+                        quadric
+                            .error(start + direction * *x)
+                            .total_cmp(&quadric.error(start + direction * *y))
+                    })
+                    .unwrap()
+            }
+        };
+        let position = start + direction * t;
+
+        if self.flips_triangles(keep, remove, position) {
+            return None;
+        }
+
+        Some(Collapse {
+            cost: quadric.error(position),
+            keep,
+            remove,
+            t,
+            pairs,
+            versions: (self.versions[keep], self.versions[remove]),
+        })
+    }
+
+    fn flips_triangles(&self, keep: usize, remove: usize, position: DVec3) -> bool {
+        self.group_triangles[keep]
+            .iter()
+            .chain(&self.group_triangles[remove])
+            .any(|t| {
+                let groups = self.triangles[*t].map(|v| self.wedge_group[v]);
+                if groups.contains(&keep) && groups.contains(&remove) {
+                    // This triangle is removed by the collapse.
+                    return false;
+                }
+
+                let old = groups.map(|g| self.positions[g]);
+                let new = groups.map(|g| {
+                    if g == keep || g == remove {
+                        position
+                    } else {
+                        self.positions[g]
+                    }
+                });
+                let old_normal = (old[1] - old[0]).cross(old[2] - old[0]);
+                let new_normal = (new[1] - new[0]).cross(new[2] - new[0]);
+                old_normal.dot(new_normal) <= 0.0
+            })
+    }
+
+    fn apply(&mut self, collapse: &Collapse) {
+        let Collapse {
+            keep, remove, t, ..
+        } = *collapse;
+
+        let remove_triangles = std::mem::take(&mut self.group_triangles[remove]);
+        for t in &remove_triangles {
+            let triangle = &mut self.triangles[*t];
+            for v in triangle.iter_mut() {
+                if let Some((new, _)) = collapse.pairs.iter().find(|(_, old)| old == v) {
+                    *v = *new;
+                }
+            }
+
+            if self.is_alive[*t] && !triangle.map(|v| self.wedge_group[v]).iter().all_unique() {
+                self.is_alive[*t] = false;
+                self.triangle_count -= 1;
+            }
+        }
+
+        let mut keep_triangles = std::mem::take(&mut self.group_triangles[keep]);
+        keep_triangles.extend(remove_triangles);
+        keep_triangles.retain(|t| self.is_alive[*t]);
+        keep_triangles.sort_unstable();
+        keep_triangles.dedup();
+
+        // Update neighboring positions that referenced the removed triangles.
+        for g in keep_triangles
+            .iter()
+            .flat_map(|t| self.triangles[*t])
+            .map(|v| self.wedge_group[v])
+            .unique()
+            .filter(|g| *g != keep)
+            .collect_vec()
+        {
+            let alive = &self.is_alive;
+            self.group_triangles[g].retain(|t| alive[*t]);
+        }
+        self.group_triangles[keep] = keep_triangles;
+
+        self.positions[keep] =
+            self.positions[keep] + (self.positions[remove] - self.positions[keep]) * t;
+        self.quadrics[keep] = self.quadrics[keep].add(&self.quadrics[remove]);
+        self.is_constrained[keep] |= self.is_constrained[remove];
+        self.group_vertices[remove].clear();
+    }
+}
+
+/// The vertices in `triangle` with the positions `a` and `b`.
+fn vertex_pair(triangle: &[usize; 3], wedge_group: &[usize], a: usize, b: usize) -> (usize, usize) {
+    let find = |group| {
+        triangle
+            .iter()
+            .copied()
+            .find(|v| wedge_group[*v] == group)
+            .unwrap()
+    };
+    (find(a), find(b))
+}
+
+fn triangle_normal([a, b, c]: [DVec3; 3]) -> Option<DVec3> {
+    (b - a).cross(c - a).try_normalize()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mesh_data::{AttributeData, BoneInfluence, VectorData, VertexWeight};
+
+    /// A flat grid in the XY plane with `size` quads along each axis.
+    /// The UVs are the positions scaled to the 0 to 1 range.
+    fn grid(size: usize) -> MeshObjectData {
+        let mut positions = Vec::new();
+        for y in 0..=size {
+            for x in 0..=size {
+                positions.push([x as f32, y as f32, 0.0]);
+            }
+        }
+
+        let mut vertex_indices = Vec::new();
+        for y in 0..size {
+            for x in 0..size {
+                let i = (y * (size + 1) + x) as u32;
+                let row = (size + 1) as u32;
+                vertex_indices.extend([i, i + 1, i + row, i + row, i + 1, i + row + 1]);
+            }
+        }
+
+        let scale = size as f32;
+        MeshObjectData {
+            vertex_indices,
+            texture_coordinates: vec![AttributeData::new(
+                "map1",
+                VectorData::Vector2(
+                    positions
+                        .iter()
+                        .map(|[x, y, _]| [x / scale, y / scale])
+                        .collect(),
+                ),
+            )],
+            positions: vec![AttributeData::new(
+                "Position0",
+                VectorData::Vector3(positions),
+            )],
+            ..Default::default()
+        }
+    }
+
+    fn object_positions(object: &MeshObjectData) -> Vec<Vec3A> {
+        object.positions[0].data.to_glam_vec3a()
+    }
+
+    fn object_uvs(object: &MeshObjectData) -> Vec<[f32; 2]> {
+        match &object.texture_coordinates[0].data {
+            VectorData::Vector2(v) => v.clone(),
+            _ => panic!("unexpected data"),
+        }
+    }
+
+    fn assert_bounds(object: &MeshObjectData, min: Vec3A, max: Vec3A) {
+        let positions = object_positions(object);
+        let actual_min = positions.iter().fold(Vec3A::INFINITY, |a, b| a.min(*b));
+        let actual_max = positions.iter().fold(Vec3A::NEG_INFINITY, |a, b| a.max(*b));
+        assert_eq!((min, max), (actual_min, actual_max));
+    }
+
+    #[test]
+    fn simplify_flat_grid() {
+        let mut object = grid(4);
+        object.bone_influences = vec![
+            BoneInfluence {
+                bone_name: "A".into(),
+                vertex_weights: (0..25)
+                    .filter(|i| i % 5 != 4)
+                    .map(|i| VertexWeight {
+                        vertex_index: i,
+                        vertex_weight: 1.0 - (i % 5) as f32 / 4.0,
+                    })
+                    .collect(),
+            },
+            BoneInfluence {
+                bone_name: "B".into(),
+                vertex_weights: (0..25)
+                    .filter(|i| i % 5 != 0)
+                    .map(|i| VertexWeight {
+                        vertex_index: i,
+                        vertex_weight: (i % 5) as f32 / 4.0,
+                    })
+                    .collect(),
+            },
+        ];
+
+        let simplified = object
+            .simplify(&SimplifyOptions {
+                target_triangle_count: 8,
+                max_error: None,
+                subindex: 1,
+            })
+            .unwrap();
+
+        assert_eq!(object.name, simplified.name);
+        assert_eq!(1, simplified.subindex);
+        assert!(simplified.vertex_indices.len() / 3 <= 8);
+        assert_bounds(&simplified, Vec3A::ZERO, Vec3A::new(4.0, 4.0, 0.0));
+
+        // Attributes and weights are interpolated along the edges.
+        let positions = object_positions(&simplified);
+        let uvs = object_uvs(&simplified);
+        let weights = SkinWeights::from_bone_influences(
+            &simplified.bone_influences,
+            &["A", "B"],
+            positions.len(),
+        )
+        .unwrap();
+        for ((p, uv), weights) in positions.iter().zip(&uvs).zip(&weights.vertex_weights) {
+            assert!((p.x / 4.0 - uv[0]).abs() < 1e-5);
+            assert!((p.y / 4.0 - uv[1]).abs() < 1e-5);
+            let b = weights
+                .iter()
+                .find(|w| w.0 == 1)
+                .map(|w| w.1)
+                .unwrap_or(0.0);
+            assert!((p.x / 4.0 - b).abs() < 1e-5);
+        }
+    }
+
+    #[test]
+    fn simplify_preserves_uv_seam() {
+        let mut object = grid(4);
+        object.weld_vertices(0.0).unwrap();
+
+        // Split the vertices along x = 2 and offset the UVs for the right half.
+        let positions = object_positions(&object);
+        let mut uvs = object_uvs(&object);
+        let mut vertex_positions: Vec<_> = positions.iter().map(|p| p.to_array()).collect();
+        for (i, p) in positions.iter().enumerate() {
+            if p.x == 2.0 {
+                vertex_positions.push(p.to_array());
+                uvs.push([uvs[i][0] + 1.0, uvs[i][1]]);
+                let new = (vertex_positions.len() - 1) as u32;
+                for t in object.vertex_indices.chunks_exact_mut(3) {
+                    let is_right = t.iter().any(|v| vertex_positions[*v as usize][0] > 2.0);
+                    for v in t.iter_mut().filter(|v| **v as usize == i && is_right) {
+                        *v = new;
+                    }
+                }
+            } else if p.x > 2.0 {
+                uvs[i][0] += 1.0;
+            }
+        }
+        object.positions[0].data = VectorData::Vector3(vertex_positions);
+        object.texture_coordinates[0].data = VectorData::Vector2(uvs);
+
+        let simplified = object
+            .simplify(&SimplifyOptions {
+                target_triangle_count: 0,
+                max_error: Some(0.01),
+                subindex: 1,
+            })
+            .unwrap();
+
+        let positions = object_positions(&simplified);
+        let uvs = object_uvs(&simplified);
+        // Each side of the seam can be simplified to a single quad.
+        assert_eq!(4, simplified.vertex_indices.len() / 3);
+        assert_bounds(&simplified, Vec3A::ZERO, Vec3A::new(4.0, 4.0, 0.0));
+
+        // Triangles should stay on one side of the seam with the UVs for that side.
+        for t in simplified.vertex_indices.chunks_exact(3) {
+            let offsets: Vec<_> = t
+                .iter()
+                .map(|v| {
+                    let (p, uv) = (positions[*v as usize], uvs[*v as usize]);
+                    assert!((p.y / 4.0 - uv[1]).abs() < 1e-5);
+                    ((uv[0] - p.x / 4.0) * 1000.0).round()
+                })
+                .collect();
+            assert!(offsets.iter().all_equal());
+            let max_x = t
+                .iter()
+                .map(|v| positions[*v as usize].x)
+                .fold(0.0, f32::max);
+            let min_x = t
+                .iter()
+                .map(|v| positions[*v as usize].x)
+                .fold(4.0, f32::min);
+            assert!(max_x <= 2.0 || min_x >= 2.0);
+        }
+    }
+
+    #[test]
+    fn simplify_max_error() {
+        let mut object = grid(4);
+        if let VectorData::Vector3(positions) = &mut object.positions[0].data {
+            positions[12][2] = 1.0;
+        }
+
+        let simplified = object
+            .simplify(&SimplifyOptions {
+                target_triangle_count: 0,
+                max_error: Some(0.01),
+                subindex: 1,
+            })
+            .unwrap();
+
+        assert!(simplified.vertex_indices.len() < object.vertex_indices.len());
+        assert_bounds(&simplified, Vec3A::ZERO, Vec3A::new(4.0, 4.0, 1.0));
+    }
+
+    #[test]
+    fn simplify_target_triangle_count() {
+        let object = grid(4);
+        let simplified = object
+            .simplify(&SimplifyOptions {
+                target_triangle_count: 30,
+                max_error: None,
+                subindex: 1,
+            })
+            .unwrap();
+        assert!((29..=30).contains(&(simplified.vertex_indices.len() / 3)));
+    }
+
+    #[test]
+    fn simplify_non_triangulated() {
+        let mut object = grid(1);
+        object.vertex_indices.pop();
+        let result = object.simplify(&SimplifyOptions {
+            target_triangle_count: 0,
+            max_error: None,
+            subindex: 1,
+        });
+        assert!(matches!(
+            result,
+            Err(error::Error::NonTriangulatedFaces {
+                vertex_index_count: 5
+            })
+        ));
+    }
+}
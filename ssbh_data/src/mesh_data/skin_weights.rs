@@ -0,0 +1,824 @@
+use ahash::AHashMap;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use super::{
+    error, transform_points, transform_vectors, BoneInfluence, MeshData, MeshObjectData,
+    VertexWeight,
+};
+use crate::skel_data::SkelData;
+
+/// The bone indices and weights with `N` slots for each vertex.
+pub type FixedSkinWeights<const N: usize> = (Vec<[usize; N]>, Vec<[f32; N]>);
+
+/// Skin weights stored per vertex instead of per bone like [BoneInfluence].
+///
+/// Bones are referenced by their index in [bone_names](#structfield.bone_names),
+/// which is usually the order of the bones in the [SkelData].
+/**
+```rust
+# use ssbh_data::mesh_data::{BoneInfluence, SkinWeights, VertexWeight};
+let influences = vec![BoneInfluence {
+    bone_name: "Hip".into(),
+    vertex_weights: vec![VertexWeight { vertex_index: 1, vertex_weight: 1.0 }],
+}];
+
+let weights = SkinWeights::from_bone_influences(&influences, &["Trans", "Hip"], 2)?;
+assert_eq!(vec![vec![], vec![(1, 1.0)]], weights.vertex_weights);
+
+let (bone_indices, weights_by_slot) = weights.to_fixed::<4>()?;
+assert_eq!([1, 0, 0, 0], bone_indices[1]);
+assert_eq!([1.0, 0.0, 0.0, 0.0], weights_by_slot[1]);
+# Ok::<(), Box<dyn std::error::Error>>(())
+```
+ */
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct SkinWeights {
+    /// The bone names indexed by the bone indices in [vertex_weights](#structfield.vertex_weights).
+    pub bone_names: Vec<String>,
+    /// The (bone index, weight) pairs for each vertex.
+    pub vertex_weights: Vec<Vec<(usize, f32)>>,
+}
+
+impl SkinWeights {
+    /// Groups the weights in `influences` by vertex for `vertex_count` vertices.
+    /// Bone indices are the position of the bone name in `bone_names`.
+    ///
+    /// Weights of 0.0 have no effect on skinning and are skipped like unused slots in [SkinWeights::from_fixed].
+    pub fn from_bone_influences<S: AsRef<str>>(
+        influences: &[BoneInfluence],
+        bone_names: &[S],
+        vertex_count: usize,
+    ) -> Result<Self, error::Error> {
+        let bone_indices: AHashMap<_, _> = bone_names
+            .iter()
+            .enumerate()
+            .map(|(i, name)| (name.as_ref(), i))
+            .collect();
+
+        let mut vertex_weights = vec![Vec::new(); vertex_count];
+        for influence in influences {
+            let bone_index = *bone_indices
+                .get(influence.bone_name.as_str())
+                .ok_or_else(|| error::Error::InfluenceBoneNotFound {
+                    bone_name: influence.bone_name.clone(),
+                })?;
+
+            for weight in &influence.vertex_weights {
+                let weights = vertex_weights.get_mut(weight.vertex_index as usize).ok_or(
+                    error::Error::VertexIndexOutOfRange {
+                        vertex_index: weight.vertex_index as usize,
+                        vertex_count,
+                    },
+                )?;
+                if weight.vertex_weight != 0.0 {
+                    weights.push((bone_index, weight.vertex_weight));
+                }
+            }
+        }
+
+        Ok(Self {
+            bone_names: bone_names.iter().map(|n| n.as_ref().to_string()).collect(),
+            vertex_weights,
+        })
+    }
+
+    /// Groups the weights in `influences` by vertex using the bone order in `skel`.
+    pub fn from_bone_influences_skel(
+        influences: &[BoneInfluence],
+        skel: &SkelData,
+        vertex_count: usize,
+    ) -> Result<Self, error::Error> {
+        let bone_names: Vec<_> = skel.bones.iter().map(|b| b.name.as_str()).collect();
+        Self::from_bone_influences(influences, &bone_names, vertex_count)
+    }
+
+    /// Groups the weights by bone.
+    ///
+    /// This is the inverse of [SkinWeights::from_bone_influences] for influences
+    /// in the same order as [bone_names](#structfield.bone_names) with weights sorted by vertex index.
+    /// Otherwise, the influences are reordered to match [bone_names](#structfield.bone_names),
+    /// the weights for each influence are sorted by vertex index, and influences without any weights are skipped.
+    pub fn to_bone_influences(&self) -> Vec<BoneInfluence> {
+        let mut weights_by_bone = vec![Vec::new(); self.bone_names.len()];
+        for (vertex_index, weights) in self.vertex_weights.iter().enumerate() {
+            for (bone_index, weight) in weights {
+                if let Some(bone_weights) = weights_by_bone.get_mut(*bone_index) {
+                    bone_weights.push(VertexWeight {
+                        vertex_index: vertex_index as u32,
+                        vertex_weight: *weight,
+                    });
+                }
+            }
+        }
+
+        self.bone_names
+            .iter()
+            .zip(weights_by_bone)
+            .filter(|(_, weights)| !weights.is_empty())
+            .map(|(name, vertex_weights)| BoneInfluence {
+                bone_name: name.clone(),
+                vertex_weights,
+            })
+            .collect()
+    }
+
+    /// Converts the weights to `N` bone index and weight slots for each vertex.
+    /// Unused slots have a bone index and weight of 0.
+    /// Returns an error if a vertex has more than `N` influences.
+    pub fn to_fixed<const N: usize>(&self) -> Result<FixedSkinWeights<N>, error::Error> {
+        let mut bone_indices = Vec::with_capacity(self.vertex_weights.len());
+        let mut weights = Vec::with_capacity(self.vertex_weights.len());
+        for (i, vertex_weights) in self.vertex_weights.iter().enumerate() {
+            if vertex_weights.len() > N {
+                return Err(error::Error::TooManyInfluences {
+                    vertex_index: i,
+                    influence_count: vertex_weights.len(),
+                    max_influences: N,
+                });
+            }
+
+            let mut vertex_bone_indices = [0; N];
+            let mut vertex_bone_weights = [0.0; N];
+            for (slot, (bone_index, weight)) in vertex_weights.iter().enumerate() {
+                vertex_bone_indices[slot] = *bone_index;
+                vertex_bone_weights[slot] = *weight;
+            }
+            bone_indices.push(vertex_bone_indices);
+            weights.push(vertex_bone_weights);
+        }
+
+        Ok((bone_indices, weights))
+    }
+
+    /// Creates per vertex weights from `N` bone index and weight slots for each vertex.
+    /// Slots with a weight of 0.0 are treated as unused.
+    /// The used slots keep their order, so this is the inverse of [SkinWeights::to_fixed]
+    /// for weights without any values of 0.0.
+    pub fn from_fixed<const N: usize, S: AsRef<str>>(
+        bone_names: &[S],
+        bone_indices: &[[usize; N]],
+        weights: &[[f32; N]],
+    ) -> Self {
+        Self {
+            bone_names: bone_names.iter().map(|n| n.as_ref().to_string()).collect(),
+            vertex_weights: bone_indices
+                .iter()
+                .zip(weights)
+                .map(|(indices, weights)| {
+                    indices
+                        .iter()
+                        .copied()
+                        .zip(weights.iter().copied())
+                        .filter(|(_, w)| *w != 0.0)
+                        .collect()
+                })
+                .collect(),
+        }
+    }
+}
+
+impl MeshObjectData {
+    /// Converts [bone_influences](#structfield.bone_influences) to per vertex weights.
+    /// See [SkinWeights::from_bone_influences].
+    pub fn skin_weights<S: AsRef<str>>(
+        &self,
+        bone_names: &[S],
+    ) -> Result<SkinWeights, error::Error> {
+        SkinWeights::from_bone_influences(&self.bone_influences, bone_names, self.vertex_count()?)
+    }
+
+    /// Scales the weights for each vertex in [bone_influences](#structfield.bone_influences) to sum to 1.0.
+    /// Vertices with a total weight of 0.0 are unchanged.
+    /**
+    ```rust
+    # use ssbh_data::mesh_data::{BoneInfluence, MeshObjectData, VertexWeight};
+    let mut object = MeshObjectData {
+        bone_influences: vec![
+            BoneInfluence {
+                bone_name: "A".into(),
+                vertex_weights: vec![VertexWeight { vertex_index: 0, vertex_weight: 0.2 }],
+            },
+            BoneInfluence {
+                bone_name: "B".into(),
+                vertex_weights: vec![VertexWeight { vertex_index: 0, vertex_weight: 0.6 }],
+            },
+        ],
+        ..Default::default()
+    };
+
+    object.normalize_skin_weights();
+    assert_eq!(0.25, object.bone_influences[0].vertex_weights[0].vertex_weight);
+    assert_eq!(0.75, object.bone_influences[1].vertex_weights[0].vertex_weight);
+    ```
+     */
+    pub fn normalize_skin_weights(&mut self) {
+        let totals = total_weights(&self.bone_influences);
+        for weight in self
+            .bone_influences
+            .iter_mut()
+            .flat_map(|i| i.vertex_weights.iter_mut())
+        {
+            let total = totals[&weight.vertex_index];
+            if total > 0.0 {
+                weight.vertex_weight /= total;
+            }
+        }
+    }
+
+    /// Removes the smallest weights for each vertex with more than `max_influences` weights.
+    /// Returns the number of weights removed.
+    ///
+    /// The remaining weights are not normalized.
+    /// Call [normalize_skin_weights](#method.normalize_skin_weights) to make the weights sum to 1.0 again.
+    pub fn limit_skin_weight_influences(&mut self, max_influences: usize) -> usize {
+        let mut weights_by_vertex: AHashMap<u32, Vec<(usize, usize, f32)>> = AHashMap::new();
+        for (i, influence) in self.bone_influences.iter().enumerate() {
+            for (j, weight) in influence.vertex_weights.iter().enumerate() {
+                weights_by_vertex
+                    .entry(weight.vertex_index)
+                    .or_default()
+                    .push((i, j, weight.vertex_weight));
+            }
+        }
+
+        let mut is_removed: Vec<_> = self
+            .bone_influences
+            .iter()
+            .map(|i| vec![false; i.vertex_weights.len()])
+            .collect();
+        let mut removed_count = 0;
+        for weights in weights_by_vertex.values_mut() {
+            if weights.len() > max_influences {
+                // The sort is stable, so ties keep the earlier influence.
+                weights.sort_by(|a, b| b.2.total_cmp(&a.2));
+                for (i, j, _) in &weights[max_influences..] {
+                    is_removed[*i][*j] = true;
+                    removed_count += 1;
+                }
+            }
+        }
+
+        for (influence, is_removed) in self.bone_influences.iter_mut().zip(is_removed) {
+            let mut is_removed = is_removed.into_iter();
+            influence
+                .vertex_weights
+                .retain(|_| !is_removed.next().unwrap_or(false));
+        }
+
+        removed_count
+    }
+
+    /// Removes weights less than `threshold`.
+    /// Returns the number of weights removed.
+    ///
+    /// The remaining weights are not normalized.
+    /// Call [normalize_skin_weights](#method.normalize_skin_weights) to make the weights sum to 1.0 again.
+    pub fn prune_skin_weights(&mut self, threshold: f32) -> usize {
+        let mut removed_count = 0;
+        for influence in &mut self.bone_influences {
+            let count = influence.vertex_weights.len();
+            influence
+                .vertex_weights
+                .retain(|w| w.vertex_weight >= threshold);
+            removed_count += count - influence.vertex_weights.len();
+        }
+        removed_count
+    }
+
+    /// Adds the weights for `source_bone_name` to the weights for `target_bone_name`
+    /// and removes the influence for `source_bone_name`.
+    /// This preserves the total weight of each vertex when removing a bone like a helper bone.
+    ///
+    /// If there is no influence for `target_bone_name`, the source influence is renamed instead.
+    /// Returns `false` if there is no influence for `source_bone_name`.
+    /**
+    ```rust
+    # use ssbh_data::mesh_data::MeshObjectData;
+    # let mut object = MeshObjectData::default();
+    if object.merge_bone_influence("H_Helper", "ArmL") {
+        println!("Merged weights for H_Helper into ArmL");
+    }
+    ```
+     */
+    pub fn merge_bone_influence(&mut self, source_bone_name: &str, target_bone_name: &str) -> bool {
+        if source_bone_name == target_bone_name {
+            return self
+                .bone_influences
+                .iter()
+                .any(|i| i.bone_name == source_bone_name);
+        }
+
+        let (sources, mut influences): (Vec<_>, Vec<_>) = std::mem::take(&mut self.bone_influences)
+            .into_iter()
+            .partition(|i| i.bone_name == source_bone_name);
+        if sources.is_empty() {
+            self.bone_influences = influences;
+            return false;
+        }
+
+        let source_weights = sources.into_iter().flat_map(|i| i.vertex_weights);
+        match influences
+            .iter_mut()
+            .find(|i| i.bone_name == target_bone_name)
+        {
+            Some(target) => {
+                let mut target_indices: AHashMap<_, _> = target
+                    .vertex_weights
+                    .iter()
+                    .enumerate()
+                    .map(|(i, w)| (w.vertex_index, i))
+                    .collect();
+                for weight in source_weights {
+                    match target_indices.get(&weight.vertex_index) {
+                        Some(i) => target.vertex_weights[*i].vertex_weight += weight.vertex_weight,
+                        None => {
+                            target_indices.insert(weight.vertex_index, target.vertex_weights.len());
+                            target.vertex_weights.push(weight);
+                        }
+                    }
+                }
+            }
+            None => influences.push(BoneInfluence {
+                bone_name: target_bone_name.to_string(),
+                vertex_weights: source_weights.collect(),
+            }),
+        }
+
+        self.bone_influences = influences;
+        true
+    }
+
+    /// Finds the vertices with a total weight of 0.0 in [bone_influences](#structfield.bone_influences).
+    /// Unweighted vertices will not deform correctly in game.
+    ///
+    /// Objects without any influences are bound to [parent_bone_name](#structfield.parent_bone_name) instead,
+    /// so the result is empty.
+    pub fn unweighted_vertices(&self) -> Result<Vec<u32>, error::Error> {
+        let vertex_count = self.vertex_count()?;
+        if self.bone_influences.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let totals = total_weights(&self.bone_influences);
+        Ok((0..vertex_count as u32)
+            .filter(|v| totals.get(v).copied().unwrap_or(0.0) <= 0.0)
+            .collect())
+    }
+}
+
+impl MeshData {
+    /// Renames the bone influences and parent bones named `name` to `new_name` for all objects.
+    /// See [SkelData::rename_bone](crate::skel_data::SkelData::rename_bone).
+    pub fn rename_bone(&mut self, name: &str, new_name: &str) {
+        for object in &mut self.objects {
+            for influence in &mut object.bone_influences {
+                if influence.bone_name == name {
+                    influence.bone_name = new_name.to_string();
+                }
+            }
+            if object.parent_bone_name == name {
+                object.parent_bone_name = new_name.to_string();
+            }
+        }
+    }
+
+    /// Removes references to the bone named `name` from all objects
+    /// and returns the number of modified objects.
+    /// `skel` should be the skeleton before calling [SkelData::remove_bone](crate::skel_data::SkelData::remove_bone).
+    ///
+    /// If the bone has a parent in `skel`, skin weights are merged into the parent bone
+    /// like [MeshObjectData::merge_bone_influence] and objects parented to the bone use the parent bone.
+    /// Otherwise, the skin weights are removed and objects parented to the bone have no parent bone.
+    ///
+    /// Objects without [bone_influences](struct.MeshObjectData.html#structfield.bone_influences)
+    /// are positioned relative to their parent bone,
+    /// so the vertex data for objects parented to the removed bone is transformed by the removed bone's transform.
+    /// This preserves the world positions of the vertices like the child bones for [SkelData::remove_bone].
+    pub fn remove_bone(&mut self, name: &str, skel: &SkelData) -> usize {
+        let removed = skel.bones.iter().find(|b| b.name == name);
+        let replacement = removed
+            .and_then(|b| b.parent_index)
+            .and_then(|i| skel.bones.get(i))
+            .map(|b| b.name.as_str());
+
+        let mut count = 0;
+        for object in &mut self.objects {
+            let is_single_bound = object.bone_influences.is_empty();
+
+            let changed = match replacement {
+                Some(replacement) => object.merge_bone_influence(name, replacement),
+                None => {
+                    let influence_count = object.bone_influences.len();
+                    object.bone_influences.retain(|i| i.bone_name != name);
+                    object.bone_influences.len() != influence_count
+                }
+            };
+
+            let parent_changed = object.parent_bone_name == name;
+            if parent_changed {
+                object.parent_bone_name = replacement.unwrap_or_default().to_string();
+                if let Some(bone) = removed.filter(|_| is_single_bound) {
+                    transform_vertices(object, &bone.transform);
+                }
+            }
+
+            if changed || parent_changed {
+                count += 1;
+            }
+        }
+        count
+    }
+}
+
+fn transform_vertices(object: &mut MeshObjectData, transform: &[[f32; 4]; 4]) {
+    for attribute in &mut object.positions {
+        attribute.data = transform_points(&attribute.data, transform);
+    }
+    for attribute in object
+        .normals
+        .iter_mut()
+        .chain(&mut object.binormals)
+        .chain(&mut object.tangents)
+    {
+        attribute.data = transform_vectors(&attribute.data, transform);
+    }
+}
+
+fn total_weights(influences: &[BoneInfluence]) -> AHashMap<u32, f32> {
+    let mut totals = AHashMap::new();
+    for VertexWeight {
+        vertex_index,
+        vertex_weight,
+    } in influences.iter().flat_map(|i| &i.vertex_weights)
+    {
+        *totals.entry(*vertex_index).or_insert(0.0) += vertex_weight;
+    }
+    totals
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mesh_data::{AttributeData, VectorData};
+    use crate::skel_data::{BillboardType, BoneData};
+    use geometry_tools::glam::{Mat4, Vec3};
+
+    fn influence(bone_name: &str, weights: &[(u32, f32)]) -> BoneInfluence {
+        BoneInfluence {
+            bone_name: bone_name.to_string(),
+            vertex_weights: weights
+                .iter()
+                .map(|(vertex_index, vertex_weight)| VertexWeight {
+                    vertex_index: *vertex_index,
+                    vertex_weight: *vertex_weight,
+                })
+                .collect(),
+        }
+    }
+
+    fn weights(influence: &BoneInfluence) -> Vec<(u32, f32)> {
+        influence
+            .vertex_weights
+            .iter()
+            .map(|w| (w.vertex_index, w.vertex_weight))
+            .collect()
+    }
+
+    fn influence_weights(influences: &[BoneInfluence]) -> Vec<(&str, Vec<(u32, f32)>)> {
+        influences
+            .iter()
+            .map(|i| (i.bone_name.as_str(), weights(i)))
+            .collect()
+    }
+
+    #[test]
+    fn skin_weights_round_trip() {
+        let influences = vec![
+            influence("A", &[(0, 0.5), (3, 0.25)]),
+            influence("B", &[(0, 0.5), (2, 1.0), (3, 0.75)]),
+        ];
+
+        let skin_weights =
+            SkinWeights::from_bone_influences(&influences, &["A", "B", "C"], 5).unwrap();
+        assert_eq!(
+            vec![
+                vec![(0, 0.5), (1, 0.5)],
+                Vec::new(),
+                vec![(1, 1.0)],
+                vec![(0, 0.25), (1, 0.75)],
+                Vec::new()
+            ],
+            skin_weights.vertex_weights
+        );
+        assert_eq!(
+            influence_weights(&influences),
+            influence_weights(&skin_weights.to_bone_influences())
+        );
+
+        // The fixed slots preserve the per vertex weights.
+        let (bone_indices, weights) = skin_weights.to_fixed::<4>().unwrap();
+        let fixed_weights = SkinWeights::from_fixed(&["A", "B", "C"], &bone_indices, &weights);
+        assert_eq!(skin_weights, fixed_weights);
+        assert_eq!(
+            influence_weights(&influences),
+            influence_weights(&fixed_weights.to_bone_influences())
+        );
+    }
+
+    #[test]
+    fn skin_weights_reordered_influences() {
+        let influences = vec![
+            influence("C", &[]),
+            influence("B", &[(2, 1.0), (0, 0.5)]),
+            influence("A", &[(0, 0.5), (1, 0.0)]),
+        ];
+
+        let skin_weights =
+            SkinWeights::from_bone_influences(&influences, &["A", "B", "C"], 4).unwrap();
+        assert_eq!(
+            vec![
+                vec![(1, 0.5), (0, 0.5)],
+                Vec::new(),
+                vec![(1, 1.0)],
+                Vec::new()
+            ],
+            skin_weights.vertex_weights
+        );
+
+        // Influences follow the bone order, weights are sorted, and zero weights are removed.
+        let new_influences = skin_weights.to_bone_influences();
+        assert_eq!(2, new_influences.len());
+        assert_eq!("A", new_influences[0].bone_name);
+        assert_eq!(vec![(0, 0.5)], weights(&new_influences[0]));
+        assert_eq!("B", new_influences[1].bone_name);
+        assert_eq!(vec![(0, 0.5), (2, 1.0)], weights(&new_influences[1]));
+    }
+
+    #[test]
+    fn skin_weights_missing_bone() {
+        let result = SkinWeights::from_bone_influences(&[influence("D", &[])], &["A"], 1);
+        assert!(matches!(
+            result,
+            Err(error::Error::InfluenceBoneNotFound { bone_name }) if bone_name == "D"
+        ));
+    }
+
+    #[test]
+    fn skin_weights_vertex_out_of_range() {
+        let result = SkinWeights::from_bone_influences(&[influence("A", &[(3, 1.0)])], &["A"], 3);
+        assert!(matches!(
+            result,
+            Err(error::Error::VertexIndexOutOfRange {
+                vertex_index: 3,
+                vertex_count: 3
+            })
+        ));
+    }
+
+    #[test]
+    fn skin_weights_fixed_round_trip() {
+        let skin_weights = SkinWeights {
+            bone_names: vec!["A".into(), "B".into()],
+            vertex_weights: vec![vec![(1, 0.25), (0, 0.75)], vec![(1, 1.0)]],
+        };
+
+        let (bone_indices, weights) = skin_weights.to_fixed::<2>().unwrap();
+        assert_eq!(vec![[1, 0], [1, 0]], bone_indices);
+        assert_eq!(vec![[0.25, 0.75], [1.0, 0.0]], weights);
+        assert_eq!(
+            skin_weights,
+            SkinWeights::from_fixed(&["A", "B"], &bone_indices, &weights)
+        );
+    }
+
+    #[test]
+    fn skin_weights_fixed_too_many_influences() {
+        let skin_weights = SkinWeights {
+            bone_names: vec!["A".into(), "B".into()],
+            vertex_weights: vec![Vec::new(), vec![(1, 0.25), (0, 0.75)]],
+        };
+
+        assert!(matches!(
+            skin_weights.to_fixed::<1>(),
+            Err(error::Error::TooManyInfluences {
+                vertex_index: 1,
+                influence_count: 2,
+                max_influences: 1
+            })
+        ));
+    }
+
+    #[test]
+    fn normalize_skin_weights_zero_total() {
+        let mut object = MeshObjectData {
+            bone_influences: vec![
+                influence("A", &[(0, 0.5), (1, 0.0)]),
+                influence("B", &[(0, 1.5)]),
+            ],
+            ..Default::default()
+        };
+
+        object.normalize_skin_weights();
+        assert_eq!(
+            vec![(0, 0.25), (1, 0.0)],
+            weights(&object.bone_influences[0])
+        );
+        assert_eq!(vec![(0, 0.75)], weights(&object.bone_influences[1]));
+    }
+
+    #[test]
+    fn limit_skin_weight_influences() {
+        let mut object = MeshObjectData {
+            bone_influences: vec![
+                influence("A", &[(0, 0.1), (1, 0.5)]),
+                influence("B", &[(0, 0.3), (1, 0.5)]),
+                influence("C", &[(0, 0.6)]),
+            ],
+            ..Default::default()
+        };
+
+        assert_eq!(1, object.limit_skin_weight_influences(2));
+        assert_eq!(vec![(1, 0.5)], weights(&object.bone_influences[0]));
+        assert_eq!(
+            vec![(0, 0.3), (1, 0.5)],
+            weights(&object.bone_influences[1])
+        );
+        assert_eq!(vec![(0, 0.6)], weights(&object.bone_influences[2]));
+    }
+
+    #[test]
+    fn prune_skin_weights() {
+        let mut object = MeshObjectData {
+            bone_influences: vec![
+                influence("A", &[(0, 0.001), (1, 0.5)]),
+                influence("B", &[(0, 0.999)]),
+            ],
+            ..Default::default()
+        };
+
+        assert_eq!(1, object.prune_skin_weights(0.01));
+        assert_eq!(vec![(1, 0.5)], weights(&object.bone_influences[0]));
+        assert_eq!(vec![(0, 0.999)], weights(&object.bone_influences[1]));
+    }
+
+    #[test]
+    fn merge_bone_influence_existing_target() {
+        let mut object = MeshObjectData {
+            bone_influences: vec![
+                influence("A", &[(0, 0.25), (1, 1.0)]),
+                influence("Helper", &[(0, 0.75), (2, 1.0)]),
+            ],
+            ..Default::default()
+        };
+
+        assert!(object.merge_bone_influence("Helper", "A"));
+        assert_eq!(1, object.bone_influences.len());
+        assert_eq!(
+            vec![(0, 1.0), (1, 1.0), (2, 1.0)],
+            weights(&object.bone_influences[0])
+        );
+    }
+
+    #[test]
+    fn merge_bone_influence_new_target() {
+        let mut object = MeshObjectData {
+            bone_influences: vec![influence("Helper", &[(0, 1.0)])],
+            ..Default::default()
+        };
+
+        assert!(object.merge_bone_influence("Helper", "A"));
+        assert!(!object.merge_bone_influence("Helper", "A"));
+        assert_eq!("A", object.bone_influences[0].bone_name);
+        assert_eq!(vec![(0, 1.0)], weights(&object.bone_influences[0]));
+    }
+
+    #[test]
+    fn unweighted_vertices() {
+        let mut object = MeshObjectData {
+            positions: vec![AttributeData::new(
+                "Position0",
+                VectorData::Vector3(vec![[0.0; 3]; 4]),
+            )],
+            ..Default::default()
+        };
+        assert!(object.unweighted_vertices().unwrap().is_empty());
+
+        object.bone_influences = vec![
+            influence("A", &[(0, 1.0), (1, 0.0)]),
+            influence("B", &[(2, 0.5)]),
+        ];
+        assert_eq!(vec![1, 3], object.unweighted_vertices().unwrap());
+    }
+
+    #[test]
+    fn mesh_rename_remove_bone() {
+        let object = |parent: &str, bones: &[&str]| MeshObjectData {
+            parent_bone_name: parent.into(),
+            bone_influences: bones
+                .iter()
+                .map(|b| BoneInfluence {
+                    bone_name: b.to_string(),
+                    vertex_weights: vec![VertexWeight {
+                        vertex_index: 0,
+                        vertex_weight: 0.5,
+                    }],
+                })
+                .collect(),
+            ..Default::default()
+        };
+        let mut mesh = MeshData::new(
+            1,
+            10,
+            vec![object("", &["Hip", "Helper"]), object("Helper", &[])],
+        );
+
+        mesh.rename_bone("Helper", "H_Helper");
+        assert_eq!("H_Helper", mesh.objects[0].bone_influences[1].bone_name);
+        assert_eq!("H_Helper", mesh.objects[1].parent_bone_name);
+
+        let bone = |name: &str, parent_index| BoneData {
+            name: name.into(),
+            transform: Mat4::IDENTITY.to_cols_array_2d(),
+            parent_index,
+            billboard_type: BillboardType::Disabled,
+        };
+
+        let mut removed = mesh.clone();
+        let skel = SkelData {
+            major_version: 1,
+            minor_version: 0,
+            bones: vec![bone("H_Helper", None), bone("Hip", None)],
+        };
+        assert_eq!(2, removed.remove_bone("H_Helper", &skel));
+        assert_eq!(1, removed.objects[0].bone_influences.len());
+        assert_eq!("", removed.objects[1].parent_bone_name);
+
+        let skel = SkelData {
+            major_version: 1,
+            minor_version: 0,
+            bones: vec![bone("Hip", None), bone("H_Helper", Some(0))],
+        };
+        assert_eq!(2, mesh.remove_bone("H_Helper", &skel));
+        assert_eq!(1, mesh.objects[0].bone_influences.len());
+        assert_eq!(
+            1.0,
+            mesh.objects[0].bone_influences[0].vertex_weights[0].vertex_weight
+        );
+        assert_eq!("Hip", mesh.objects[1].parent_bone_name);
+        assert_eq!(0, mesh.remove_bone("H_Helper", &skel));
+    }
+
+    #[test]
+    fn mesh_remove_bone_single_bound_positions() {
+        let object = MeshObjectData {
+            parent_bone_name: "Helper".into(),
+            vertex_indices: vec![0],
+            positions: vec![AttributeData::new(
+                "Position0",
+                VectorData::Vector3(vec![[1.0, 0.0, 0.0]]),
+            )],
+            normals: vec![AttributeData::new(
+                "Normal0",
+                VectorData::Vector4(vec![[1.0, 0.0, 0.0, 1.0]]),
+            )],
+            ..Default::default()
+        };
+        let mut mesh = MeshData::new(1, 10, vec![object]);
+
+        // Rotate 90 degrees about Z and then translate.
+        let transform = Mat4::from_translation(Vec3::new(0.0, 0.0, 2.0))
+            * Mat4::from_rotation_z(std::f32::consts::FRAC_PI_2);
+        let skel = SkelData {
+            major_version: 1,
+            minor_version: 0,
+            bones: vec![
+                BoneData {
+                    name: "Hip".into(),
+                    transform: Mat4::IDENTITY.to_cols_array_2d(),
+                    parent_index: None,
+                    billboard_type: BillboardType::Disabled,
+                },
+                BoneData {
+                    name: "Helper".into(),
+                    transform: transform.to_cols_array_2d(),
+                    parent_index: Some(0),
+                    billboard_type: BillboardType::Disabled,
+                },
+            ],
+        };
+        assert_eq!(1, mesh.remove_bone("Helper", &skel));
+        assert_eq!("Hip", mesh.objects[0].parent_bone_name);
+
+        let assert_close = |expected: &[f32], actual: &VectorData| {
+            let actual = actual.to_vec4_with_w(1.0)[0];
+            for (e, a) in expected.iter().zip(actual) {
+                assert!((e - a).abs() < 1e-6, "{expected:?} != {actual:?}");
+            }
+        };
+        assert_close(&[0.0, 1.0, 2.0], &mesh.objects[0].positions[0].data);
+        assert_close(&[0.0, 1.0, 0.0, 1.0], &mesh.objects[0].normals[0].data);
+    }
+}
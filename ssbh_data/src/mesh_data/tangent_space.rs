@@ -0,0 +1,594 @@
+use std::collections::HashMap;
+
+use geometry_tools::glam::{Vec2, Vec3A};
+use geometry_tools::vectors::calculate_tangent_w;
+
+use super::{error, float_key, AttributeData, MeshObjectData, VectorData};
+
+/// Options for calculating the normals, tangents, and binormals of a [MeshObjectData].
+///
+/// Vectors are always smoothed over the faces using a vertex.
+/// Faces using a different vertex with the same position are also included
+/// unless excluded by [hard_edge_angle](#structfield.hard_edge_angle) or [respect_uv_seams](#structfield.respect_uv_seams).
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct TangentSpaceOptions {
+    /// Weight the contribution of each face by its angle at the vertex instead of by its area.
+    pub angle_weighted: bool,
+    /// The maximum angle in degrees between face normals for smoothing across vertices with the same position.
+    /// Hard edges require the vertices along the edge to already be split.
+    /// [None] smooths across all faces.
+    pub hard_edge_angle: Option<f32>,
+    /// Only smooth normals across vertices with the same position
+    /// if the vertices also have the same texture coordinates.
+    /// Tangents and binormals are never smoothed across UV seams.
+    pub respect_uv_seams: bool,
+}
+
+impl MeshObjectData {
+    /// Calculates the normals from the first position attribute and writes them to `"Normal0"`.
+    /// Existing 4 component normals preserve their W component.
+    /**
+    ```rust
+    # use ssbh_data::mesh_data::{AttributeData, MeshObjectData, TangentSpaceOptions, VectorData};
+    let mut object = MeshObjectData {
+        vertex_indices: vec![0, 1, 2],
+        positions: vec![AttributeData::new("Position0", VectorData::Vector3(vec![[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]]))],
+        ..Default::default()
+    };
+
+    object.calculate_normals(&TangentSpaceOptions::default())?;
+    assert_eq!("Normal0", object.normals[0].name);
+    assert_eq!(VectorData::Vector3(vec![[0.0, 0.0, 1.0]; 3]), object.normals[0].data);
+    # Ok::<(), Box<dyn std::error::Error>>(())
+    ```
+     */
+    pub fn calculate_normals(&mut self, options: &TangentSpaceOptions) -> Result<(), error::Error> {
+        let triangles = self.triangles()?;
+        let positions = self.required_positions()?;
+
+        let face_normals = face_normals(&positions, &triangles);
+        let corner_vectors: Vec<_> = triangles
+            .iter()
+            .zip(&face_normals)
+            .map(|(t, n)| corner_vectors(*n, &positions, t, options.angle_weighted))
+            .collect();
+
+        let mut keys = vertex_keys(&positions);
+        if options.respect_uv_seams {
+            for uvs in &self.texture_coordinates {
+                append_keys(&mut keys, &uvs.data);
+            }
+        }
+
+        let normals: Vec<_> = smooth_vectors(
+            &triangles,
+            &keys,
+            &corner_vectors,
+            &face_normals,
+            options.hard_edge_angle,
+        )
+        .into_iter()
+        .map(|n| n.normalize_or_zero())
+        .collect();
+
+        let data = vector3_data(find_attribute(&self.normals, "Normal0"), &normals);
+        set_attribute(&mut self.normals, "Normal0", data);
+        Ok(())
+    }
+
+    /// Calculates the tangents using the normals and the texture coordinates named `uv_name`
+    /// and writes them to `"Tangent0"`.
+    /// The W component stores the handedness of 1.0 or -1.0 to account for mirrored texture coordinates.
+    pub fn calculate_tangents(
+        &mut self,
+        uv_name: &str,
+        options: &TangentSpaceOptions,
+    ) -> Result<(), error::Error> {
+        let (tangents, _) = self.calculate_tangents_binormals(uv_name, options)?;
+        set_attribute(&mut self.tangents, "Tangent0", tangents);
+        Ok(())
+    }
+
+    /// Calculates the binormals using the normals and the texture coordinates named `uv_name`
+    /// and writes them to `"Binormal0"`.
+    /// Existing 4 component binormals preserve their W component.
+    pub fn calculate_binormals(
+        &mut self,
+        uv_name: &str,
+        options: &TangentSpaceOptions,
+    ) -> Result<(), error::Error> {
+        let (_, binormals) = self.calculate_tangents_binormals(uv_name, options)?;
+        set_attribute(&mut self.binormals, "Binormal0", binormals);
+        Ok(())
+    }
+
+    /// Recalculates the normals, tangents, and binormals.
+    /// This is equivalent to calling [calculate_normals](#method.calculate_normals),
+    /// [calculate_tangents](#method.calculate_tangents), and [calculate_binormals](#method.calculate_binormals).
+    /**
+    ```rust no_run
+    # use ssbh_data::mesh_data::{MeshObjectData, TangentSpaceOptions};
+    # let mut object = MeshObjectData::default();
+    let options = TangentSpaceOptions {
+        angle_weighted: true,
+        hard_edge_angle: Some(60.0),
+        respect_uv_seams: true,
+    };
+    object.calculate_tangent_space("map1", &options)?;
+    # Ok::<(), Box<dyn std::error::Error>>(())
+    ```
+     */
+    pub fn calculate_tangent_space(
+        &mut self,
+        uv_name: &str,
+        options: &TangentSpaceOptions,
+    ) -> Result<(), error::Error> {
+        self.calculate_normals(options)?;
+        let (tangents, binormals) = self.calculate_tangents_binormals(uv_name, options)?;
+        set_attribute(&mut self.tangents, "Tangent0", tangents);
+        set_attribute(&mut self.binormals, "Binormal0", binormals);
+        Ok(())
+    }
+
+    fn calculate_tangents_binormals(
+        &self,
+        uv_name: &str,
+        options: &TangentSpaceOptions,
+    ) -> Result<(VectorData, VectorData), error::Error> {
+        let triangles = self.triangles()?;
+        let positions = self.required_positions()?;
+        let normals = find_attribute(&self.normals, "Normal0")
+            .ok_or_else(|| error::Error::MissingAttribute {
+                attribute_name: "Normal0".into(),
+            })?
+            .to_glam_vec3a();
+        let uvs = self
+            .texture_coordinates
+            .iter()
+            .find(|a| a.name == uv_name)
+            .ok_or_else(|| error::Error::MissingAttribute {
+                attribute_name: uv_name.into(),
+            })?
+            .data
+            .to_glam_vec2();
+
+        let face_normals = face_normals(&positions, &triangles);
+        let mut tangent_corners = Vec::with_capacity(triangles.len());
+        let mut bitangent_corners = Vec::with_capacity(triangles.len());
+        for t in &triangles {
+            let (tangent, bitangent) = face_tangent_bitangent(&positions, &uvs, t);
+            tangent_corners.push(corner_vectors(
+                tangent,
+                &positions,
+                t,
+                options.angle_weighted,
+            ));
+            bitangent_corners.push(corner_vectors(
+                bitangent,
+                &positions,
+                t,
+                options.angle_weighted,
+            ));
+        }
+
+        // The tangent space is discontinuous along UV seams and hard edges.
+        let mut keys = vertex_keys(&positions);
+        append_keys(&mut keys, &VectorData::Vector3(to_arrays(&normals)));
+        append_keys(
+            &mut keys,
+            &VectorData::Vector2(uvs.iter().map(|uv| uv.to_array()).collect()),
+        );
+
+        let tangents = smooth_vectors(
+            &triangles,
+            &keys,
+            &tangent_corners,
+            &face_normals,
+            options.hard_edge_angle,
+        );
+        let bitangents = smooth_vectors(
+            &triangles,
+            &keys,
+            &bitangent_corners,
+            &face_normals,
+            options.hard_edge_angle,
+        );
+
+        let mut tangents_w = Vec::with_capacity(normals.len());
+        let mut binormals = Vec::with_capacity(normals.len());
+        for ((t, b), n) in tangents.iter().zip(&bitangents).zip(&normals) {
+            let n = n.normalize_or_zero();
+            let t = orthonormalize(*t, n).unwrap_or_else(|| n.any_orthonormal_vector());
+            let b = orthonormalize(*b, n).unwrap_or_else(|| n.cross(t));
+
+            let w = calculate_tangent_w(t, b, n);
+            tangents_w.push([t.x, t.y, t.z, w]);
+            binormals.push(b);
+        }
+
+        Ok((
+            VectorData::Vector4(tangents_w),
+            vector3_data(find_attribute(&self.binormals, "Binormal0"), &binormals),
+        ))
+    }
+
+    /// The data for the first position attribute.
+    pub(crate) fn required_positions(&self) -> Result<Vec<Vec3A>, error::Error> {
+        self.positions
+            .first()
+            .map(|a| a.data.to_glam_vec3a())
+            .ok_or_else(|| error::Error::MissingAttribute {
+                attribute_name: "Position0".into(),
+            })
+    }
+}
+
+fn find_attribute<'a>(attributes: &'a [AttributeData], name: &str) -> Option<&'a VectorData> {
+    // Fall back to the first attribute in case the name is different.
+    attributes
+        .iter()
+        .find(|a| a.name == name)
+        .or_else(|| attributes.first())
+        .map(|a| &a.data)
+}
+
+fn set_attribute(attributes: &mut Vec<AttributeData>, name: &str, data: VectorData) {
+    match attributes.iter_mut().find(|a| a.name == name) {
+        Some(attribute) => {
+            // The data type is only valid for the same number of components.
+            if std::mem::discriminant(&attribute.data) != std::mem::discriminant(&data) {
+                attribute.data_type = None;
+            }
+            attribute.data = data;
+        }
+        None => attributes.push(AttributeData::new(name, data)),
+    }
+}
+
+fn vector3_data(existing: Option<&VectorData>, values: &[Vec3A]) -> VectorData {
+    match existing {
+        Some(VectorData::Vector4(old)) if old.len() == values.len() => VectorData::Vector4(
+            values
+                .iter()
+                .zip(old)
+                .map(|(v, o)| [v.x, v.y, v.z, o[3]])
+                .collect(),
+        ),
+        _ => VectorData::Vector3(to_arrays(values)),
+    }
+}
+
+fn to_arrays(values: &[Vec3A]) -> Vec<[f32; 3]> {
+    values.iter().map(|v| v.to_array()).collect()
+}
+
+fn orthonormalize(v: Vec3A, n: Vec3A) -> Option<Vec3A> {
+    (v - n * n.dot(v)).try_normalize()
+}
+
+/// Unnormalized face normals with length proportional to the face area.
+fn face_normals(positions: &[Vec3A], triangles: &[[usize; 3]]) -> Vec<Vec3A> {
+    triangles
+        .iter()
+        .map(|[v0, v1, v2]| {
+            (positions[*v1] - positions[*v0]).cross(positions[*v2] - positions[*v0])
+        })
+        .collect()
+}
+
+fn face_tangent_bitangent(
+    positions: &[Vec3A],
+    uvs: &[Vec2],
+    [v0, v1, v2]: &[usize; 3],
+) -> (Vec3A, Vec3A) {
+    let pos_a = positions[*v1] - positions[*v0];
+    let pos_b = positions[*v2] - positions[*v0];
+    let uv_a = uvs[*v1] - uvs[*v0];
+    let uv_b = uvs[*v2] - uvs[*v0];
+
+    let r = 1.0 / (uv_a.x * uv_b.y - uv_b.x * uv_a.y);
+    let tangent = (pos_a * uv_b.y - pos_b * uv_a.y) * r;
+    let bitangent = (pos_b * uv_a.x - pos_a * uv_b.x) * r;
+
+    // Faces with degenerate texture coordinates don't contribute.
+    (
+        if tangent.is_finite() {
+            tangent
+        } else {
+            Vec3A::ZERO
+        },
+        if bitangent.is_finite() {
+            bitangent
+        } else {
+            Vec3A::ZERO
+        },
+    )
+}
+
+/// The contribution of a face vector to each of the face's vertices.
+fn corner_vectors(
+    face_vector: Vec3A,
+    positions: &[Vec3A],
+    triangle: &[usize; 3],
+    angle_weighted: bool,
+) -> [Vec3A; 3] {
+    if angle_weighted {
+        let direction = face_vector.normalize_or_zero();
+        [0, 1, 2].map(|i| {
+            let p = positions[triangle[i]];
+            let a = (positions[triangle[(i + 1) % 3]] - p).normalize_or_zero();
+            let b = (positions[triangle[(i + 2) % 3]] - p).normalize_or_zero();
+            direction * a.dot(b).clamp(-1.0, 1.0).acos()
+        })
+    } else {
+        [face_vector; 3]
+    }
+}
+
+fn vertex_keys(positions: &[Vec3A]) -> Vec<Vec<u32>> {
+    positions
+        .iter()
+        .map(|p| p.to_array().iter().map(|f| float_key(*f)).collect())
+        .collect()
+}
+
+fn append_keys(keys: &mut [Vec<u32>], data: &VectorData) {
+    for (i, key) in keys.iter_mut().enumerate() {
+        key.extend(data.element(i).iter().map(|f| float_key(*f)));
+    }
+}
+
+/// Sums the corner vectors of the faces for each vertex and any vertices with the same key.
+fn smooth_vectors(
+    triangles: &[[usize; 3]],
+    keys: &[Vec<u32>],
+    corner_vectors: &[[Vec3A; 3]],
+    face_normals: &[Vec3A],
+    hard_edge_angle: Option<f32>,
+) -> Vec<Vec3A> {
+    let mut vertex_corners = vec![Vec::new(); keys.len()];
+    for (f, triangle) in triangles.iter().enumerate() {
+        for (c, v) in triangle.iter().enumerate() {
+            vertex_corners[*v].push((f, c));
+        }
+    }
+
+    let mut shared_vertices: HashMap<&[u32], Vec<usize>> = HashMap::new();
+    for (v, key) in keys.iter().enumerate() {
+        shared_vertices.entry(key).or_default().push(v);
+    }
+
+    let unit_normals: Vec<_> = face_normals.iter().map(|n| n.normalize_or_zero()).collect();
+    let min_cos = hard_edge_angle.map(|a| a.to_radians().cos());
+
+    keys.iter()
+        .enumerate()
+        .map(|(v, key)| {
+            let corners = &vertex_corners[v];
+            shared_vertices[key.as_slice()]
+                .iter()
+                .flat_map(|shared| &vertex_corners[*shared])
+                .filter(|(g, _)| match min_cos {
+                    // Only smooth with faces within the angle threshold of a face using this vertex.
+                    Some(min_cos) => corners
+                        .iter()
+                        .any(|(f, _)| f == g || unit_normals[*f].dot(unit_normals[*g]) >= min_cos),
+                    None => true,
+                })
+                .map(|(g, c)| corner_vectors[*g][*c])
+                .sum()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_vectors_eq(expected: &[[f32; 3]], actual: &VectorData) {
+        match actual {
+            VectorData::Vector3(actual) => {
+                assert_eq!(expected.len(), actual.len());
+                for (e, a) in expected.iter().zip(actual) {
+                    assert!(
+                        e.iter().zip(a).all(|(e, a)| (e - a).abs() < 0.0001),
+                        "{e:?} != {a:?}"
+                    );
+                }
+            }
+            _ => panic!("unexpected component count"),
+        }
+    }
+
+    // Two perpendicular triangles that share an edge using split vertices.
+    fn split_edge() -> MeshObjectData {
+        MeshObjectData {
+            vertex_indices: vec![0, 1, 2, 3, 4, 5],
+            positions: vec![AttributeData::new(
+                "Position0",
+                VectorData::Vector3(vec![
+                    [0.0, 0.0, 0.0],
+                    [1.0, 0.0, 0.0],
+                    [0.0, 1.0, 0.0],
+                    [0.0, 0.0, 0.0],
+                    [0.0, 0.0, 1.0],
+                    [1.0, 0.0, 0.0],
+                ]),
+            )],
+            texture_coordinates: vec![AttributeData::new(
+                "map1",
+                VectorData::Vector2(vec![
+                    [0.0, 0.0],
+                    [1.0, 0.0],
+                    [0.0, 1.0],
+                    [0.0, 0.0],
+                    [0.0, 1.0],
+                    [1.0, 0.0],
+                ]),
+            )],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn calculate_normals_smooth() {
+        let mut object = split_edge();
+        object
+            .calculate_normals(&TangentSpaceOptions::default())
+            .unwrap();
+
+        let s = 0.5f32.sqrt();
+        assert_vectors_eq(
+            &[
+                [0.0, s, s],
+                [0.0, s, s],
+                [0.0, 0.0, 1.0],
+                [0.0, s, s],
+                [0.0, 1.0, 0.0],
+                [0.0, s, s],
+            ],
+            &object.normals[0].data,
+        );
+    }
+
+    #[test]
+    fn calculate_normals_hard_edges() {
+        let mut object = split_edge();
+        object
+            .calculate_normals(&TangentSpaceOptions {
+                angle_weighted: true,
+                hard_edge_angle: Some(45.0),
+                respect_uv_seams: false,
+            })
+            .unwrap();
+
+        assert_vectors_eq(
+            &[
+                [0.0, 0.0, 1.0],
+                [0.0, 0.0, 1.0],
+                [0.0, 0.0, 1.0],
+                [0.0, 1.0, 0.0],
+                [0.0, 1.0, 0.0],
+                [0.0, 1.0, 0.0],
+            ],
+            &object.normals[0].data,
+        );
+    }
+
+    #[test]
+    fn calculate_normals_uv_seams() {
+        let mut object = split_edge();
+        object.texture_coordinates[0].data = VectorData::Vector2(vec![[0.0, 0.0]; 6]);
+        if let VectorData::Vector2(uvs) = &mut object.texture_coordinates[0].data {
+            uvs[3] = [0.5, 0.5];
+        }
+
+        object
+            .calculate_normals(&TangentSpaceOptions {
+                respect_uv_seams: true,
+                ..Default::default()
+            })
+            .unwrap();
+
+        // Only the vertices at the origin are separated by a seam.
+        let s = 0.5f32.sqrt();
+        assert_vectors_eq(
+            &[
+                [0.0, 0.0, 1.0],
+                [0.0, s, s],
+                [0.0, 0.0, 1.0],
+                [0.0, 1.0, 0.0],
+                [0.0, 1.0, 0.0],
+                [0.0, s, s],
+            ],
+            &object.normals[0].data,
+        );
+    }
+
+    #[test]
+    fn calculate_normals_preserve_w() {
+        let mut object = split_edge();
+        object.normals = vec![AttributeData {
+            name: "Normal0".into(),
+            data: VectorData::Vector4(vec![[0.0, 0.0, 0.0, 0.5]; 6]),
+            data_type: Some(crate::mesh_data::AttributeDataType::HalfFloat4),
+        }];
+
+        object
+            .calculate_normals(&TangentSpaceOptions {
+                hard_edge_angle: Some(45.0),
+                ..Default::default()
+            })
+            .unwrap();
+
+        assert_eq!(1, object.normals.len());
+        assert_eq!(
+            Some(crate::mesh_data::AttributeDataType::HalfFloat4),
+            object.normals[0].data_type
+        );
+        assert_eq!(
+            VectorData::Vector4(vec![
+                [0.0, 0.0, 1.0, 0.5],
+                [0.0, 0.0, 1.0, 0.5],
+                [0.0, 0.0, 1.0, 0.5],
+                [0.0, 1.0, 0.0, 0.5],
+                [0.0, 1.0, 0.0, 0.5],
+                [0.0, 1.0, 0.0, 0.5],
+            ]),
+            object.normals[0].data
+        );
+    }
+
+    #[test]
+    fn calculate_tangent_space_mirrored_uvs() {
+        let mut object = split_edge();
+        object.vertex_indices = vec![0, 1, 2];
+        object.texture_coordinates[0].data = VectorData::Vector2(vec![
+            [0.0, 0.0],
+            [-1.0, 0.0],
+            [0.0, 1.0],
+            [0.0, 0.0],
+            [0.0, 0.0],
+            [0.0, 0.0],
+        ]);
+
+        object
+            .calculate_tangent_space("map1", &TangentSpaceOptions::default())
+            .unwrap();
+
+        assert_eq!("Tangent0", object.tangents[0].name);
+        match &object.tangents[0].data {
+            VectorData::Vector4(tangents) => assert_eq!([-1.0, 0.0, 0.0, -1.0], tangents[0]),
+            _ => panic!("unexpected component count"),
+        }
+        assert_eq!("Binormal0", object.binormals[0].name);
+        match &object.binormals[0].data {
+            VectorData::Vector3(binormals) => assert_eq!([0.0, 1.0, 0.0], binormals[0]),
+            _ => panic!("unexpected component count"),
+        }
+    }
+
+    #[test]
+    fn calculate_tangents_missing_uvs() {
+        let mut object = split_edge();
+        object
+            .calculate_normals(&TangentSpaceOptions::default())
+            .unwrap();
+
+        let result = object.calculate_tangents("bake1", &TangentSpaceOptions::default());
+        assert!(matches!(
+            result,
+            Err(error::Error::MissingAttribute { attribute_name }) if attribute_name == "bake1"
+        ));
+    }
+
+    #[test]
+    fn calculate_tangents_missing_normals() {
+        let mut object = split_edge();
+        let result = object.calculate_binormals("map1", &TangentSpaceOptions::default());
+        assert!(matches!(
+            result,
+            Err(error::Error::MissingAttribute { attribute_name }) if attribute_name == "Normal0"
+        ));
+    }
+}
@@ -0,0 +1,450 @@
+use std::collections::{BTreeMap, HashMap};
+
+use thiserror::Error;
+
+use super::{error, position_ids, MeshObjectData};
+use crate::validation::Severity;
+
+/// Options for [MeshObjectData::diagnose].
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct DiagnosticOptions {
+    /// The names of attributes read by the object's shader like `"Normal0"` or `"map1"`.
+    /// These are usually the names of the vertex shader [inputs](crate::shdr_data::Metadata#structfield.inputs).
+    pub required_attributes: Vec<String>,
+    /// Triangles with an area less than or equal to this value are reported as [TopologyIssue::ZeroAreaTriangle].
+    pub area_tolerance: f32,
+}
+
+/// A problem with the vertex data or triangles of a [MeshObjectData].
+#[derive(Debug, PartialEq, Clone, Error)]
+pub enum TopologyIssue {
+    /// The triangle uses the same vertex or vertices with the same position more than once.
+    #[error("triangle {} repeats a vertex", triangle_index)]
+    DegenerateTriangle { triangle_index: usize },
+
+    /// The triangle has distinct vertices but an area within the tolerance.
+    #[error("triangle {} has zero area", triangle_index)]
+    ZeroAreaTriangle { triangle_index: usize },
+
+    /// The triangle has the same vertex positions and winding order as an earlier triangle.
+    #[error("triangle {} duplicates triangle {}", triangle_index, duplicate_of)]
+    DuplicateTriangle {
+        triangle_index: usize,
+        duplicate_of: usize,
+    },
+
+    /// More than two triangles share the edge between the positions of two vertices.
+    #[error(
+        "edge from vertex {} to vertex {} is shared by {} triangles",
+        vertex_indices[0],
+        vertex_indices[1],
+        triangle_count
+    )]
+    NonManifoldEdge {
+        vertex_indices: [usize; 2],
+        triangle_count: usize,
+    },
+
+    /// Two triangles that share an edge have opposite winding orders.
+    #[error(
+        "triangles {} and {} share an edge but have opposite winding",
+        triangle_index,
+        adjacent_triangle_index
+    )]
+    InconsistentWinding {
+        triangle_index: usize,
+        adjacent_triangle_index: usize,
+    },
+
+    /// The attribute has NaN or infinite values.
+    #[error(
+        "attribute {} has {} vertices with NaN or infinite values starting at vertex {}",
+        attribute_name,
+        vertex_count,
+        first_vertex_index
+    )]
+    NonFiniteValues {
+        attribute_name: String,
+        first_vertex_index: usize,
+        vertex_count: usize,
+    },
+
+    /// An attribute from [required_attributes](struct.DiagnosticOptions.html#structfield.required_attributes) is missing.
+    #[error("required attribute {} is missing", attribute_name)]
+    MissingAttribute { attribute_name: String },
+}
+
+impl TopologyIssue {
+    pub fn severity(&self) -> Severity {
+        match self {
+            TopologyIssue::InconsistentWinding { .. }
+            | TopologyIssue::NonFiniteValues { .. }
+            | TopologyIssue::MissingAttribute { .. } => Severity::Error,
+            _ => Severity::Warning,
+        }
+    }
+}
+
+/// The triangles using an edge between two positions.
+struct EdgeFaces {
+    /// The first vertex for each position.
+    vertex_indices: [usize; 2],
+    /// The triangle index and whether the triangle traverses the edge from the first to the second position.
+    faces: Vec<(usize, bool)>,
+}
+
+impl MeshObjectData {
+    /// Checks the triangles and attribute values for common problems that cause rendering artifacts.
+    /// Vertices with the same position are treated as the same vertex when comparing triangles and edges.
+    /// Degenerate and zero area triangles are not included in the edge checks.
+    ///
+    /// Returns an error if the vertex indices are invalid or the object has no positions.
+    /**
+    ```rust
+    # use ssbh_data::mesh_data::{AttributeData, DiagnosticOptions, MeshObjectData, TopologyIssue, VectorData};
+    let object = MeshObjectData {
+        vertex_indices: vec![0, 1, 2, 0, 2, 1],
+        positions: vec![AttributeData::new("Position0", VectorData::Vector3(vec![[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]]))],
+        ..Default::default()
+    };
+
+    let options = DiagnosticOptions {
+        required_attributes: vec!["Normal0".into()],
+        ..Default::default()
+    };
+    let issues = object.diagnose(&options)?;
+    assert!(issues.contains(&TopologyIssue::MissingAttribute {
+        attribute_name: "Normal0".into()
+    }));
+    # Ok::<(), Box<dyn std::error::Error>>(())
+    ```
+     */
+    pub fn diagnose(
+        &self,
+        options: &DiagnosticOptions,
+    ) -> Result<Vec<TopologyIssue>, error::Error> {
+        let mut issues = Vec::new();
+
+        for name in &options.required_attributes {
+            if !self.attributes().any(|a| &a.name == name) {
+                issues.push(TopologyIssue::MissingAttribute {
+                    attribute_name: name.clone(),
+                });
+            }
+        }
+
+        for attribute in self.attributes() {
+            let mut non_finite = (0..attribute.data.len())
+                .filter(|i| attribute.data.element(*i).iter().any(|f| !f.is_finite()));
+            if let Some(first_vertex_index) = non_finite.next() {
+                issues.push(TopologyIssue::NonFiniteValues {
+                    attribute_name: attribute.name.clone(),
+                    first_vertex_index,
+                    vertex_count: non_finite.count() + 1,
+                });
+            }
+        }
+
+        let triangles = self.triangles()?;
+        let positions = self.required_positions()?;
+        let position_ids = position_ids(&self.positions[0].data);
+
+        let mut first_triangles = HashMap::new();
+        let mut valid_triangles = Vec::new();
+        for (i, [v0, v1, v2]) in triangles.iter().copied().enumerate() {
+            let ids = [position_ids[v0], position_ids[v1], position_ids[v2]];
+            if ids[0] == ids[1] || ids[1] == ids[2] || ids[0] == ids[2] {
+                issues.push(TopologyIssue::DegenerateTriangle { triangle_index: i });
+                continue;
+            }
+
+            let area = (positions[v1] - positions[v0])
+                .cross(positions[v2] - positions[v0])
+                .length()
+                * 0.5;
+            if area.is_nan() || area <= options.area_tolerance {
+                issues.push(TopologyIssue::ZeroAreaTriangle { triangle_index: i });
+                continue;
+            }
+
+            // Rotate the smallest position first to compare triangles with the same winding.
+            let start = (0..3).min_by_key(|j| ids[*j]).unwrap();
+            let key = [ids[start], ids[(start + 1) % 3], ids[(start + 2) % 3]];
+            match first_triangles.get(&key) {
+                Some(duplicate_of) => issues.push(TopologyIssue::DuplicateTriangle {
+                    triangle_index: i,
+                    duplicate_of: *duplicate_of,
+                }),
+                None => {
+                    first_triangles.insert(key, i);
+                    valid_triangles.push(i);
+                }
+            }
+        }
+
+        let edges = edge_faces(&triangles, &position_ids, &valid_triangles);
+        for edge in edges.values() {
+            if edge.faces.len() > 2 {
+                issues.push(TopologyIssue::NonManifoldEdge {
+                    vertex_indices: edge.vertex_indices,
+                    triangle_count: edge.faces.len(),
+                });
+            }
+        }
+        for edge in edges.values() {
+            if let [(t0, forward0), (t1, forward1)] = edge.faces[..] {
+                if forward0 == forward1 {
+                    issues.push(TopologyIssue::InconsistentWinding {
+                        triangle_index: t0.min(t1),
+                        adjacent_triangle_index: t0.max(t1),
+                    });
+                }
+            }
+        }
+
+        Ok(issues)
+    }
+
+    /// Reverses the winding order of triangles to match adjacent triangles sharing an edge
+    /// and returns the number of reversed triangles.
+    /// Each group of connected triangles keeps the winding order used by the majority of its triangles.
+    ///
+    /// Only edges shared by exactly two triangles are considered, and non orientable surfaces
+    /// may still have [TopologyIssue::InconsistentWinding] after fixing.
+    /// The vertex data including normals is unchanged.
+    pub fn fix_winding(&mut self) -> Result<usize, error::Error> {
+        let triangles = self.triangles()?;
+        self.required_positions()?;
+        let position_ids = position_ids(&self.positions[0].data);
+
+        let valid_triangles: Vec<_> = (0..triangles.len()).collect();
+        let edges = edge_faces(&triangles, &position_ids, &valid_triangles);
+
+        // Find the adjacent triangles and whether they traverse the shared edge in the same direction.
+        let mut adjacent = vec![Vec::new(); triangles.len()];
+        for edge in edges.values() {
+            if let [(t0, forward0), (t1, forward1)] = edge.faces[..] {
+                if t0 != t1 {
+                    adjacent[t0].push((t1, forward0 == forward1));
+                    adjacent[t1].push((t0, forward0 == forward1));
+                }
+            }
+        }
+
+        let mut flipped: Vec<Option<bool>> = vec![None; triangles.len()];
+        for seed in 0..triangles.len() {
+            if flipped[seed].is_some() {
+                continue;
+            }
+
+            flipped[seed] = Some(false);
+            let mut component = vec![seed];
+            let mut stack = vec![seed];
+            while let Some(t) = stack.pop() {
+                let flip_t = flipped[t].unwrap_or_default();
+                for (u, same_direction) in &adjacent[t] {
+                    if flipped[*u].is_none() {
+                        flipped[*u] = Some(flip_t ^ same_direction);
+                        component.push(*u);
+                        stack.push(*u);
+                    }
+                }
+            }
+
+            let flip_count = component
+                .iter()
+                .filter(|t| flipped[**t] == Some(true))
+                .count();
+            if flip_count * 2 > component.len() {
+                for t in component {
+                    flipped[t] = flipped[t].map(|f| !f);
+                }
+            }
+        }
+
+        let mut count = 0;
+        for (t, flip) in flipped.into_iter().enumerate() {
+            if flip == Some(true) {
+                self.vertex_indices.swap(t * 3 + 1, t * 3 + 2);
+                count += 1;
+            }
+        }
+        Ok(count)
+    }
+}
+
+fn edge_faces(
+    triangles: &[[usize; 3]],
+    position_ids: &[usize],
+    triangle_indices: &[usize],
+) -> BTreeMap<(usize, usize), EdgeFaces> {
+    let mut edges = BTreeMap::new();
+    for t in triangle_indices {
+        let [v0, v1, v2] = triangles[*t];
+        for (a, b) in [(v0, v1), (v1, v2), (v2, v0)] {
+            let (pa, pb) = (position_ids[a], position_ids[b]);
+            if pa == pb {
+                continue;
+            }
+            let edge = edges
+                .entry((pa.min(pb), pa.max(pb)))
+                .or_insert_with(|| EdgeFaces {
+                    vertex_indices: if pa < pb { [a, b] } else { [b, a] },
+                    faces: Vec::new(),
+                });
+            edge.faces.push((*t, pa < pb));
+        }
+    }
+    edges
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mesh_data::{AttributeData, VectorData};
+
+    fn object(positions: Vec<[f32; 3]>, vertex_indices: Vec<u32>) -> MeshObjectData {
+        MeshObjectData {
+            vertex_indices,
+            positions: vec![AttributeData::new(
+                "Position0",
+                VectorData::Vector3(positions),
+            )],
+            ..Default::default()
+        }
+    }
+
+    fn quad_positions() -> Vec<[f32; 3]> {
+        vec![
+            [0.0, 0.0, 0.0],
+            [1.0, 0.0, 0.0],
+            [0.0, 1.0, 0.0],
+            [1.0, 1.0, 0.0],
+        ]
+    }
+
+    #[test]
+    fn diagnose_clean_quad() {
+        let object = object(quad_positions(), vec![0, 1, 2, 2, 1, 3]);
+        assert!(object
+            .diagnose(&DiagnosticOptions::default())
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn diagnose_degenerate_and_duplicate() {
+        let mut positions = quad_positions();
+        // A vertex split from vertex 1 and a point between vertices 0 and 1.
+        positions.push([1.0, 0.0, 0.0]);
+        positions.push([0.5, 0.0, 0.0]);
+        let object = object(positions, vec![0, 1, 2, 0, 0, 3, 1, 4, 3, 2, 0, 4, 0, 5, 1]);
+
+        let issues = object.diagnose(&DiagnosticOptions::default()).unwrap();
+        assert_eq!(
+            vec![
+                TopologyIssue::DegenerateTriangle { triangle_index: 1 },
+                TopologyIssue::DegenerateTriangle { triangle_index: 2 },
+                TopologyIssue::DuplicateTriangle {
+                    triangle_index: 3,
+                    duplicate_of: 0
+                },
+                TopologyIssue::ZeroAreaTriangle { triangle_index: 4 },
+            ],
+            issues
+        );
+    }
+
+    #[test]
+    fn diagnose_non_manifold_and_winding() {
+        let mut positions = quad_positions();
+        positions.push([0.5, 0.5, 1.0]);
+        // Triangle 2 is a fin on the edge shared by triangles 0 and 1.
+        // Winding is only compared for edges with two triangles.
+        let fin = object(positions, vec![0, 1, 2, 1, 2, 3, 1, 4, 2]);
+
+        let issues = fin.diagnose(&DiagnosticOptions::default()).unwrap();
+        assert_eq!(
+            vec![TopologyIssue::NonManifoldEdge {
+                vertex_indices: [1, 2],
+                triangle_count: 3
+            }],
+            issues
+        );
+
+        let reversed = object(quad_positions(), vec![0, 1, 2, 1, 2, 3]);
+        let issues = reversed.diagnose(&DiagnosticOptions::default()).unwrap();
+        assert_eq!(
+            vec![TopologyIssue::InconsistentWinding {
+                triangle_index: 0,
+                adjacent_triangle_index: 1
+            }],
+            issues
+        );
+        assert_eq!(Severity::Error, issues[0].severity());
+    }
+
+    #[test]
+    fn diagnose_attributes() {
+        let mut object = object(
+            vec![[0.0; 3], [f32::NAN, 0.0, 0.0], [0.0, f32::INFINITY, 0.0]],
+            Vec::new(),
+        );
+        object.texture_coordinates = vec![AttributeData::new(
+            "map1",
+            VectorData::Vector2(vec![[0.0; 2]; 3]),
+        )];
+
+        let options = DiagnosticOptions {
+            required_attributes: vec!["map1".into(), "Normal0".into()],
+            ..Default::default()
+        };
+        assert_eq!(
+            vec![
+                TopologyIssue::MissingAttribute {
+                    attribute_name: "Normal0".into()
+                },
+                TopologyIssue::NonFiniteValues {
+                    attribute_name: "Position0".into(),
+                    first_vertex_index: 1,
+                    vertex_count: 2
+                },
+            ],
+            object.diagnose(&options).unwrap()
+        );
+    }
+
+    #[test]
+    fn diagnose_invalid_indices() {
+        let object = object(quad_positions(), vec![0, 1, 4]);
+        assert!(matches!(
+            object.diagnose(&DiagnosticOptions::default()),
+            Err(error::Error::VertexIndexOutOfRange { .. })
+        ));
+    }
+
+    #[test]
+    fn fix_winding_majority() {
+        // A strip of 4 triangles where triangle 1 is reversed.
+        let positions = vec![
+            [0.0, 0.0, 0.0],
+            [1.0, 0.0, 0.0],
+            [0.0, 1.0, 0.0],
+            [1.0, 1.0, 0.0],
+            [0.0, 2.0, 0.0],
+            [1.0, 2.0, 0.0],
+        ];
+        let mut object = object(positions, vec![0, 1, 2, 1, 2, 3, 2, 3, 4, 4, 3, 5]);
+
+        assert_eq!(1, object.fix_winding().unwrap());
+        assert_eq!(
+            vec![0, 1, 2, 1, 3, 2, 2, 3, 4, 4, 3, 5],
+            object.vertex_indices
+        );
+        assert!(object
+            .diagnose(&DiagnosticOptions::default())
+            .unwrap()
+            .is_empty());
+        assert_eq!(0, object.fix_winding().unwrap());
+    }
+}
@@ -0,0 +1,21 @@
+/// Returns the root of the set containing `i`.
+pub(crate) fn find(parents: &mut [usize], mut i: usize) -> usize {
+    while parents[i] != i {
+        // Path halving keeps the trees shallow.
+        parents[i] = parents[parents[i]];
+        i = parents[i];
+    }
+    i
+}
+
+/// Merges the sets containing `a` and `b`.
+pub(crate) fn union(parents: &mut [usize], a: usize, b: usize) {
+    let a = find(parents, a);
+    let b = find(parents, b);
+    // Keep the smallest index as the root.
+    if a < b {
+        parents[b] = a;
+    } else {
+        parents[a] = b;
+    }
+}
@@ -0,0 +1,502 @@
+use geometry_tools::glam::Vec2;
+
+use super::{error, AttributeData, AttributeDataType, MeshObjectData, VectorData};
+use crate::anim_data::UvTransform;
+
+/// The names of the texture coordinate layers used by Smash Ultimate in their conventional order.
+///
+/// Mesh version 1.8 uses `"TextureCoordinate0"` and similar names instead,
+/// which are not included.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy)]
+pub enum UvLayer {
+    /// `"map1"` for the main texture coordinates.
+    Map1,
+    /// `"bake1"` for baked lighting and ambient occlusion maps.
+    Bake1,
+    /// `"uvSet"` for additional texture layers.
+    UvSet,
+    /// `"uvSet1"` for additional texture layers.
+    UvSet1,
+    /// `"uvSet2"` for additional texture layers.
+    UvSet2,
+}
+
+impl UvLayer {
+    /// The attribute name for this layer like `"map1"`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            UvLayer::Map1 => "map1",
+            UvLayer::Bake1 => "bake1",
+            UvLayer::UvSet => "uvSet",
+            UvLayer::UvSet1 => "uvSet1",
+            UvLayer::UvSet2 => "uvSet2",
+        }
+    }
+
+    /// Finds the layer for an attribute name like `"map1"` or [None] if the name is not recognized.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "map1" => Some(UvLayer::Map1),
+            "bake1" => Some(UvLayer::Bake1),
+            "uvSet" => Some(UvLayer::UvSet),
+            "uvSet1" => Some(UvLayer::UvSet1),
+            "uvSet2" => Some(UvLayer::UvSet2),
+            _ => None,
+        }
+    }
+}
+
+/// The scale the game applies to the stored values of the color set with the given `name`.
+///
+/// Color sets are usually stored as [AttributeDataType::Byte4] with values in the range 0.0 to 1.0.
+/// The values for `"colorSet1"` and `"colorSet3"` are scaled by 2.0,
+/// so a stored value of 0.5 is a neutral color of 1.0 in game.
+/// All other color sets have a scale of 1.0.
+/**
+```rust
+# use ssbh_data::mesh_data::color_set_scale;
+assert_eq!(2.0, color_set_scale("colorSet1"));
+assert_eq!(1.0, color_set_scale("colorSet5"));
+```
+ */
+pub fn color_set_scale(name: &str) -> f32 {
+    match name {
+        "colorSet1" | "colorSet3" => 2.0,
+        _ => 1.0,
+    }
+}
+
+impl MeshObjectData {
+    /// Flips the V coordinate of the texture coordinate attribute `name` by replacing `v` with `1.0 - v`.
+    /// This converts between applications that place the UV origin at the top left and bottom left of the texture.
+    pub fn flip_uvs_v(&mut self, name: &str) -> Result<(), error::Error> {
+        let data = &mut texture_coordinate_mut(&mut self.texture_coordinates, name)?.data;
+        for i in 0..data.len() {
+            if let Some(v) = data.element_mut(i).get_mut(1) {
+                *v = 1.0 - *v;
+            }
+        }
+        Ok(())
+    }
+
+    /// Applies `transform` to the texture coordinate attribute `name`.
+    /// Coordinates are scaled, then rotated counterclockwise by [rotation](crate::anim_data::UvTransform#structfield.rotation)
+    /// in radians around the origin, and then translated.
+    /// Any components after U and V are unchanged.
+    /**
+    ```rust
+    # use ssbh_data::mesh_data::{AttributeData, MeshObjectData, VectorData};
+    # use ssbh_data::anim_data::UvTransform;
+    let mut object = MeshObjectData {
+        texture_coordinates: vec![AttributeData::new("map1", VectorData::Vector2(vec![[0.5, 0.25]]))],
+        ..Default::default()
+    };
+
+    let transform = UvTransform {
+        scale_u: 2.0,
+        scale_v: 2.0,
+        rotation: 0.0,
+        translate_u: 0.5,
+        translate_v: 0.0,
+    };
+    object.apply_uv_transform("map1", &transform)?;
+    assert_eq!(VectorData::Vector2(vec![[1.5, 0.5]]), object.texture_coordinates[0].data);
+    # Ok::<(), Box<dyn std::error::Error>>(())
+    ```
+     */
+    pub fn apply_uv_transform(
+        &mut self,
+        name: &str,
+        transform: &UvTransform,
+    ) -> Result<(), error::Error> {
+        let data = &mut texture_coordinate_mut(&mut self.texture_coordinates, name)?.data;
+
+        let scale = Vec2::new(transform.scale_u, transform.scale_v);
+        let rotation = Vec2::from_angle(transform.rotation);
+        let translation = Vec2::new(transform.translate_u, transform.translate_v);
+
+        for i in 0..data.len() {
+            let element = data.element_mut(i);
+            if let [u, v, ..] = element {
+                let uv = rotation.rotate(Vec2::new(*u, *v) * scale) + translation;
+                *u = uv.x;
+                *v = uv.y;
+            }
+        }
+        Ok(())
+    }
+
+    /// The U and V coordinates for each recognized [UvLayer] in [texture_coordinates](#structfield.texture_coordinates)
+    /// in the order they appear in the object.
+    /// Attributes with unrecognized names are skipped.
+    pub fn uv_layers(&self) -> Vec<(UvLayer, Vec<[f32; 2]>)> {
+        self.texture_coordinates
+            .iter()
+            .filter_map(|a| {
+                UvLayer::from_name(&a.name).map(|layer| {
+                    let uvs = a.data.to_glam_vec2().into_iter().map(|uv| uv.to_array());
+                    (layer, uvs.collect())
+                })
+            })
+            .collect()
+    }
+
+    /// Replaces the recognized [UvLayer] attributes in [texture_coordinates](#structfield.texture_coordinates)
+    /// with `layers` sorted in the conventional [UvLayer] order.
+    /// Attributes with unrecognized names like `"TextureCoordinate0"` are kept after the layers.
+    /// Layers with the same name as an existing attribute keep the existing [data_type](struct.AttributeData.html#structfield.data_type).
+    ///
+    /// Returns [error::Error::DuplicateUvLayer] if `layers` contains the same layer more than once.
+    /// Returns [error::Error::AttributeDataLengthMismatch] and leaves the object unchanged
+    /// if the layers do not all have the same number of vertices as the object.
+    pub fn set_uv_layers(
+        &mut self,
+        mut layers: Vec<(UvLayer, Vec<[f32; 2]>)>,
+    ) -> Result<(), error::Error> {
+        layers.sort_by_key(|(layer, _)| *layer);
+        if let Some(w) = layers.windows(2).find(|w| w[0].0 == w[1].0) {
+            return Err(error::Error::DuplicateUvLayer {
+                attribute_name: w[0].0.name().to_string(),
+            });
+        }
+
+        let unrecognized: Vec<_> = self
+            .texture_coordinates
+            .iter()
+            .filter(|a| UvLayer::from_name(&a.name).is_none())
+            .cloned()
+            .collect();
+
+        let texture_coordinates = layers
+            .into_iter()
+            .map(|(layer, uvs)| AttributeData {
+                name: layer.name().to_string(),
+                data: VectorData::Vector2(uvs),
+                data_type: self
+                    .texture_coordinates
+                    .iter()
+                    .find(|a| a.name == layer.name())
+                    .and_then(|a| a.data_type),
+            })
+            .chain(unrecognized)
+            .collect();
+
+        let previous = std::mem::replace(&mut self.texture_coordinates, texture_coordinates);
+        if let Err(e) = self.vertex_count() {
+            self.texture_coordinates = previous;
+            return Err(e);
+        }
+        Ok(())
+    }
+
+    /// The in game RGBA values for the color set attribute `name` after applying [color_set_scale].
+    /// Color sets with fewer than 4 components use a stored alpha of 1.0.
+    pub fn color_set_values(&self, name: &str) -> Result<Vec<[f32; 4]>, error::Error> {
+        let attribute = self
+            .color_sets
+            .iter()
+            .find(|a| a.name == name)
+            .ok_or_else(|| missing_attribute(name))?;
+
+        let scale = color_set_scale(name);
+        Ok((0..attribute.data.len())
+            .map(|i| {
+                let mut color = [0.0, 0.0, 0.0, 1.0];
+                for (c, x) in color.iter_mut().zip(attribute.data.element(i)) {
+                    *c = *x;
+                }
+                color.map(|x| x * scale)
+            })
+            .collect())
+    }
+
+    /// Sets the in game RGBA `values` for the color set attribute `name` by dividing by [color_set_scale].
+    /// Values for [AttributeDataType::Byte4] attributes are clamped to the supported range of 0.0 to 1.0.
+    ///
+    /// A new attribute uses [AttributeDataType::Byte4],
+    /// and an existing attribute keeps its [data_type](struct.AttributeData.html#structfield.data_type).
+    /// Returns [error::Error::AttributeDataLengthMismatch] and leaves the object unchanged
+    /// if `values` does not have the same number of vertices as the object.
+    /**
+    ```rust
+    # use ssbh_data::mesh_data::{MeshObjectData, VectorData};
+    let mut object = MeshObjectData::default();
+
+    // A neutral color of 1.0 is stored as 0.5 for colorSet1.
+    object.set_color_set_values("colorSet1", &[[1.0, 1.0, 1.0, 1.0]])?;
+    assert_eq!(VectorData::Vector4(vec![[0.5; 4]]), object.color_sets[0].data);
+    assert_eq!(vec![[1.0; 4]], object.color_set_values("colorSet1")?);
+    # Ok::<(), Box<dyn std::error::Error>>(())
+    ```
+     */
+    pub fn set_color_set_values(
+        &mut self,
+        name: &str,
+        values: &[[f32; 4]],
+    ) -> Result<(), error::Error> {
+        let scale = color_set_scale(name);
+        let previous = self.color_sets.clone();
+
+        // Color sets without a data type are saved as Byte4.
+        let attribute = match self.color_sets.iter().position(|a| a.name == name) {
+            Some(index) => &mut self.color_sets[index],
+            None => {
+                self.color_sets.push(AttributeData {
+                    name: name.to_string(),
+                    data: VectorData::Vector4(Vec::new()),
+                    data_type: Some(AttributeDataType::Byte4),
+                });
+                self.color_sets.last_mut().unwrap()
+            }
+        };
+        let clamp =
+            attribute.data_type.unwrap_or(AttributeDataType::Byte4) == AttributeDataType::Byte4;
+        attribute.data = VectorData::Vector4(
+            values
+                .iter()
+                .map(|c| {
+                    c.map(|x| {
+                        if clamp {
+                            (x / scale).clamp(0.0, 1.0)
+                        } else {
+                            x / scale
+                        }
+                    })
+                })
+                .collect(),
+        );
+
+        if let Err(e) = self.vertex_count() {
+            self.color_sets = previous;
+            return Err(e);
+        }
+        Ok(())
+    }
+}
+
+fn texture_coordinate_mut<'a>(
+    attributes: &'a mut [AttributeData],
+    name: &str,
+) -> Result<&'a mut AttributeData, error::Error> {
+    attributes
+        .iter_mut()
+        .find(|a| a.name == name)
+        .ok_or_else(|| missing_attribute(name))
+}
+
+fn missing_attribute(name: &str) -> error::Error {
+    error::Error::MissingAttribute {
+        attribute_name: name.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn uvs(name: &str, data: VectorData) -> AttributeData {
+        AttributeData::new(name, data)
+    }
+
+    #[test]
+    fn flip_uvs_v() {
+        let mut object = MeshObjectData {
+            texture_coordinates: vec![
+                uvs("map1", VectorData::Vector2(vec![[0.25, 0.0], [0.5, 0.75]])),
+                uvs("bake1", VectorData::Vector2(vec![[0.25, 0.0], [0.5, 0.75]])),
+            ],
+            ..Default::default()
+        };
+        object.flip_uvs_v("map1").unwrap();
+
+        assert_eq!(
+            VectorData::Vector2(vec![[0.25, 1.0], [0.5, 0.25]]),
+            object.texture_coordinates[0].data
+        );
+        assert_eq!(
+            VectorData::Vector2(vec![[0.25, 0.0], [0.5, 0.75]]),
+            object.texture_coordinates[1].data
+        );
+        assert!(matches!(
+            object.flip_uvs_v("uvSet"),
+            Err(error::Error::MissingAttribute { attribute_name }) if attribute_name == "uvSet"
+        ));
+    }
+
+    #[test]
+    fn apply_uv_transform_rotation() {
+        let mut object = MeshObjectData {
+            texture_coordinates: vec![uvs("map1", VectorData::Vector3(vec![[1.0, 0.0, 5.0]]))],
+            ..Default::default()
+        };
+        let transform = UvTransform {
+            scale_u: 2.0,
+            scale_v: 1.0,
+            rotation: std::f32::consts::FRAC_PI_2,
+            translate_u: 0.0,
+            translate_v: 1.0,
+        };
+        object.apply_uv_transform("map1", &transform).unwrap();
+
+        let VectorData::Vector3(data) = &object.texture_coordinates[0].data else {
+            panic!("unexpected data");
+        };
+        assert!((data[0][0] - 0.0).abs() < 1e-6);
+        assert!((data[0][1] - 3.0).abs() < 1e-6);
+        assert_eq!(5.0, data[0][2]);
+    }
+
+    #[test]
+    fn pack_unpack_uv_layers() {
+        let mut object = MeshObjectData {
+            positions: vec![uvs("Position0", VectorData::Vector3(vec![[0.0; 3]; 2]))],
+            texture_coordinates: vec![
+                AttributeData {
+                    name: "bake1".into(),
+                    data: VectorData::Vector2(vec![[0.0; 2]; 2]),
+                    data_type: Some(AttributeDataType::Float2),
+                },
+                uvs("custom", VectorData::Vector2(vec![[0.0; 2]; 2])),
+            ],
+            ..Default::default()
+        };
+
+        object
+            .set_uv_layers(vec![
+                (UvLayer::UvSet, vec![[0.5, 0.5]; 2]),
+                (UvLayer::Bake1, vec![[0.25, 0.25]; 2]),
+                (UvLayer::Map1, vec![[1.0, 0.0]; 2]),
+            ])
+            .unwrap();
+
+        let names: Vec<_> = object
+            .texture_coordinates
+            .iter()
+            .map(|a| a.name.as_str())
+            .collect();
+        assert_eq!(vec!["map1", "bake1", "uvSet", "custom"], names);
+        assert_eq!(None, object.texture_coordinates[0].data_type);
+        assert_eq!(
+            Some(AttributeDataType::Float2),
+            object.texture_coordinates[1].data_type
+        );
+
+        assert_eq!(
+            vec![
+                (UvLayer::Map1, vec![[1.0, 0.0]; 2]),
+                (UvLayer::Bake1, vec![[0.25, 0.25]; 2]),
+                (UvLayer::UvSet, vec![[0.5, 0.5]; 2]),
+            ],
+            object.uv_layers()
+        );
+
+        // The vertex count must match the other attributes.
+        assert!(matches!(
+            object.set_uv_layers(vec![(UvLayer::Map1, vec![[0.0; 2]; 3])]),
+            Err(error::Error::AttributeDataLengthMismatch)
+        ));
+        assert_eq!(4, object.texture_coordinates.len());
+    }
+
+    #[test]
+    fn set_uv_layers_keeps_unrecognized_attributes() {
+        let mut object = MeshObjectData {
+            texture_coordinates: vec![
+                uvs("TextureCoordinate0", VectorData::Vector2(vec![[0.0; 2]])),
+                uvs("map1", VectorData::Vector2(vec![[0.0; 2]])),
+                uvs("custom", VectorData::Vector2(vec![[0.0; 2]])),
+            ],
+            ..Default::default()
+        };
+
+        object
+            .set_uv_layers(vec![(UvLayer::Bake1, vec![[1.0; 2]])])
+            .unwrap();
+
+        let names: Vec<_> = object
+            .texture_coordinates
+            .iter()
+            .map(|a| a.name.as_str())
+            .collect();
+        assert_eq!(vec!["bake1", "TextureCoordinate0", "custom"], names);
+    }
+
+    #[test]
+    fn set_uv_layers_duplicate_layer() {
+        let mut object = MeshObjectData {
+            texture_coordinates: vec![uvs("map1", VectorData::Vector2(vec![[0.0; 2]]))],
+            ..Default::default()
+        };
+
+        let result = object.set_uv_layers(vec![
+            (UvLayer::Map1, vec![[1.0; 2]]),
+            (UvLayer::Bake1, vec![[1.0; 2]]),
+            (UvLayer::Map1, vec![[0.5; 2]]),
+        ]);
+        assert!(matches!(
+            result,
+            Err(error::Error::DuplicateUvLayer { attribute_name }) if attribute_name == "map1"
+        ));
+        assert_eq!(vec![(UvLayer::Map1, vec![[0.0; 2]])], object.uv_layers());
+    }
+
+    #[test]
+    fn color_set_scale_conventions() {
+        let mut object = MeshObjectData {
+            color_sets: vec![AttributeData {
+                name: "colorSet5".into(),
+                data: VectorData::Vector4(vec![[0.0; 4]; 2]),
+                data_type: Some(AttributeDataType::HalfFloat4),
+            }],
+            ..Default::default()
+        };
+
+        object
+            .set_color_set_values("colorSet1", &[[2.0, 1.0, 0.5, 3.0], [-1.0, 0.0, 0.0, 1.0]])
+            .unwrap();
+        object
+            .set_color_set_values("colorSet5", &[[0.5; 4], [1.0; 4]])
+            .unwrap();
+
+        assert_eq!("colorSet1", object.color_sets[1].name);
+        assert_eq!(
+            Some(AttributeDataType::Byte4),
+            object.color_sets[1].data_type
+        );
+        assert_eq!(
+            VectorData::Vector4(vec![[1.0, 0.5, 0.25, 1.0], [0.0, 0.0, 0.0, 0.5]]),
+            object.color_sets[1].data
+        );
+        assert_eq!(
+            vec![[2.0, 1.0, 0.5, 2.0], [0.0, 0.0, 0.0, 1.0]],
+            object.color_set_values("colorSet1").unwrap()
+        );
+
+        assert_eq!(
+            Some(AttributeDataType::HalfFloat4),
+            object.color_sets[0].data_type
+        );
+        assert_eq!(
+            vec![[0.5; 4], [1.0; 4]],
+            object.color_set_values("colorSet5").unwrap()
+        );
+
+        // Only Byte4 values are clamped.
+        object
+            .set_color_set_values("colorSet5", &[[3.0; 4], [-1.0; 4]])
+            .unwrap();
+        assert_eq!(
+            vec![[3.0; 4], [-1.0; 4]],
+            object.color_set_values("colorSet5").unwrap()
+        );
+
+        assert!(matches!(
+            object.set_color_set_values("colorSet1", &[[1.0; 4]]),
+            Err(error::Error::AttributeDataLengthMismatch)
+        ));
+        assert_eq!(
+            VectorData::Vector4(vec![[1.0, 0.5, 0.25, 1.0], [0.0, 0.0, 0.0, 0.5]]),
+            object.color_sets[1].data
+        );
+    }
+}
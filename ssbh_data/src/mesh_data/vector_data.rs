@@ -6,15 +6,17 @@ use half::f16;
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 use ssbh_lib::formats::mesh::{AttributeDataTypeV10, AttributeDataTypeV8};
+use std::collections::HashMap;
 use std::ops::Mul;
 
-use super::{DataType, Half};
+use super::{AttributeDataType, Half};
 
 /// The data for a vertex attribute.
 ///
-/// The precision when saving is inferred based on supported data types for the version specified in the [MeshData](super::MeshData).
+/// The precision when saving is determined by [data_type](super::AttributeData::data_type) if present.
+/// Otherwise, the precision is inferred based on supported data types for the version specified in the [MeshData](super::MeshData).
 /// For example, position attributes will prefer the highest available precision ([f32]), and color sets will prefer the lowest available precision ([u8]).
-/// *The data type selected for saving may change between releases but will always retain the specified component count such as [VectorData::Vector2] vs [VectorData::Vector4].*
+/// *The inferred data type may change between releases but will always retain the specified component count such as [VectorData::Vector2] vs [VectorData::Vector4].*
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[derive(Debug, Clone, PartialEq)]
@@ -124,30 +126,57 @@ impl VectorData {
         }
     }
 
+    /// The components of the element at `index`.
+    pub(crate) fn element(&self, index: usize) -> &[f32] {
+        match self {
+            VectorData::Vector2(v) => &v[index],
+            VectorData::Vector3(v) => &v[index],
+            VectorData::Vector4(v) => &v[index],
+        }
+    }
+
+    /// The mutable components of the element at `index`.
+    pub(crate) fn element_mut(&mut self, index: usize) -> &mut [f32] {
+        match self {
+            VectorData::Vector2(v) => &mut v[index],
+            VectorData::Vector3(v) => &mut v[index],
+            VectorData::Vector4(v) => &mut v[index],
+        }
+    }
+
+    /// Creates new data with the element at each index in `indices`.
+    pub(crate) fn select(&self, indices: &[usize]) -> Self {
+        match self {
+            VectorData::Vector2(v) => VectorData::Vector2(indices.iter().map(|i| v[*i]).collect()),
+            VectorData::Vector3(v) => VectorData::Vector3(indices.iter().map(|i| v[*i]).collect()),
+            VectorData::Vector4(v) => VectorData::Vector4(indices.iter().map(|i| v[*i]).collect()),
+        }
+    }
+
     pub(crate) fn read<R: Read + Seek>(
         reader: &mut R,
         count: usize,
         offset: u64,
         stride: u64,
-        data_type: DataType,
+        data_type: AttributeDataType,
     ) -> BinResult<Self> {
         match data_type {
-            DataType::Float2 => Ok(VectorData::Vector2(read_vector_data::<_, f32, 2>(
+            AttributeDataType::Float2 => Ok(VectorData::Vector2(read_vector_data::<_, f32, 2>(
                 reader, count, offset, stride,
             )?)),
-            DataType::Float3 => Ok(VectorData::Vector3(read_vector_data::<_, f32, 3>(
+            AttributeDataType::Float3 => Ok(VectorData::Vector3(read_vector_data::<_, f32, 3>(
                 reader, count, offset, stride,
             )?)),
-            DataType::Float4 => Ok(VectorData::Vector4(read_vector_data::<_, f32, 4>(
+            AttributeDataType::Float4 => Ok(VectorData::Vector4(read_vector_data::<_, f32, 4>(
                 reader, count, offset, stride,
             )?)),
-            DataType::HalfFloat2 => Ok(VectorData::Vector2(read_vector_data::<_, Half, 2>(
-                reader, count, offset, stride,
-            )?)),
-            DataType::HalfFloat4 => Ok(VectorData::Vector4(read_vector_data::<_, Half, 4>(
-                reader, count, offset, stride,
-            )?)),
-            DataType::Byte4 => {
+            AttributeDataType::HalfFloat2 => Ok(VectorData::Vector2(
+                read_vector_data::<_, Half, 2>(reader, count, offset, stride)?,
+            )),
+            AttributeDataType::HalfFloat4 => Ok(VectorData::Vector4(
+                read_vector_data::<_, Half, 4>(reader, count, offset, stride)?,
+            )),
+            AttributeDataType::Byte4 => {
                 let mut elements = read_vector_data::<_, u8, 4>(reader, count, offset, stride)?;
                 // Normalize the values by converting from the range [0u8, 255u8] to [0.0f32, 1.0f32].
                 for [x, y, z, w] in elements.iter_mut() {
@@ -243,6 +272,31 @@ impl VectorDataV10 {
             VectorData::Vector4(v) => VectorDataV10::Byte4(get_clamped_u8_vectors(v)),
         }
     }
+
+    /// Converts the data to `data_type` or returns [None] if the component counts differ.
+    pub fn from_data_type(data: &VectorData, data_type: AttributeDataType) -> Option<Self> {
+        match (data, data_type) {
+            (VectorData::Vector2(v), AttributeDataType::Float2) => {
+                Some(VectorDataV10::Float2(v.clone()))
+            }
+            (VectorData::Vector2(v), AttributeDataType::HalfFloat2) => {
+                Some(VectorDataV10::HalfFloat2(get_f16_vectors(v)))
+            }
+            (VectorData::Vector3(v), AttributeDataType::Float3) => {
+                Some(VectorDataV10::Float3(v.clone()))
+            }
+            (VectorData::Vector4(v), AttributeDataType::Float4) => {
+                Some(VectorDataV10::Float4(v.clone()))
+            }
+            (VectorData::Vector4(v), AttributeDataType::HalfFloat4) => {
+                Some(VectorDataV10::HalfFloat4(get_f16_vectors(v)))
+            }
+            (VectorData::Vector4(v), AttributeDataType::Byte4) => {
+                Some(VectorDataV10::Byte4(get_clamped_u8_vectors(v)))
+            }
+            _ => None,
+        }
+    }
 }
 
 impl VectorDataV8 {
@@ -295,6 +349,29 @@ impl VectorDataV8 {
             VectorData::Vector4(v) => VectorDataV8::Byte4(get_clamped_u8_vectors(v)),
         }
     }
+
+    /// Converts the data to `data_type` or returns [None] if the component counts differ
+    /// or the data type is not supported.
+    pub fn from_data_type(data: &VectorData, data_type: AttributeDataType) -> Option<Self> {
+        match (data, data_type) {
+            (VectorData::Vector2(v), AttributeDataType::Float2) => {
+                Some(VectorDataV8::Float2(v.clone()))
+            }
+            (VectorData::Vector3(v), AttributeDataType::Float3) => {
+                Some(VectorDataV8::Float3(v.clone()))
+            }
+            (VectorData::Vector4(v), AttributeDataType::Float4) => {
+                Some(VectorDataV8::Float4(v.clone()))
+            }
+            (VectorData::Vector4(v), AttributeDataType::HalfFloat4) => {
+                Some(VectorDataV8::HalfFloat4(get_f16_vectors(v)))
+            }
+            (VectorData::Vector4(v), AttributeDataType::Byte4) => {
+                Some(VectorDataV8::Byte4(get_clamped_u8_vectors(v)))
+            }
+            _ => None,
+        }
+    }
 }
 
 fn get_f16_vector<const N: usize>(vector: &[f32; N]) -> [f16; N] {
@@ -356,6 +433,27 @@ fn read_vector_data<
     Ok(result)
 }
 
+/// Returns a hashable key for `f` that treats 0.0 and -0.0 as equal.
+pub(crate) fn float_key(f: f32) -> u32 {
+    if f == 0.0 {
+        0
+    } else {
+        f.to_bits()
+    }
+}
+
+/// Assigns the same id to vertices with the same position.
+pub(crate) fn position_ids(positions: &VectorData) -> Vec<usize> {
+    let mut ids = HashMap::new();
+    (0..positions.len())
+        .map(|i| {
+            let key: Vec<_> = positions.element(i).iter().map(|f| float_key(*f)).collect();
+            let count = ids.len();
+            *ids.entry(key).or_insert(count)
+        })
+        .collect()
+}
+
 fn get_u8_clamped(f: f32) -> u8 {
     f.clamp(0.0f32, 1.0f32).mul(255.0f32).round() as u8
 }
@@ -410,14 +508,14 @@ mod tests {
     #[test]
     fn read_vector_data_count0() {
         let mut reader = Cursor::new(hex!("01020304"));
-        let values = VectorData::read(&mut reader, 0, 0, 0, DataType::Byte4).unwrap();
+        let values = VectorData::read(&mut reader, 0, 0, 0, AttributeDataType::Byte4).unwrap();
         assert_eq!(VectorData::Vector4(Vec::new()), values);
     }
 
     #[test]
     fn read_vector_data_count1() {
         let mut reader = Cursor::new(hex!("004080FF"));
-        let values = VectorData::read(&mut reader, 1, 0, 4, DataType::Byte4).unwrap();
+        let values = VectorData::read(&mut reader, 1, 0, 4, AttributeDataType::Byte4).unwrap();
         // https://registry.khronos.org/vulkan/specs/1.3/html/chap3.html#fundamentals-fixedfpconv
         assert_eq!(
             VectorData::Vector4(vec![[
@@ -435,7 +533,7 @@ mod tests {
         // This should return an error and not attempt to read the specified number of elements.
         // This prevents a potential panic from a failed allocation.
         let mut reader = Cursor::new(hex!("01020304"));
-        let result = VectorData::read(&mut reader, usize::MAX, 0, 0, DataType::Byte4);
+        let result = VectorData::read(&mut reader, usize::MAX, 0, 0, AttributeDataType::Byte4);
         assert!(result.is_err());
     }
 
@@ -444,7 +542,7 @@ mod tests {
         // This should return an error and not attempt to read the specified number of elements.
         // This prevents a potential panic from a failed allocation.
         let mut reader = Cursor::new(hex!("01020304"));
-        let result = VectorData::read(&mut reader, usize::MAX, 0, 1, DataType::Byte4);
+        let result = VectorData::read(&mut reader, usize::MAX, 0, 1, AttributeDataType::Byte4);
         assert!(result.is_err());
     }
 
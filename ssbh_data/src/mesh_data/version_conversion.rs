@@ -0,0 +1,446 @@
+use super::{error, AttributeData, AttributeDataType, MeshData, MeshObjectData, VectorData};
+
+/// A change to the data of a [MeshData] required by the target version in [MeshData::convert_version].
+#[derive(Debug, Clone, PartialEq)]
+pub enum VersionConversionIssue {
+    /// Version 1.8 does not support binormals, so the attribute is removed.
+    BinormalRemoved {
+        mesh_object_name: String,
+        mesh_object_subindex: u64,
+        attribute_name: String,
+    },
+
+    /// Version 1.8 does not store attribute names,
+    /// so the attribute is renamed to the name generated when reading based on its usage and order.
+    AttributeRenamed {
+        mesh_object_name: String,
+        mesh_object_subindex: u64,
+        attribute_name: String,
+        new_attribute_name: String,
+    },
+
+    /// The data type is not supported by the target version, so it is replaced with a supported data type.
+    DataTypeChanged {
+        mesh_object_name: String,
+        mesh_object_subindex: u64,
+        attribute_name: String,
+        data_type: AttributeDataType,
+        new_data_type: AttributeDataType,
+    },
+
+    /// Version 1.10 stores skin weight vertex indices as [u16],
+    /// so weights for vertices with larger indices are removed.
+    SkinWeightsRemoved {
+        mesh_object_name: String,
+        mesh_object_subindex: u64,
+        bone_name: String,
+        /// The number of removed weights for this bone.
+        count: usize,
+        limit: usize,
+    },
+}
+
+impl MeshData {
+    /// Converts the data to the given version and lists every attribute or feature that was removed or modified.
+    /// The returned [MeshData] contains the data that will be saved for the target version.
+    ///
+    /// Attributes without a [data_type](struct.AttributeData.html#structfield.data_type) are saved
+    /// using a data type inferred for each version.
+    /// If the inferred data type differs for the target version, the attribute uses the inferred data type
+    /// for the current version instead, so any changes are listed like attributes with a data type.
+    ///
+    /// If `strict` is `true`, any removed or modified data
+    /// returns [error::Error::LossyVersionConversion] with the list of issues instead.
+    /// Converting to an unsupported version returns [error::Error::UnsupportedVersion].
+    /**
+    ```rust
+    # use ssbh_data::mesh_data::{AttributeData, MeshData, MeshObjectData, VectorData, VersionConversionIssue};
+    let mesh = MeshData::new(1, 10, vec![MeshObjectData {
+        name: "object".into(),
+        binormals: vec![AttributeData::new("map1", VectorData::Vector3(Vec::new()))],
+        ..Default::default()
+    }]);
+
+    let (converted, issues) = mesh.convert_version(1, 8, false)?;
+    assert!(converted.objects[0].binormals.is_empty());
+    assert!(matches!(issues[0], VersionConversionIssue::BinormalRemoved { .. }));
+
+    // Strict conversions fail instead.
+    assert!(mesh.convert_version(1, 8, true).is_err());
+    # Ok::<(), Box<dyn std::error::Error>>(())
+    ```
+     */
+    pub fn convert_version(
+        &self,
+        major_version: u16,
+        minor_version: u16,
+        strict: bool,
+    ) -> Result<(MeshData, Vec<VersionConversionIssue>), error::Error> {
+        if !matches!((major_version, minor_version), (1, 8) | (1, 9) | (1, 10)) {
+            return Err(error::Error::UnsupportedVersion {
+                major_version,
+                minor_version,
+            });
+        }
+
+        let mut mesh = self.clone();
+        mesh.major_version = major_version;
+        mesh.minor_version = minor_version;
+
+        let mut issues = Vec::new();
+        for object in &mut mesh.objects {
+            convert_object(object, self.minor_version, minor_version, &mut issues);
+        }
+
+        if strict && !issues.is_empty() {
+            return Err(error::Error::LossyVersionConversion {
+                major_version,
+                minor_version,
+                issues,
+            });
+        }
+
+        Ok((mesh, issues))
+    }
+}
+
+fn convert_object(
+    object: &mut MeshObjectData,
+    current_minor_version: u16,
+    minor_version: u16,
+    issues: &mut Vec<VersionConversionIssue>,
+) {
+    let name = object.name.clone();
+    let subindex = object.subindex;
+
+    // Keep the current inferred data type, so the checks below report any changes.
+    let current_data_type = inferred_vector2_data_type(current_minor_version);
+    if current_data_type != inferred_vector2_data_type(minor_version) {
+        for attribute in object
+            .normals
+            .iter_mut()
+            .chain(&mut object.binormals)
+            .chain(&mut object.tangents)
+            .chain(&mut object.texture_coordinates)
+            .chain(&mut object.color_sets)
+            .filter(|a| a.data_type.is_none() && matches!(a.data, VectorData::Vector2(_)))
+        {
+            attribute.data_type = Some(current_data_type);
+        }
+    }
+
+    if minor_version == 8 {
+        issues.extend(object.binormals.drain(..).map(|a| {
+            VersionConversionIssue::BinormalRemoved {
+                mesh_object_name: name.clone(),
+                mesh_object_subindex: subindex,
+                attribute_name: a.name,
+            }
+        }));
+
+        // Use the same names as Attribute::to_attribute for AttributeV8.
+        for (attributes, prefix) in [
+            (&mut object.positions, "Position"),
+            (&mut object.normals, "Normal"),
+            (&mut object.tangents, "Tangent"),
+            (&mut object.texture_coordinates, "TextureCoordinate"),
+            (&mut object.color_sets, "colorSet"),
+        ] {
+            rename_attributes(attributes, prefix, &name, subindex, issues);
+        }
+    }
+
+    if minor_version != 10 {
+        for attribute in object.attributes_mut() {
+            if attribute.data_type == Some(AttributeDataType::HalfFloat2) {
+                attribute.data_type = Some(AttributeDataType::Float2);
+                issues.push(VersionConversionIssue::DataTypeChanged {
+                    mesh_object_name: name.clone(),
+                    mesh_object_subindex: subindex,
+                    attribute_name: attribute.name.clone(),
+                    data_type: AttributeDataType::HalfFloat2,
+                    new_data_type: AttributeDataType::Float2,
+                });
+            }
+        }
+    } else {
+        let limit = u16::MAX as usize;
+        for influence in &mut object.bone_influences {
+            let count = influence.vertex_weights.len();
+            influence
+                .vertex_weights
+                .retain(|w| w.vertex_index as usize <= limit);
+
+            let removed = count - influence.vertex_weights.len();
+            if removed > 0 {
+                issues.push(VersionConversionIssue::SkinWeightsRemoved {
+                    mesh_object_name: name.clone(),
+                    mesh_object_subindex: subindex,
+                    bone_name: influence.bone_name.clone(),
+                    count: removed,
+                    limit,
+                });
+            }
+        }
+    }
+}
+
+// Only two component attributes other than positions are inferred differently between versions.
+// See VectorDataV8::from_vectors and VectorDataV10::from_vectors.
+fn inferred_vector2_data_type(minor_version: u16) -> AttributeDataType {
+    if minor_version == 10 {
+        AttributeDataType::HalfFloat2
+    } else {
+        AttributeDataType::Float2
+    }
+}
+
+fn rename_attributes(
+    attributes: &mut [AttributeData],
+    prefix: &str,
+    mesh_object_name: &str,
+    mesh_object_subindex: u64,
+    issues: &mut Vec<VersionConversionIssue>,
+) {
+    for (i, attribute) in attributes.iter_mut().enumerate() {
+        let new_name = format!("{prefix}{i}");
+        if attribute.name != new_name {
+            issues.push(VersionConversionIssue::AttributeRenamed {
+                mesh_object_name: mesh_object_name.to_string(),
+                mesh_object_subindex,
+                attribute_name: std::mem::replace(&mut attribute.name, new_name.clone()),
+                new_attribute_name: new_name,
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mesh_data::{BoneInfluence, VectorData, VertexWeight};
+    use ssbh_lib::formats::mesh::Mesh;
+
+    fn attribute(name: &str, data_type: Option<AttributeDataType>) -> AttributeData {
+        AttributeData {
+            name: name.into(),
+            data: VectorData::Vector2(vec![[0.0; 2]; 2]),
+            data_type,
+        }
+    }
+
+    fn mesh(minor_version: u16, objects: Vec<MeshObjectData>) -> MeshData {
+        MeshData::new(1, minor_version, objects)
+    }
+
+    fn object() -> MeshObjectData {
+        MeshObjectData {
+            name: "a".into(),
+            subindex: 1,
+            vertex_indices: vec![0, 1, 1],
+            positions: vec![AttributeData::new(
+                "Position0",
+                VectorData::Vector3(vec![[0.0; 3]; 2]),
+            )],
+            binormals: vec![AttributeData::new(
+                "map1",
+                VectorData::Vector3(vec![[0.0; 3]; 2]),
+            )],
+            texture_coordinates: vec![
+                attribute("map1", Some(AttributeDataType::HalfFloat2)),
+                attribute("bake1", None),
+            ],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn convert_1_10_to_1_8() {
+        let (converted, issues) = mesh(10, vec![object()])
+            .convert_version(1, 8, false)
+            .unwrap();
+        assert_eq!((1, 8), (converted.major_version, converted.minor_version));
+
+        let object = &converted.objects[0];
+        assert!(object.binormals.is_empty());
+        assert_eq!("TextureCoordinate0", object.texture_coordinates[0].name);
+        assert_eq!(
+            Some(AttributeDataType::Float2),
+            object.texture_coordinates[0].data_type
+        );
+        assert_eq!("TextureCoordinate1", object.texture_coordinates[1].name);
+
+        assert_eq!(
+            vec![
+                VersionConversionIssue::BinormalRemoved {
+                    mesh_object_name: "a".into(),
+                    mesh_object_subindex: 1,
+                    attribute_name: "map1".into()
+                },
+                VersionConversionIssue::AttributeRenamed {
+                    mesh_object_name: "a".into(),
+                    mesh_object_subindex: 1,
+                    attribute_name: "map1".into(),
+                    new_attribute_name: "TextureCoordinate0".into()
+                },
+                VersionConversionIssue::AttributeRenamed {
+                    mesh_object_name: "a".into(),
+                    mesh_object_subindex: 1,
+                    attribute_name: "bake1".into(),
+                    new_attribute_name: "TextureCoordinate1".into()
+                },
+                VersionConversionIssue::DataTypeChanged {
+                    mesh_object_name: "a".into(),
+                    mesh_object_subindex: 1,
+                    attribute_name: "TextureCoordinate0".into(),
+                    data_type: AttributeDataType::HalfFloat2,
+                    new_data_type: AttributeDataType::Float2
+                },
+                // The inferred data type for version 1.10 is also reported.
+                VersionConversionIssue::DataTypeChanged {
+                    mesh_object_name: "a".into(),
+                    mesh_object_subindex: 1,
+                    attribute_name: "TextureCoordinate1".into(),
+                    data_type: AttributeDataType::HalfFloat2,
+                    new_data_type: AttributeDataType::Float2
+                },
+            ],
+            issues
+        );
+
+        // The converted data can be saved and matches the data read from the file.
+        let mesh = Mesh::try_from(&converted).unwrap();
+        let names: Vec<_> = MeshData::try_from(&mesh).unwrap().objects[0]
+            .texture_coordinates
+            .iter()
+            .map(|a| a.name.clone())
+            .collect();
+        assert_eq!(vec!["TextureCoordinate0", "TextureCoordinate1"], names);
+    }
+
+    #[test]
+    fn convert_1_10_to_1_9() {
+        let (converted, issues) = mesh(10, vec![object()])
+            .convert_version(1, 9, false)
+            .unwrap();
+        assert_eq!(1, converted.objects[0].binormals.len());
+        assert_eq!(
+            vec!["map1", "bake1"],
+            issues
+                .iter()
+                .map(|i| match i {
+                    VersionConversionIssue::DataTypeChanged { attribute_name, .. } =>
+                        attribute_name.as_str(),
+                    _ => panic!("unexpected issue {i:?}"),
+                })
+                .collect::<Vec<_>>()
+        );
+
+        // Saving without converting also falls back to a data type supported by version 1.9.
+        let saved = |data: &MeshData| {
+            MeshData::try_from(&Mesh::try_from(data).unwrap())
+                .unwrap()
+                .objects[0]
+                .texture_coordinates[0]
+                .data_type
+        };
+        assert_eq!(Some(AttributeDataType::Float2), saved(&converted));
+        assert_eq!(
+            Some(AttributeDataType::Float2),
+            saved(&mesh(9, vec![object()]))
+        );
+    }
+
+    #[test]
+    fn convert_1_8_to_1_10_skin_weights() {
+        let mut object = object();
+        object.bone_influences = vec![BoneInfluence {
+            bone_name: "Hip".into(),
+            vertex_weights: vec![
+                VertexWeight {
+                    vertex_index: 1,
+                    vertex_weight: 1.0,
+                },
+                VertexWeight {
+                    vertex_index: u16::MAX as u32 + 1,
+                    vertex_weight: 1.0,
+                },
+            ],
+        }];
+
+        let (converted, issues) = mesh(8, vec![object]).convert_version(1, 10, false).unwrap();
+        assert_eq!(
+            1,
+            converted.objects[0].bone_influences[0].vertex_weights.len()
+        );
+        assert_eq!(
+            vec![VersionConversionIssue::SkinWeightsRemoved {
+                mesh_object_name: "a".into(),
+                mesh_object_subindex: 1,
+                bone_name: "Hip".into(),
+                count: 1,
+                limit: u16::MAX as usize
+            }],
+            issues
+        );
+    }
+
+    #[test]
+    fn convert_1_8_to_1_10_inferred_data_types() {
+        let mut object = object();
+        object.texture_coordinates[0].data_type = None;
+        object.color_sets = vec![attribute("colorSet1", None)];
+
+        let (converted, issues) = mesh(8, vec![object]).convert_version(1, 10, true).unwrap();
+        assert!(issues.is_empty());
+
+        // Preserve the data types that would have been inferred for version 1.8.
+        let object = &converted.objects[0];
+        assert_eq!(
+            Some(AttributeDataType::Float2),
+            object.texture_coordinates[0].data_type
+        );
+        assert_eq!(
+            Some(AttributeDataType::Float2),
+            object.color_sets[0].data_type
+        );
+        assert_eq!(None, object.positions[0].data_type);
+        assert_eq!(None, object.binormals[0].data_type);
+
+        let mesh = Mesh::try_from(&converted).unwrap();
+        assert_eq!(
+            Some(AttributeDataType::Float2),
+            MeshData::try_from(&mesh).unwrap().objects[0].texture_coordinates[0].data_type
+        );
+    }
+
+    #[test]
+    fn convert_strict() {
+        let data = mesh(10, vec![object()]);
+        let result = data.convert_version(1, 8, true);
+        assert!(matches!(
+            result,
+            Err(error::Error::LossyVersionConversion {
+                major_version: 1,
+                minor_version: 8,
+                issues
+            }) if issues.len() == 5
+        ));
+
+        // Converting to the same version doesn't change anything.
+        let (converted, issues) = data.convert_version(1, 10, true).unwrap();
+        assert!(issues.is_empty());
+        assert_eq!(2, converted.objects[0].texture_coordinates.len());
+    }
+
+    #[test]
+    fn convert_unsupported_version() {
+        assert!(matches!(
+            mesh(10, Vec::new()).convert_version(2, 0, false),
+            Err(error::Error::UnsupportedVersion {
+                major_version: 2,
+                minor_version: 0
+            })
+        ));
+    }
+}
@@ -0,0 +1,301 @@
+use std::error::Error;
+
+use ssbh_lib::formats::mesh::Mesh;
+
+use super::{
+    error, get_attributes, read_attribute_data, read_mesh_object, read_rigging_data,
+    read_vertex_indices, Attribute, AttributeData, AttributeUsage, BoneInfluence, MeshObjectData,
+};
+
+macro_rules! match_mesh {
+    ($mesh:expr, $inner:ident => $body:expr) => {
+        match $mesh {
+            Mesh::V8($inner) => $body,
+            Mesh::V9($inner) => $body,
+            Mesh::V10($inner) => $body,
+        }
+    };
+}
+
+/// A view of the objects in a [Mesh] that only decodes buffer data when requested.
+///
+/// Converting to [MeshData](super::MeshData) decodes all the attributes for every object.
+/// Applications that only need some of the objects or attributes
+/// like positions for calculating bounding information can avoid decoding the remaining data.
+/**
+```rust no_run
+# fn main() -> Result<(), Box<dyn std::error::Error>> {
+use ssbh_data::mesh_data::MeshView;
+use ssbh_lib::formats::mesh::Mesh;
+
+let mesh = Mesh::from_file("model.numshb")?;
+let view = MeshView::new(&mesh);
+for object in view.objects() {
+    if let Some(positions) = object.read_attribute("Position0")? {
+        println!("{} {}: {}", object.name, object.subindex, positions.data.len());
+    }
+}
+# Ok(()) }
+```
+ */
+#[derive(Debug, Clone, Copy)]
+pub struct MeshView<'a> {
+    mesh: &'a Mesh,
+}
+
+impl<'a> MeshView<'a> {
+    pub fn new(mesh: &'a Mesh) -> Self {
+        Self { mesh }
+    }
+
+    /// The metadata for each object in the same order as the [Mesh].
+    /// No buffer data is decoded.
+    pub fn objects(&self) -> impl Iterator<Item = MeshObjectView<'a>> + 'a {
+        let view = *self;
+        let count = match_mesh!(self.mesh, mesh => mesh.objects.elements.len());
+        (0..count).map(move |index| view.object_view(index))
+    }
+
+    /// Finds the object with the given `name` and `subindex`.
+    pub fn object(&self, name: &str, subindex: u64) -> Option<MeshObjectView<'a>> {
+        let index = match_mesh!(self.mesh, mesh => mesh
+            .objects
+            .elements
+            .iter()
+            .position(|o| o.subindex == subindex && o.name.to_str() == Some(name)))?;
+        Some(self.object_view(index))
+    }
+
+    fn object_view(&self, index: usize) -> MeshObjectView<'a> {
+        match_mesh!(self.mesh, mesh => {
+            let o = &mesh.objects.elements[index];
+            MeshObjectView {
+                name: o.name.to_string_lossy(),
+                subindex: o.subindex,
+                parent_bone_name: o.parent_bone_name.to_str().unwrap_or("").to_string(),
+                sort_bias: o.sort_bias,
+                disable_depth_write: o.depth_flags.disable_depth_write != 0,
+                disable_depth_test: o.depth_flags.disable_depth_test != 0,
+                vertex_count: o.vertex_count as usize,
+                vertex_index_count: o.vertex_index_count as usize,
+                mesh: self.mesh,
+                index,
+            }
+        })
+    }
+}
+
+/// The metadata for an object in a [MeshView] with methods to decode its data.
+/// The fields match the fields for [MeshObjectData].
+#[derive(Debug, Clone)]
+pub struct MeshObjectView<'a> {
+    pub name: String,
+    pub subindex: u64,
+    pub parent_bone_name: String,
+    pub sort_bias: i32,
+    pub disable_depth_write: bool,
+    pub disable_depth_test: bool,
+    /// The number of vertices stored in the [Mesh].
+    pub vertex_count: usize,
+    /// The number of elements in the vertex indices.
+    pub vertex_index_count: usize,
+    mesh: &'a Mesh,
+    index: usize,
+}
+
+impl MeshObjectView<'_> {
+    /// The names of all the attributes for this object in the order they appear in the [Mesh].
+    pub fn attribute_names(&self) -> Vec<String> {
+        match_mesh!(self.mesh, mesh => mesh.objects.elements[self.index]
+            .attributes
+            .elements
+            .iter()
+            .map(|a| a.to_attribute().name)
+            .collect())
+    }
+
+    /// Decodes the data for the attribute with the given `name`
+    /// or returns [None] if the object has no attribute with that name.
+    pub fn read_attribute(
+        &self,
+        name: &str,
+    ) -> Result<Option<AttributeData>, error::AttributeError> {
+        match_mesh!(self.mesh, mesh => {
+            let mesh_object = &mesh.objects.elements[self.index];
+            mesh_object
+                .attributes
+                .elements
+                .iter()
+                .map(|a| a.to_attribute())
+                .find(|a| a.name == name)
+                .map(|a| {
+                    Ok(AttributeData {
+                        data: read_attribute_data(mesh, mesh_object, &a)?,
+                        name: a.name,
+                        data_type: Some(a.data_type),
+                    })
+                })
+                .transpose()
+        })
+    }
+
+    /// Decodes the data for all the position attributes.
+    /// This is equivalent to [positions](struct.MeshObjectData.html#structfield.positions).
+    pub fn read_positions(&self) -> Result<Vec<AttributeData>, error::AttributeError> {
+        match_mesh!(self.mesh, mesh => {
+            let mesh_object = &mesh.objects.elements[self.index];
+            get_attributes(mesh_object, AttributeUsage::Position)
+                .into_iter()
+                .map(|a| {
+                    Ok(AttributeData {
+                        data: read_attribute_data(mesh, mesh_object, &a)?,
+                        name: a.name,
+                        data_type: Some(a.data_type),
+                    })
+                })
+                .collect()
+        })
+    }
+
+    /// Decodes the vertex indices.
+    /// This is equivalent to [vertex_indices](struct.MeshObjectData.html#structfield.vertex_indices).
+    pub fn read_vertex_indices(&self) -> Result<Vec<u32>, error::AttributeError> {
+        match_mesh!(self.mesh, mesh => read_vertex_indices(
+            &mesh.index_buffer.elements,
+            &mesh.objects.elements[self.index],
+        )
+        .map_err(Into::into))
+    }
+
+    /// Decodes the skin weights.
+    /// This is equivalent to [bone_influences](struct.MeshObjectData.html#structfield.bone_influences).
+    pub fn read_bone_influences(&self) -> Result<Vec<BoneInfluence>, Box<dyn Error>> {
+        match_mesh!(self.mesh, mesh => read_rigging_data(
+            &mesh.rigging_buffers.elements,
+            &self.name,
+            self.subindex,
+        ))
+    }
+
+    /// Decodes all the data for this object.
+    /// This is equivalent to the corresponding object when converting the [Mesh] to [MeshData](super::MeshData).
+    pub fn read(&self) -> Result<MeshObjectData, Box<dyn Error>> {
+        match_mesh!(self.mesh, mesh => read_mesh_object(mesh, &mesh.objects.elements[self.index]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mesh_data::{MeshData, VectorData, VertexWeight};
+
+    fn mesh_data(minor_version: u16) -> MeshData {
+        MeshData::new(
+            1,
+            minor_version,
+            vec![
+                MeshObjectData {
+                    name: "a".into(),
+                    subindex: 0,
+                    vertex_indices: vec![0, 1, 2],
+                    positions: vec![AttributeData::new(
+                        "Position0",
+                        VectorData::Vector3(vec![[0.0; 3], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]]),
+                    )],
+                    texture_coordinates: vec![AttributeData::new(
+                        "map1",
+                        VectorData::Vector2(vec![[0.0, 0.0], [1.0, 0.0], [0.0, 1.0]]),
+                    )],
+                    bone_influences: vec![BoneInfluence {
+                        bone_name: "Hip".into(),
+                        vertex_weights: vec![VertexWeight {
+                            vertex_index: 1,
+                            vertex_weight: 0.5,
+                        }],
+                    }],
+                    ..Default::default()
+                },
+                MeshObjectData {
+                    name: "a".into(),
+                    subindex: 1,
+                    parent_bone_name: "Head".into(),
+                    sort_bias: 3,
+                    vertex_indices: vec![0, 0, 0],
+                    positions: vec![AttributeData::new(
+                        "Position0",
+                        VectorData::Vector3(vec![[2.0; 3]]),
+                    )],
+                    ..Default::default()
+                },
+            ],
+        )
+    }
+
+    #[test]
+    fn view_metadata() {
+        let mesh = Mesh::try_from(&mesh_data(10)).unwrap();
+        let view = MeshView::new(&mesh);
+        let objects: Vec<_> = view.objects().collect();
+
+        assert_eq!(2, objects.len());
+        assert_eq!(
+            ("a", 0, 3, 3),
+            (
+                objects[0].name.as_str(),
+                objects[0].subindex,
+                objects[0].vertex_count,
+                objects[0].vertex_index_count
+            )
+        );
+        assert_eq!(vec!["Position0", "map1"], objects[0].attribute_names());
+
+        let object = view.object("a", 1).unwrap();
+        assert_eq!("Head", object.parent_bone_name);
+        assert_eq!(3, object.sort_bias);
+        assert_eq!(1, object.vertex_count);
+        assert!(view.object("a", 2).is_none());
+    }
+
+    #[test]
+    fn view_read_attributes() {
+        for minor_version in [8, 9, 10] {
+            let data = mesh_data(minor_version);
+            let mesh = Mesh::try_from(&data).unwrap();
+            let object = MeshView::new(&mesh).object("a", 0).unwrap();
+
+            let positions = object.read_positions().unwrap();
+            assert_eq!(1, positions.len());
+            assert_eq!(data.objects[0].positions[0].data, positions[0].data);
+
+            let name = if minor_version == 8 {
+                "TextureCoordinate0"
+            } else {
+                "map1"
+            };
+            assert_eq!(
+                data.objects[0].texture_coordinates[0].data,
+                object.read_attribute(name).unwrap().unwrap().data
+            );
+            assert!(object.read_attribute("colorSet1").unwrap().is_none());
+            assert_eq!(vec![0, 1, 2], object.read_vertex_indices().unwrap());
+        }
+    }
+
+    #[test]
+    fn view_read_matches_mesh_data() {
+        let mesh = Mesh::try_from(&mesh_data(10)).unwrap();
+        let expected = MeshData::try_from(&mesh).unwrap();
+
+        for (object, expected) in MeshView::new(&mesh).objects().zip(&expected.objects) {
+            let actual = object.read().unwrap();
+            assert_eq!(expected.name, actual.name);
+            assert_eq!(expected.subindex, actual.subindex);
+            assert_eq!(expected.vertex_indices, actual.vertex_indices);
+            assert_eq!(expected.positions[0].data, actual.positions[0].data);
+            assert_eq!(
+                expected.bone_influences.len(),
+                object.read_bone_influences().unwrap().len()
+            );
+        }
+    }
+}
@@ -0,0 +1,209 @@
+use itertools::Itertools;
+
+use super::bvh::{closest_point_triangle, triangle_bounds, Bvh};
+use super::{error, MeshObjectData, SkinWeights};
+
+/// The method for finding the source weights for each target vertex
+/// in [MeshObjectData::transfer_skin_weights].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WeightTransferMethod {
+    /// Copy the weights from the closest source vertex.
+    NearestVertex,
+    /// Interpolate the weights at the closest point on the source triangles
+    /// using barycentric coordinates.
+    ClosestPoint,
+}
+
+impl MeshObjectData {
+    /// Replaces [bone_influences](#structfield.bone_influences) with the weights from `source`
+    /// based on the first position attribute of each object.
+    ///
+    /// This is useful for replacing a mesh with an edited version that has no skin weights.
+    /// The objects should be in the same space and have roughly the same shape.
+    /**
+    ```rust no_run
+    # use ssbh_data::mesh_data::{MeshObjectData, WeightTransferMethod};
+    # let original = MeshObjectData::default();
+    # let mut sculpted = MeshObjectData::default();
+    sculpted.transfer_skin_weights(&original, WeightTransferMethod::ClosestPoint)?;
+    # Ok::<(), Box<dyn std::error::Error>>(())
+    ```
+     */
+    pub fn transfer_skin_weights(
+        &mut self,
+        source: &MeshObjectData,
+        method: WeightTransferMethod,
+    ) -> Result<(), error::Error> {
+        let positions = self.required_positions()?;
+        let source_positions = source.required_positions()?;
+
+        let bone_names: Vec<_> = source
+            .bone_influences
+            .iter()
+            .map(|i| i.bone_name.as_str())
+            .unique()
+            .collect();
+        let source_weights = SkinWeights::from_bone_influences(
+            &source.bone_influences,
+            &bone_names,
+            source.vertex_count()?,
+        )?;
+
+        let vertex_weights = match method {
+            WeightTransferMethod::NearestVertex => {
+                let bounds: Vec<_> = source_positions.iter().map(|p| (*p, *p)).collect();
+                let bvh = Bvh::new(&bounds);
+                positions
+                    .iter()
+                    .map(|p| {
+                        bvh.closest(*p, |i| Some((source_positions[i].distance_squared(*p), ())))
+                            .map(|(i, _, _)| source_weights.vertex_weights[i].clone())
+                            .unwrap_or_default()
+                    })
+                    .collect()
+            }
+            WeightTransferMethod::ClosestPoint => {
+                let triangles = source.triangles()?;
+                let triangle_positions = |t: &[usize; 3]| t.map(|v| source_positions[v]);
+                let bounds: Vec<_> = triangles
+                    .iter()
+                    .map(|t| triangle_bounds(triangle_positions(t)))
+                    .collect();
+                let bvh = Bvh::new(&bounds);
+                positions
+                    .iter()
+                    .map(|p| {
+                        bvh.closest(*p, |t| {
+                            let (point, coords) =
+                                closest_point_triangle(*p, triangle_positions(&triangles[t]));
+                            Some((point.distance_squared(*p), coords))
+                        })
+                        .map(|(t, _, coords)| {
+                            interpolate_weights(&source_weights, &triangles[t], coords)
+                        })
+                        .unwrap_or_default()
+                    })
+                    .collect()
+            }
+        };
+
+        self.bone_influences = SkinWeights {
+            bone_names: bone_names.iter().map(|n| n.to_string()).collect(),
+            vertex_weights,
+        }
+        .to_bone_influences();
+        Ok(())
+    }
+}
+
+fn interpolate_weights(
+    skin_weights: &SkinWeights,
+    triangle: &[usize; 3],
+    coords: [f32; 3],
+) -> Vec<(usize, f32)> {
+    let mut weights: Vec<(usize, f32)> = Vec::new();
+    for (v, coord) in triangle.iter().zip(coords) {
+        for (bone_index, weight) in &skin_weights.vertex_weights[*v] {
+            match weights.iter_mut().find(|(i, _)| i == bone_index) {
+                Some((_, total)) => *total += weight * coord,
+                None => weights.push((*bone_index, weight * coord)),
+            }
+        }
+    }
+
+    // Barycentric coordinates of 0.0 shouldn't add influences.
+    weights.retain(|(_, w)| *w > 0.0);
+    weights
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mesh_data::{AttributeData, BoneInfluence, VectorData, VertexWeight};
+
+    fn object(positions: Vec<[f32; 3]>, vertex_indices: Vec<u32>) -> MeshObjectData {
+        MeshObjectData {
+            vertex_indices,
+            positions: vec![AttributeData::new(
+                "Position0",
+                VectorData::Vector3(positions),
+            )],
+            ..Default::default()
+        }
+    }
+
+    fn source() -> MeshObjectData {
+        let mut source = object(
+            vec![[0.0, 0.0, 0.0], [2.0, 0.0, 0.0], [0.0, 2.0, 0.0]],
+            vec![0, 1, 2],
+        );
+        source.bone_influences = ["A", "B", "C"]
+            .iter()
+            .enumerate()
+            .map(|(i, name)| BoneInfluence {
+                bone_name: name.to_string(),
+                vertex_weights: vec![VertexWeight {
+                    vertex_index: i as u32,
+                    vertex_weight: 1.0,
+                }],
+            })
+            .collect();
+        source
+    }
+
+    fn weights(object: &MeshObjectData) -> Vec<(&str, Vec<(u32, f32)>)> {
+        object
+            .bone_influences
+            .iter()
+            .map(|i| {
+                (
+                    i.bone_name.as_str(),
+                    i.vertex_weights
+                        .iter()
+                        .map(|w| (w.vertex_index, w.vertex_weight))
+                        .collect(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn transfer_skin_weights_nearest_vertex() {
+        let mut target = object(vec![[0.1, 0.1, 0.0], [1.9, 0.5, 0.0]], Vec::new());
+        target
+            .transfer_skin_weights(&source(), WeightTransferMethod::NearestVertex)
+            .unwrap();
+
+        assert_eq!(
+            vec![("A", vec![(0, 1.0)]), ("B", vec![(1, 1.0)])],
+            weights(&target)
+        );
+    }
+
+    #[test]
+    fn transfer_skin_weights_closest_point() {
+        let mut target = object(vec![[1.0, 0.0, 1.0], [0.0, 2.0, -1.0]], Vec::new());
+        target
+            .transfer_skin_weights(&source(), WeightTransferMethod::ClosestPoint)
+            .unwrap();
+
+        assert_eq!(
+            vec![
+                ("A", vec![(0, 0.5)]),
+                ("B", vec![(0, 0.5)]),
+                ("C", vec![(1, 1.0)])
+            ],
+            weights(&target)
+        );
+    }
+
+    #[test]
+    fn transfer_skin_weights_missing_positions() {
+        let mut target = MeshObjectData::default();
+        let result = target.transfer_skin_weights(&source(), WeightTransferMethod::NearestVertex);
+        assert!(matches!(
+            result,
+            Err(error::Error::MissingAttribute { attribute_name }) if attribute_name == "Position0"
+        ));
+    }
+}
@@ -4,10 +4,8 @@
 //! Unmodified files are not guaranteed to be binary identical after saving.
 //! Some bounding information is recalculated on export and is unlikely to match the original file
 //! due to algorithmic differences and floating point errors.
-use crate::mesh_data::MeshObjectData;
-use geometry_tools::bounding::{
-    calculate_bounding_sphere_from_points, calculate_bounding_sphere_from_spheres,
-};
+use crate::mesh_data::{calculate_bounding_sphere, BoundingMethod, MeshObjectData};
+use geometry_tools::bounding::calculate_bounding_sphere_from_spheres;
 use itertools::Itertools;
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
@@ -51,7 +49,19 @@ pub struct EntryFlags {
 impl MeshExData {
     /// Groups `objects` by name and calculates bounding information.
     /// Flags are set to a default value.
+    ///
+    /// Bounding spheres use the default [BoundingMethod].
+    /// See [from_mesh_objects_with_method](#method.from_mesh_objects_with_method).
     pub fn from_mesh_objects(objects: &[MeshObjectData]) -> Self {
+        Self::from_mesh_objects_with_method(objects, BoundingMethod::default())
+    }
+
+    /// Groups `objects` by name and calculates bounding information using `bounding_method`.
+    /// Flags are set to a default value.
+    pub fn from_mesh_objects_with_method(
+        objects: &[MeshObjectData],
+        bounding_method: BoundingMethod,
+    ) -> Self {
         // TODO: Should flags always default to true?
         Self {
             mesh_object_groups: objects
@@ -59,22 +69,9 @@ impl MeshExData {
                 .group_by(|o| &o.name)
                 .into_iter()
                 .map(|(name, group)| {
-                    // TODO: Find a cleaner way to collect points.
-                    // Make geometry_tools accept more types?
                     let group: Vec<_> = group.into_iter().collect();
-                    let points: Vec<_> = group
-                        .iter()
-                        .filter_map(|m| m.positions.first().map(|p| p.data.to_vec4_with_w(1.0)))
-                        .flatten()
-                        .map(|v| geometry_tools::glam::Vec3A::from_slice(&v))
-                        .collect_vec();
-                    let sphere = calculate_bounding_sphere_from_points(&points);
-
                     MeshObjectGroupData {
-                        bounding_sphere: BoundingSphere {
-                            center: Vector3::new(sphere.x, sphere.y, sphere.z),
-                            radius: sphere.w,
-                        },
+                        bounding_sphere: calculate_group_bounding_sphere(&group, bounding_method),
                         mesh_object_full_name: name.clone(),
                         mesh_object_name: strip_mesh_name_tags(name),
                         entry_flags: group
@@ -91,11 +88,31 @@ impl MeshExData {
     }
 }
 
+pub(crate) fn calculate_group_bounding_sphere(
+    group: &[&MeshObjectData],
+    bounding_method: BoundingMethod,
+) -> BoundingSphere {
+    // TODO: Find a cleaner way to collect points.
+    // Make geometry_tools accept more types?
+    let points: Vec<_> = group
+        .iter()
+        .filter_map(|m| m.positions.first().map(|p| p.data.to_vec4_with_w(1.0)))
+        .flatten()
+        .map(|v| geometry_tools::glam::Vec3A::from_slice(&v))
+        .collect_vec();
+    let sphere = calculate_bounding_sphere(&points, bounding_method);
+
+    BoundingSphere {
+        center: Vector3::new(sphere.x, sphere.y, sphere.z),
+        radius: sphere.w,
+    }
+}
+
 // TODO: Add methods to create MeshExData from points.
 
 // TODO: Is there a cleaner way of writing this?
 // TODO: Should this be public?
-fn strip_mesh_name_tags(full_name: &str) -> String {
+pub(crate) fn strip_mesh_name_tags(full_name: &str) -> String {
     // Strip portions of a mesh object's name that aren't necessary for identification.
     // This includes Autodesk Maya's convention of appending "Shape".
     // Names can contain multiple tags like "face_default_O_V_VISShape" -> "face_default".
@@ -458,28 +475,28 @@ mod tests {
             MeshObjectData {
                 name: "a_VIS".to_string(),
                 subindex: 0,
-                positions: vec![AttributeData {
-                    name: String::new(),
-                    data: VectorData::Vector3(vec![[-1.0, -1.0, -1.0]; 3]),
-                }],
+                positions: vec![AttributeData::new(
+                    String::new(),
+                    VectorData::Vector3(vec![[-1.0, -1.0, -1.0]; 3]),
+                )],
                 ..Default::default()
             },
             MeshObjectData {
                 name: "a_VIS".to_string(),
                 subindex: 1,
-                positions: vec![AttributeData {
-                    name: String::new(),
-                    data: VectorData::Vector3(vec![[1.0, 1.0, 1.0]; 3]),
-                }],
+                positions: vec![AttributeData::new(
+                    String::new(),
+                    VectorData::Vector3(vec![[1.0, 1.0, 1.0]; 3]),
+                )],
                 ..Default::default()
             },
             MeshObjectData {
                 name: "b_VIS".to_string(),
                 subindex: 0,
-                positions: vec![AttributeData {
-                    name: String::new(),
-                    data: VectorData::Vector3(vec![[0.0, 0.0, 0.0]; 3]),
-                }],
+                positions: vec![AttributeData::new(
+                    String::new(),
+                    VectorData::Vector3(vec![[0.0, 0.0, 0.0]; 3]),
+                )],
                 ..Default::default()
             },
         ]);
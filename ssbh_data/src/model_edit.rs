@@ -0,0 +1,764 @@
+//! Editing operations for mesh objects that update the files that depend on them.
+//!
+//! Mesh objects are referenced from other files in several different ways.
+//! [ModlData] entries use the name and subindex, [MeshExData] groups consecutive objects with the same name,
+//! [AdjData] entries use the index in [objects](crate::mesh_data::MeshData::objects),
+//! and visibility [AnimData] nodes apply to all objects with names starting with the node name.
+//! A [ModelEditor] applies each operation to the [MeshData] and all of the provided dependent files.
+//!
+//! # Examples
+/*!
+```rust no_run
+# fn main() -> Result<(), Box<dyn std::error::Error>> {
+use ssbh_data::prelude::*;
+
+let mut model = ModelFolder::from_folder("mario/model/body/c00")?;
+let mut visibility = AnimData::from_file("mario/motion/body/c00/a00wait1.nuanmb")?;
+
+if let Some(mut editor) = model.editor() {
+    editor.anims.push(&mut visibility);
+
+    let index = editor.duplicate_object(0)?;
+    editor.rename_object(index, "hat_VIS_O_OBJShape")?;
+    editor.remove_object(1)?;
+}
+
+model.write_to_folder("mario/model/body/c00")?;
+visibility.write_to_file("mario/motion/body/c00/a00wait1.nuanmb")?;
+# Ok(()) }
+```
+ */
+use std::collections::HashMap;
+
+use itertools::Itertools;
+
+use crate::anim_data::{GroupType, NodeData};
+use crate::mesh_data::{BoundingMethod, MeshObjectData};
+use crate::meshex_data::{
+    calculate_group_bounding_sphere, strip_mesh_name_tags, EntryFlags, MeshObjectGroupData,
+};
+use crate::model_folder::ModelFolder;
+use crate::modl_data::ModlEntryData;
+use crate::prelude::*;
+
+pub mod error {
+    use thiserror::Error;
+
+    /// Errors while editing mesh objects with a [ModelEditor](super::ModelEditor).
+    #[derive(Debug, Error)]
+    pub enum Error {
+        /// The index does not refer to a mesh object.
+        #[error(
+            "mesh object index {} is out of range for a mesh object collection of size {}",
+            index,
+            mesh_object_count
+        )]
+        MeshObjectIndexOutOfRange {
+            index: usize,
+            mesh_object_count: usize,
+        },
+
+        /// The new order does not contain each mesh object index exactly once.
+        #[error(
+            "the new order must contain each index less than {} exactly once",
+            mesh_object_count
+        )]
+        InvalidOrder { mesh_object_count: usize },
+    }
+}
+
+/// Mutable references to a [MeshData] and the files that reference its objects.
+/// Each operation updates all of the dependent files that are present.
+///
+/// Changed [MeshExData] groups have their bounding spheres recalculated.
+/// Unchanged groups preserve their original values.
+#[derive(Debug)]
+pub struct ModelEditor<'a> {
+    pub mesh: &'a mut MeshData,
+    pub modl: Option<&'a mut ModlData>,
+    pub meshex: Option<&'a mut MeshExData>,
+    pub adj: Option<&'a mut AdjData>,
+    /// Animations with [GroupType::Visibility] nodes for the mesh objects.
+    /// Other group types are not modified.
+    ///
+    /// A node applies to every object with a name starting with the node name like `"Hat_VIS"` for `"Hat_VIS_O_OBJShape"`.
+    /// Objects that are no longer matched by any node get a new node with the full object name
+    /// and the values of the node for the original object.
+    /// Nodes that no longer match any objects are removed.
+    pub anims: Vec<&'a mut AnimData>,
+}
+
+impl ModelFolder {
+    /// Creates a [ModelEditor] for the successfully loaded files
+    /// or [None] if the [MeshData] could not be loaded.
+    pub fn editor(&mut self) -> Option<ModelEditor<'_>> {
+        Some(ModelEditor {
+            mesh: self.mesh.data.as_mut().ok()?,
+            modl: Some(&mut self.modl),
+            meshex: self.meshex.as_mut().and_then(|m| m.data.as_mut().ok()),
+            adj: self.adj.as_mut().and_then(|a| a.data.as_mut().ok()),
+            anims: self
+                .anim
+                .as_mut()
+                .and_then(|a| a.data.as_mut().ok())
+                .into_iter()
+                .collect(),
+        })
+    }
+}
+
+impl<'a> ModelEditor<'a> {
+    /// Creates a new [ModelEditor] with no dependent files.
+    pub fn new(mesh: &'a mut MeshData) -> Self {
+        Self {
+            mesh,
+            modl: None,
+            meshex: None,
+            adj: None,
+            anims: Vec::new(),
+        }
+    }
+
+    /// Renames the object at `index` to `name`.
+    ///
+    /// The subindex is preserved unless another object already uses it with the new name,
+    /// in which case the next available subindex is used.
+    pub fn rename_object(&mut self, index: usize, name: &str) -> Result<(), error::Error> {
+        self.check_index(index)?;
+
+        let mut objects = self.mesh.objects.clone();
+        let subindex = objects[index].subindex;
+        objects[index].name = name.to_string();
+        if objects
+            .iter()
+            .enumerate()
+            .any(|(i, o)| i != index && o.name == name && o.subindex == subindex)
+        {
+            objects[index].subindex = next_subindex(&objects, name);
+        }
+
+        let new_to_old = (0..objects.len()).map(Some).collect();
+        self.apply(objects, new_to_old);
+        Ok(())
+    }
+
+    /// Removes the object at `index` and all references to it.
+    pub fn remove_object(&mut self, index: usize) -> Result<(), error::Error> {
+        self.check_index(index)?;
+
+        let mut objects = self.mesh.objects.clone();
+        objects.remove(index);
+
+        let new_to_old = (0..self.mesh.objects.len())
+            .filter(|i| *i != index)
+            .map(Some)
+            .collect();
+        self.apply(objects, new_to_old);
+        Ok(())
+    }
+
+    /// Reorders the objects so the object at `order[i]` is moved to index `i`.
+    pub fn reorder_objects(&mut self, order: &[usize]) -> Result<(), error::Error> {
+        let count = self.mesh.objects.len();
+        if order.len() != count || !order.iter().all(|i| *i < count) || !order.iter().all_unique() {
+            return Err(error::Error::InvalidOrder {
+                mesh_object_count: count,
+            });
+        }
+
+        let objects = order
+            .iter()
+            .map(|i| self.mesh.objects[*i].clone())
+            .collect();
+        self.apply(objects, order.iter().copied().map(Some).collect());
+        Ok(())
+    }
+
+    /// Inserts a copy of the object at `index` immediately after the original object
+    /// using the same name and the next available subindex.
+    /// All references to the original object are duplicated for the copy.
+    /// Returns the index of the new object.
+    pub fn duplicate_object(&mut self, index: usize) -> Result<usize, error::Error> {
+        self.check_index(index)?;
+
+        let mut objects = self.mesh.objects.clone();
+        let mut copy = objects[index].clone();
+        copy.subindex = next_subindex(&objects, &copy.name);
+        objects.insert(index + 1, copy);
+
+        let new_to_old = (0..=index)
+            .chain(index..self.mesh.objects.len())
+            .map(Some)
+            .collect();
+        self.apply(objects, new_to_old);
+        Ok(index + 1)
+    }
+
+    fn check_index(&self, index: usize) -> Result<(), error::Error> {
+        if index < self.mesh.objects.len() {
+            Ok(())
+        } else {
+            Err(error::Error::MeshObjectIndexOutOfRange {
+                index,
+                mesh_object_count: self.mesh.objects.len(),
+            })
+        }
+    }
+
+    // new_to_old[i] is the index in the current objects for the new object at index i.
+    fn apply(&mut self, objects: Vec<MeshObjectData>, new_to_old: Vec<Option<usize>>) {
+        let old_to_new = old_to_new_indices(&new_to_old, self.mesh.objects.len());
+
+        if let Some(modl) = self.modl.as_deref_mut() {
+            update_modl(modl, &self.mesh.objects, &objects, &old_to_new);
+        }
+        if let Some(meshex) = self.meshex.as_deref_mut() {
+            update_meshex(
+                meshex,
+                &self.mesh.objects,
+                &objects,
+                &new_to_old,
+                self.mesh.bounding_method,
+            );
+        }
+        if let Some(adj) = self.adj.as_deref_mut() {
+            update_adj(adj, &old_to_new);
+        }
+        for anim in self.anims.iter_mut() {
+            update_visibility(anim, &self.mesh.objects, &objects, &new_to_old);
+        }
+
+        self.mesh.objects = objects;
+    }
+}
+
+fn next_subindex(objects: &[MeshObjectData], name: &str) -> u64 {
+    objects
+        .iter()
+        .filter(|o| o.name == name)
+        .map(|o| o.subindex + 1)
+        .max()
+        .unwrap_or(0)
+}
+
+fn old_to_new_indices(new_to_old: &[Option<usize>], old_count: usize) -> Vec<Vec<usize>> {
+    let mut old_to_new = vec![Vec::new(); old_count];
+    for (new, old) in new_to_old.iter().enumerate() {
+        if let Some(old) = old {
+            old_to_new[*old].push(new);
+        }
+    }
+    old_to_new
+}
+
+fn update_modl(
+    modl: &mut ModlData,
+    old_objects: &[MeshObjectData],
+    new_objects: &[MeshObjectData],
+    old_to_new: &[Vec<usize>],
+) {
+    let old_indices: HashMap<_, _> = old_objects
+        .iter()
+        .enumerate()
+        .map(|(i, o)| ((o.name.as_str(), o.subindex), i))
+        .collect();
+
+    // Preserve the entry order and any entries that don't match an object.
+    modl.entries = modl
+        .entries
+        .iter()
+        .flat_map(|e| {
+            match old_indices.get(&(e.mesh_object_name.as_str(), e.mesh_object_subindex)) {
+                Some(old) => old_to_new[*old]
+                    .iter()
+                    .map(|new| ModlEntryData {
+                        mesh_object_name: new_objects[*new].name.clone(),
+                        mesh_object_subindex: new_objects[*new].subindex,
+                        material_label: e.material_label.clone(),
+                    })
+                    .collect(),
+                None => vec![e.clone()],
+            }
+        })
+        .collect();
+}
+
+fn update_meshex(
+    meshex: &mut MeshExData,
+    old_objects: &[MeshObjectData],
+    new_objects: &[MeshObjectData],
+    new_to_old: &[Option<usize>],
+    bounding_method: BoundingMethod,
+) {
+    // Find the group and flags for each of the current objects.
+    let old_groups = group_indices(old_objects);
+    let mut old_group_index = vec![None; old_objects.len()];
+    let mut old_flags = vec![None; old_objects.len()];
+    for (group_index, members) in old_groups.iter().enumerate() {
+        for (i, old) in members.iter().enumerate() {
+            old_group_index[*old] = Some(group_index);
+            old_flags[*old] = meshex
+                .mesh_object_groups
+                .get(group_index)
+                .and_then(|g| g.entry_flags.get(i).copied());
+        }
+    }
+
+    meshex.mesh_object_groups = group_indices(new_objects)
+        .into_iter()
+        .map(|members| {
+            let old_members = members.iter().map(|i| new_to_old[*i]).collect_vec();
+            let name = &new_objects[members[0]].name;
+
+            // Preserve the data for groups that have not changed.
+            let unchanged_group = old_members[0]
+                .and_then(|old| old_group_index[old])
+                .filter(|g| {
+                    old_groups[*g].iter().map(|i| Some(*i)).collect_vec() == old_members
+                        && old_objects[old_groups[*g][0]].name == *name
+                })
+                .and_then(|g| meshex.mesh_object_groups.get(g));
+
+            match unchanged_group {
+                Some(group) => group.clone(),
+                None => MeshObjectGroupData {
+                    bounding_sphere: calculate_group_bounding_sphere(
+                        &members.iter().map(|i| &new_objects[*i]).collect_vec(),
+                        bounding_method,
+                    ),
+                    mesh_object_full_name: name.clone(),
+                    mesh_object_name: strip_mesh_name_tags(name),
+                    entry_flags: old_members
+                        .iter()
+                        .map(|old| {
+                            old.and_then(|old| old_flags[old]).unwrap_or(EntryFlags {
+                                draw_model: true,
+                                cast_shadow: true,
+                            })
+                        })
+                        .collect(),
+                },
+            }
+        })
+        .collect();
+}
+
+// Group consecutive objects with the same name to match MeshExData::from_mesh_objects.
+fn group_indices(objects: &[MeshObjectData]) -> Vec<Vec<usize>> {
+    objects
+        .iter()
+        .enumerate()
+        .group_by(|(_, o)| &o.name)
+        .into_iter()
+        .map(|(_, group)| group.map(|(i, _)| i).collect())
+        .collect()
+}
+
+fn update_adj(adj: &mut AdjData, old_to_new: &[Vec<usize>]) {
+    adj.entries = adj
+        .entries
+        .iter()
+        .flat_map(|e| match old_to_new.get(e.mesh_object_index) {
+            Some(new_indices) => new_indices
+                .iter()
+                .map(|new| {
+                    let mut entry = e.clone();
+                    entry.mesh_object_index = *new;
+                    entry
+                })
+                .collect(),
+            None => vec![e.clone()],
+        })
+        .collect();
+
+    // Entries are stored in the same order as the mesh objects.
+    adj.entries.sort_by_key(|e| e.mesh_object_index);
+}
+
+fn update_visibility(
+    anim: &mut AnimData,
+    old_objects: &[MeshObjectData],
+    new_objects: &[MeshObjectData],
+    new_to_old: &[Option<usize>],
+) {
+    // Nodes apply to any objects with a name starting with the node name.
+    let matches = |node: &NodeData, name: &str| name.starts_with(node.name.as_str());
+
+    for group in anim
+        .groups
+        .iter_mut()
+        .filter(|g| g.group_type == GroupType::Visibility)
+    {
+        // Remove nodes that no longer match any objects.
+        let is_kept = group
+            .nodes
+            .iter()
+            .map(|n| {
+                !old_objects.iter().any(|o| matches(n, &o.name))
+                    || new_objects.iter().any(|o| matches(n, &o.name))
+            })
+            .collect_vec();
+
+        // Objects that are no longer matched use a copy of the node for the original object.
+        let mut added_names: Vec<Vec<&str>> = vec![Vec::new(); group.nodes.len()];
+        for (new, old) in new_to_old.iter().enumerate() {
+            let name = new_objects[new].name.as_str();
+            let is_matched = group
+                .nodes
+                .iter()
+                .zip(&is_kept)
+                .any(|(n, kept)| *kept && matches(n, name))
+                || added_names.iter().flatten().any(|n| name.starts_with(n));
+            if is_matched {
+                continue;
+            }
+
+            let old_node = old.and_then(|old| {
+                group
+                    .nodes
+                    .iter()
+                    .position(|n| matches(n, &old_objects[old].name))
+            });
+            if let Some(node_index) = old_node {
+                added_names[node_index].push(name);
+            }
+        }
+
+        group.nodes = group
+            .nodes
+            .iter()
+            .zip(is_kept)
+            .zip(added_names)
+            .flat_map(|((node, kept), names)| {
+                kept.then(|| node.clone())
+                    .into_iter()
+                    .chain(names.into_iter().map(|name| NodeData {
+                        name: name.to_string(),
+                        ..node.clone()
+                    }))
+            })
+            .collect();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        adj_data::AdjEntryData,
+        anim_data::{GroupData, TrackData, TrackValues, TransformFlags},
+        meshex_data::BoundingSphere,
+    };
+    use ssbh_lib::Vector3;
+
+    fn objects(names: &[(&str, u64)]) -> Vec<MeshObjectData> {
+        names
+            .iter()
+            .map(|(name, subindex)| MeshObjectData {
+                name: name.to_string(),
+                subindex: *subindex,
+                ..Default::default()
+            })
+            .collect()
+    }
+
+    fn mesh(names: &[(&str, u64)]) -> MeshData {
+        MeshData::new(1, 10, objects(names))
+    }
+
+    fn modl(entries: &[(&str, u64, &str)]) -> ModlData {
+        ModlData {
+            major_version: 1,
+            minor_version: 7,
+            model_name: String::new(),
+            skeleton_file_name: String::new(),
+            material_file_names: Vec::new(),
+            animation_file_name: None,
+            mesh_file_name: String::new(),
+            entries: entries
+                .iter()
+                .map(|(name, subindex, label)| ModlEntryData {
+                    mesh_object_name: name.to_string(),
+                    mesh_object_subindex: *subindex,
+                    material_label: label.to_string(),
+                })
+                .collect(),
+        }
+    }
+
+    fn adj(indices: &[usize]) -> AdjData {
+        AdjData {
+            entries: indices
+                .iter()
+                .map(|i| AdjEntryData {
+                    mesh_object_index: *i,
+                    vertex_adjacency: vec![*i as i16],
+                })
+                .collect(),
+        }
+    }
+
+    fn visibility(names: &[&str]) -> AnimData {
+        AnimData {
+            major_version: 2,
+            minor_version: 0,
+            final_frame_index: 0.0,
+            groups: vec![GroupData {
+                group_type: GroupType::Visibility,
+                nodes: names
+                    .iter()
+                    .map(|name| NodeData {
+                        name: name.to_string(),
+                        tracks: vec![TrackData {
+                            name: "Visibility".to_string(),
+                            compensate_scale: false,
+                            transform_flags: TransformFlags::default(),
+                            values: TrackValues::Boolean(vec![true]),
+                        }],
+                    })
+                    .collect(),
+            }],
+        }
+    }
+
+    fn names(mesh: &MeshData) -> Vec<(&str, u64)> {
+        mesh.objects
+            .iter()
+            .map(|o| (o.name.as_str(), o.subindex))
+            .collect()
+    }
+
+    fn node_names(anim: &AnimData) -> Vec<&str> {
+        anim.groups[0]
+            .nodes
+            .iter()
+            .map(|n| n.name.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn rename_object_all_files() {
+        let mut mesh = mesh(&[("a", 0), ("a", 1), ("b", 0)]);
+        let mut modl = modl(&[("a", 0, "m0"), ("a", 1, "m1"), ("b", 0, "m2")]);
+        let mut meshex = MeshExData::from_mesh_objects(&mesh.objects);
+        meshex.mesh_object_groups[1].entry_flags[0].cast_shadow = false;
+        let mut anim = visibility(&["a", "b"]);
+
+        let mut editor = ModelEditor {
+            modl: Some(&mut modl),
+            meshex: Some(&mut meshex),
+            anims: vec![&mut anim],
+            ..ModelEditor::new(&mut mesh)
+        };
+        editor.rename_object(2, "a").unwrap();
+
+        // The subindex is already used by another object.
+        assert_eq!(vec![("a", 0), ("a", 1), ("a", 2)], names(&mesh));
+        assert_eq!(
+            modl.entries[2],
+            ModlEntryData {
+                mesh_object_name: "a".to_string(),
+                mesh_object_subindex: 2,
+                material_label: "m2".to_string()
+            }
+        );
+        assert_eq!(1, meshex.mesh_object_groups.len());
+        assert_eq!(
+            vec![true, true, false],
+            meshex.mesh_object_groups[0]
+                .entry_flags
+                .iter()
+                .map(|f| f.cast_shadow)
+                .collect_vec()
+        );
+        // The existing node for "a" should take priority.
+        assert_eq!(vec!["a"], node_names(&anim));
+    }
+
+    #[test]
+    fn rename_object_shared_name_visibility() {
+        let mut mesh = mesh(&[("a", 0), ("a", 1)]);
+        let mut anim = visibility(&["a"]);
+
+        let mut editor = ModelEditor::new(&mut mesh);
+        editor.anims.push(&mut anim);
+        editor.rename_object(1, "c").unwrap();
+
+        assert_eq!(vec![("a", 0), ("c", 1)], names(&mesh));
+        assert_eq!(vec!["a", "c"], node_names(&anim));
+    }
+
+    #[test]
+    fn edit_objects_prefix_visibility() {
+        let mut mesh = mesh(&[
+            ("Hat_VIS_O_OBJShape", 0),
+            ("Hat_VIS_O_OBJShape", 1),
+            ("Body_O_OBJShape", 0),
+        ]);
+        let mut anim = visibility(&["Hat_VIS", "Eye_VIS"]);
+        anim.groups[0].nodes[0].tracks[0].values = TrackValues::Boolean(vec![false]);
+
+        let mut editor = ModelEditor::new(&mut mesh);
+        editor.anims.push(&mut anim);
+
+        // The duplicate is still matched by the existing node.
+        editor.duplicate_object(0).unwrap();
+        assert_eq!(vec!["Hat_VIS", "Eye_VIS"], node_names(editor.anims[0]));
+
+        editor.rename_object(0, "Cap_VIS_O_OBJShape").unwrap();
+        assert_eq!(
+            vec!["Hat_VIS", "Cap_VIS_O_OBJShape", "Eye_VIS"],
+            node_names(editor.anims[0])
+        );
+        assert_eq!(
+            TrackValues::Boolean(vec![false]),
+            editor.anims[0].groups[0].nodes[1].tracks[0].values
+        );
+
+        // Nodes that only matched removed objects are removed.
+        editor.remove_object(1).unwrap();
+        editor.remove_object(1).unwrap();
+        assert_eq!(
+            vec![("Cap_VIS_O_OBJShape", 0), ("Body_O_OBJShape", 0)],
+            names(&mesh)
+        );
+        assert_eq!(vec!["Cap_VIS_O_OBJShape", "Eye_VIS"], node_names(&anim));
+    }
+
+    #[test]
+    fn remove_object_all_files() {
+        let mut mesh = mesh(&[("a", 0), ("b", 0), ("c", 0)]);
+        let mut modl = modl(&[("a", 0, "m0"), ("b", 0, "m1"), ("c", 0, "m2")]);
+        let mut meshex = MeshExData::from_mesh_objects(&mesh.objects);
+        meshex.mesh_object_groups[2].bounding_sphere = BoundingSphere {
+            center: Vector3::new(1.0, 2.0, 3.0),
+            radius: 4.0,
+        };
+        let mut adj = adj(&[0, 1, 2]);
+        let mut anim = visibility(&["a", "b", "c"]);
+
+        let mut editor = ModelEditor {
+            modl: Some(&mut modl),
+            meshex: Some(&mut meshex),
+            adj: Some(&mut adj),
+            anims: vec![&mut anim],
+            ..ModelEditor::new(&mut mesh)
+        };
+        editor.remove_object(1).unwrap();
+
+        assert_eq!(vec![("a", 0), ("c", 0)], names(&mesh));
+        assert_eq!(
+            vec!["a", "c"],
+            modl.entries
+                .iter()
+                .map(|e| e.mesh_object_name.as_str())
+                .collect_vec()
+        );
+        assert_eq!(
+            vec!["a", "c"],
+            meshex
+                .mesh_object_groups
+                .iter()
+                .map(|g| g.mesh_object_full_name.as_str())
+                .collect_vec()
+        );
+        // Unchanged groups should preserve their bounding spheres.
+        assert_eq!(4.0, meshex.mesh_object_groups[1].bounding_sphere.radius);
+        assert_eq!(
+            vec![(0, vec![0]), (1, vec![2])],
+            adj.entries
+                .iter()
+                .map(|e| (e.mesh_object_index, e.vertex_adjacency.clone()))
+                .collect_vec()
+        );
+        assert_eq!(vec!["a", "c"], node_names(&anim));
+    }
+
+    #[test]
+    fn reorder_objects_adj() {
+        let mut mesh = mesh(&[("a", 0), ("b", 0), ("c", 0)]);
+        let mut adj = adj(&[0, 2]);
+
+        let mut editor = ModelEditor::new(&mut mesh);
+        editor.adj = Some(&mut adj);
+        editor.reorder_objects(&[2, 0, 1]).unwrap();
+
+        assert_eq!(vec![("c", 0), ("a", 0), ("b", 0)], names(&mesh));
+        assert_eq!(
+            vec![(0, vec![2]), (1, vec![0])],
+            adj.entries
+                .iter()
+                .map(|e| (e.mesh_object_index, e.vertex_adjacency.clone()))
+                .collect_vec()
+        );
+    }
+
+    #[test]
+    fn reorder_objects_invalid_order() {
+        let mut mesh = mesh(&[("a", 0), ("b", 0)]);
+        let mut editor = ModelEditor::new(&mut mesh);
+
+        assert!(matches!(
+            editor.reorder_objects(&[0, 0]),
+            Err(error::Error::InvalidOrder {
+                mesh_object_count: 2
+            })
+        ));
+        assert!(matches!(
+            editor.reorder_objects(&[0]),
+            Err(error::Error::InvalidOrder {
+                mesh_object_count: 2
+            })
+        ));
+        assert_eq!(
+            "the new order must contain each index less than 2 exactly once",
+            editor.reorder_objects(&[0]).unwrap_err().to_string()
+        );
+    }
+
+    #[test]
+    fn duplicate_object_all_files() {
+        let mut mesh = mesh(&[("a", 0), ("b", 0)]);
+        let mut modl = modl(&[("a", 0, "m0"), ("b", 0, "m1")]);
+        let mut meshex = MeshExData::from_mesh_objects(&mesh.objects);
+        let mut adj = adj(&[0, 1]);
+
+        let mut editor = ModelEditor {
+            modl: Some(&mut modl),
+            meshex: Some(&mut meshex),
+            adj: Some(&mut adj),
+            ..ModelEditor::new(&mut mesh)
+        };
+        assert_eq!(1, editor.duplicate_object(0).unwrap());
+
+        assert_eq!(vec![("a", 0), ("a", 1), ("b", 0)], names(&mesh));
+        assert_eq!(
+            vec![("a", 0), ("a", 1), ("b", 0)],
+            modl.entries
+                .iter()
+                .map(|e| (e.mesh_object_name.as_str(), e.mesh_object_subindex))
+                .collect_vec()
+        );
+        assert_eq!(2, meshex.mesh_object_groups[0].entry_flags.len());
+        assert_eq!(
+            vec![(0, vec![0]), (1, vec![0]), (2, vec![1])],
+            adj.entries
+                .iter()
+                .map(|e| (e.mesh_object_index, e.vertex_adjacency.clone()))
+                .collect_vec()
+        );
+    }
+
+    #[test]
+    fn remove_object_invalid_index() {
+        let mut mesh = mesh(&[("a", 0)]);
+        let mut editor = ModelEditor::new(&mut mesh);
+        assert!(matches!(
+            editor.remove_object(1),
+            Err(error::Error::MeshObjectIndexOutOfRange {
+                index: 1,
+                mesh_object_count: 1
+            })
+        ));
+    }
+}
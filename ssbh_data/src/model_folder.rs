@@ -0,0 +1,543 @@
+//! Types for loading and saving all the files for a model using a [ModlData].
+//!
+//! # Examples
+//! The [ModlData] in a .numdlb file names the mesh, skeleton, material, and animation files for a model.
+//! Each file is loaded separately, so a missing or invalid file does not prevent loading the others.
+/*!
+```rust no_run
+# fn main() -> Result<(), Box<dyn std::error::Error>> {
+use ssbh_data::model_folder::ModelFolder;
+
+let model = ModelFolder::from_modl_file("mario/model/body/c00/model.numdlb")?;
+
+match &model.mesh.data {
+    Ok(mesh) => println!("Mesh Objects: {}", mesh.objects.len()),
+    Err(e) => println!("{e}"),
+}
+
+model.write_to_folder("mario/model/body/c05")?;
+# Ok(()) }
+```
+ */
+use std::path::Path;
+
+use crate::prelude::*;
+
+/// The default file name for [ModlData] in a model folder.
+pub const MODL_FILE_NAME: &str = "model.numdlb";
+/// The file name for [HlpbData] in a model folder.
+pub const HLPB_FILE_NAME: &str = "model.nuhlpb";
+/// The file name for [MeshExData] in a model folder.
+pub const MESHEX_FILE_NAME: &str = "model.numshexb";
+/// The file name for [AdjData] in a model folder.
+pub const ADJ_FILE_NAME: &str = "model.adjb";
+
+pub mod error {
+    use std::path::PathBuf;
+    use thiserror::Error;
+
+    /// Errors while loading or saving a file in a [ModelFolder](super::ModelFolder).
+    #[derive(Debug, Error)]
+    pub enum Error {
+        /// The file does not exist.
+        #[error("file {} does not exist", path.display())]
+        MissingFile { path: PathBuf },
+
+        /// The file exists but could not be read or converted.
+        #[error("failed to read {}: {}", path.display(), error)]
+        ReadFile {
+            path: PathBuf,
+            error: Box<dyn std::error::Error>,
+        },
+
+        /// The data could not be converted or written to the file.
+        #[error("failed to write {}: {}", path.display(), error)]
+        WriteFile {
+            path: PathBuf,
+            error: Box<dyn std::error::Error>,
+        },
+
+        /// The number of material files does not match the number of material file names in the [ModlData](crate::modl_data::ModlData).
+        #[error(
+            "material file count {} does not match the material file name count {}",
+            matl_count,
+            file_name_count
+        )]
+        MaterialCountMismatch {
+            matl_count: usize,
+            file_name_count: usize,
+        },
+    }
+}
+
+/// A file in a [ModelFolder] and the result of loading it.
+#[derive(Debug)]
+pub struct ModelFile<T> {
+    /// The file name relative to the model folder like `"model.numshb"` used when loading.
+    /// Saving uses the file names from the [ModlData] or the fixed file names instead.
+    pub name: String,
+    pub data: Result<T, error::Error>,
+}
+
+impl<T> ModelFile<T> {
+    /// Creates a new [ModelFile] with successfully loaded `data`.
+    pub fn new<S: Into<String>>(name: S, data: T) -> Self {
+        Self {
+            name: name.into(),
+            data: Ok(data),
+        }
+    }
+}
+
+/// The files for a model referenced by a [ModlData] and the additional files that use fixed names.
+#[derive(Debug)]
+pub struct ModelFolder {
+    /// The file name of the .numdlb file like `"model.numdlb"`.
+    pub modl_file_name: String,
+    pub modl: ModlData,
+    /// The file for [mesh_file_name](struct.ModlData.html#structfield.mesh_file_name).
+    pub mesh: ModelFile<MeshData>,
+    /// The file for [skeleton_file_name](struct.ModlData.html#structfield.skeleton_file_name).
+    pub skel: ModelFile<SkelData>,
+    /// The files for [material_file_names](struct.ModlData.html#structfield.material_file_names) in the same order.
+    pub matls: Vec<ModelFile<MatlData>>,
+    /// The file for [animation_file_name](struct.ModlData.html#structfield.animation_file_name)
+    /// or [None] if the [ModlData] has no animation file.
+    pub anim: Option<ModelFile<AnimData>>,
+    /// The file for [HLPB_FILE_NAME] or [None] if the file does not exist.
+    pub hlpb: Option<ModelFile<HlpbData>>,
+    /// The file for [MESHEX_FILE_NAME] or [None] if the file does not exist.
+    pub meshex: Option<ModelFile<MeshExData>>,
+    /// The file for [ADJ_FILE_NAME] or [None] if the file does not exist.
+    pub adj: Option<ModelFile<AdjData>>,
+}
+
+impl ModelFolder {
+    /// Loads the [MODL_FILE_NAME] file in `folder` and all the files it references.
+    /// See [ModelFolder::from_modl_file].
+    pub fn from_folder<P: AsRef<Path>>(folder: P) -> Result<Self, error::Error> {
+        Self::from_modl_file(folder.as_ref().join(MODL_FILE_NAME))
+    }
+
+    /// Loads the .numdlb file at `path` and all the files it references from the same folder.
+    ///
+    /// Returns an error only if the [ModlData] cannot be loaded.
+    /// Errors for the remaining files are stored in each [ModelFile].
+    pub fn from_modl_file<P: AsRef<Path>>(path: P) -> Result<Self, error::Error> {
+        let path = path.as_ref();
+        let modl = read_file::<ModlData>(path)?;
+        let folder = path.parent().unwrap_or_else(|| Path::new(""));
+
+        Ok(Self {
+            modl_file_name: path
+                .file_name()
+                .map(|f| f.to_string_lossy().to_string())
+                .unwrap_or_else(|| MODL_FILE_NAME.to_string()),
+            mesh: load_file(folder, &modl.mesh_file_name),
+            skel: load_file(folder, &modl.skeleton_file_name),
+            matls: modl
+                .material_file_names
+                .iter()
+                .map(|name| load_file(folder, name))
+                .collect(),
+            anim: modl
+                .animation_file_name
+                .as_ref()
+                .map(|name| load_file(folder, name)),
+            hlpb: load_optional_file(folder, HLPB_FILE_NAME),
+            meshex: load_optional_file(folder, MESHEX_FILE_NAME),
+            adj: load_optional_file(folder, ADJ_FILE_NAME),
+            modl,
+        })
+    }
+
+    /// Returns the errors for each file that could not be loaded.
+    pub fn errors(&self) -> impl Iterator<Item = (&str, &error::Error)> {
+        fn file_error<T>(f: &ModelFile<T>) -> Option<(&str, &error::Error)> {
+            f.data.as_ref().err().map(|e| (f.name.as_str(), e))
+        }
+
+        file_error(&self.mesh)
+            .into_iter()
+            .chain(file_error(&self.skel))
+            .chain(self.matls.iter().filter_map(file_error))
+            .chain(self.anim.as_ref().and_then(file_error))
+            .chain(self.hlpb.as_ref().and_then(file_error))
+            .chain(self.meshex.as_ref().and_then(file_error))
+            .chain(self.adj.as_ref().and_then(file_error))
+    }
+
+    /// Saves the [ModlData] and all successfully loaded files to `folder`, creating `folder` if needed.
+    /// Returns the file names that were not saved because their [data](struct.ModelFile.html#structfield.data) is an error.
+    /// The animation is also skipped if only one of [anim](#structfield.anim)
+    /// and the animation file name in [modl](#structfield.modl) is present.
+    ///
+    /// The mesh, skeleton, material, and animation files use the file names from [modl](#structfield.modl),
+    /// and the remaining files use the same fixed file names as [ModelFolder::from_modl_file].
+    /// The [name](struct.ModelFile.html#structfield.name) of each file is not used,
+    /// so renaming files in the [ModlData] keeps the saved references consistent.
+    ///
+    /// Returns [error::Error::MaterialCountMismatch] without writing any files
+    /// if [matls](#structfield.matls) and the material file names have different lengths.
+    /**
+    ```rust no_run
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    use ssbh_data::model_folder::ModelFolder;
+
+    let model = ModelFolder::from_folder("mario/model/body/c00")?;
+    for name in model.write_to_folder("mario/model/body/c05")? {
+        println!("Skipped {name}");
+    }
+    # Ok(()) }
+    ```
+     */
+    pub fn write_to_folder<P: AsRef<Path>>(&self, folder: P) -> Result<Vec<String>, error::Error> {
+        if self.matls.len() != self.modl.material_file_names.len() {
+            return Err(error::Error::MaterialCountMismatch {
+                matl_count: self.matls.len(),
+                file_name_count: self.modl.material_file_names.len(),
+            });
+        }
+
+        let folder = folder.as_ref();
+        std::fs::create_dir_all(folder).map_err(|e| error::Error::WriteFile {
+            path: folder.to_owned(),
+            error: e.into(),
+        })?;
+
+        let mut skipped = Vec::new();
+        write_file(folder, &self.modl_file_name, Ok(&self.modl), &mut skipped)?;
+        write_file(
+            folder,
+            &self.modl.mesh_file_name,
+            self.mesh.data.as_ref(),
+            &mut skipped,
+        )?;
+        write_file(
+            folder,
+            &self.modl.skeleton_file_name,
+            self.skel.data.as_ref(),
+            &mut skipped,
+        )?;
+        for (name, matl) in self.modl.material_file_names.iter().zip(&self.matls) {
+            write_file(folder, name, matl.data.as_ref(), &mut skipped)?;
+        }
+        match (&self.modl.animation_file_name, &self.anim) {
+            (Some(name), Some(anim)) => write_file(folder, name, anim.data.as_ref(), &mut skipped)?,
+            (Some(name), None) => skipped.push(name.clone()),
+            (None, Some(anim)) => skipped.push(anim.name.clone()),
+            (None, None) => (),
+        }
+        write_optional_file(folder, HLPB_FILE_NAME, &self.hlpb, &mut skipped)?;
+        write_optional_file(folder, MESHEX_FILE_NAME, &self.meshex, &mut skipped)?;
+        write_optional_file(folder, ADJ_FILE_NAME, &self.adj, &mut skipped)?;
+
+        Ok(skipped)
+    }
+}
+
+fn read_file<T: SsbhData>(path: &Path) -> Result<T, error::Error> {
+    if !path.is_file() {
+        return Err(error::Error::MissingFile {
+            path: path.to_owned(),
+        });
+    }
+
+    T::from_file(path).map_err(|error| error::Error::ReadFile {
+        path: path.to_owned(),
+        error,
+    })
+}
+
+fn load_file<T: SsbhData>(folder: &Path, name: &str) -> ModelFile<T> {
+    ModelFile {
+        name: name.to_string(),
+        data: read_file(&folder.join(name)),
+    }
+}
+
+fn load_optional_file<T: SsbhData>(folder: &Path, name: &str) -> Option<ModelFile<T>> {
+    folder.join(name).is_file().then(|| load_file(folder, name))
+}
+
+fn write_file<T: SsbhData>(
+    folder: &Path,
+    name: &str,
+    data: Result<&T, &error::Error>,
+    skipped: &mut Vec<String>,
+) -> Result<(), error::Error>
+where
+    T::WriteError: 'static,
+{
+    match data {
+        Ok(data) => {
+            let path = folder.join(name);
+            data.write_to_file(&path)
+                .map_err(|e| error::Error::WriteFile {
+                    path,
+                    error: e.into(),
+                })
+        }
+        Err(_) => {
+            skipped.push(name.to_string());
+            Ok(())
+        }
+    }
+}
+
+fn write_optional_file<T: SsbhData>(
+    folder: &Path,
+    name: &str,
+    file: &Option<ModelFile<T>>,
+    skipped: &mut Vec<String>,
+) -> Result<(), error::Error>
+where
+    T::WriteError: 'static,
+{
+    match file {
+        Some(file) => write_file(folder, name, file.data.as_ref(), skipped),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modl_data::ModlEntryData;
+    use std::path::PathBuf;
+
+    fn test_folder(name: &str) -> PathBuf {
+        let folder = std::env::temp_dir().join(format!("ssbh_data_model_folder_{name}"));
+        let _ = std::fs::remove_dir_all(&folder);
+        folder
+    }
+
+    fn modl() -> ModlData {
+        ModlData {
+            major_version: 1,
+            minor_version: 7,
+            model_name: "model".to_string(),
+            skeleton_file_name: "model.nusktb".to_string(),
+            material_file_names: vec!["model.numatb".to_string()],
+            animation_file_name: None,
+            mesh_file_name: "model.numshb".to_string(),
+            entries: vec![ModlEntryData {
+                mesh_object_name: "a".to_string(),
+                mesh_object_subindex: 0,
+                material_label: "b".to_string(),
+            }],
+        }
+    }
+
+    #[test]
+    fn from_folder_missing_modl() {
+        let folder = test_folder("missing_modl");
+        let result = ModelFolder::from_folder(&folder);
+        assert!(matches!(result, Err(error::Error::MissingFile { .. })));
+    }
+
+    #[test]
+    fn write_read_folder_missing_files() {
+        let folder = test_folder("missing_files");
+
+        let model = ModelFolder {
+            modl_file_name: MODL_FILE_NAME.to_string(),
+            modl: modl(),
+            mesh: ModelFile::new("model.numshb", MeshData::new(1, 10, Vec::new())),
+            skel: ModelFile {
+                name: "model.nusktb".to_string(),
+                data: Err(error::Error::MissingFile {
+                    path: "model.nusktb".into(),
+                }),
+            },
+            matls: vec![ModelFile::new(
+                "model.numatb",
+                MatlData {
+                    major_version: 1,
+                    minor_version: 6,
+                    entries: Vec::new(),
+                },
+            )],
+            anim: None,
+            hlpb: None,
+            meshex: None,
+            adj: Some(ModelFile::new(
+                ADJ_FILE_NAME,
+                AdjData {
+                    entries: Vec::new(),
+                },
+            )),
+        };
+        assert_eq!(
+            vec!["model.nusktb"],
+            model.write_to_folder(&folder).unwrap()
+        );
+
+        let new_model = ModelFolder::from_folder(&folder).unwrap();
+        assert_eq!(model.modl, new_model.modl);
+        assert!(new_model.mesh.data.is_ok());
+        assert!(matches!(
+            new_model.skel.data,
+            Err(error::Error::MissingFile { .. })
+        ));
+        assert_eq!(1, new_model.matls.len());
+        assert!(new_model.matls[0].data.is_ok());
+        assert!(new_model.anim.is_none());
+        assert!(new_model.hlpb.is_none());
+        assert!(new_model.meshex.is_none());
+        assert!(new_model.adj.as_ref().unwrap().data.is_ok());
+        assert_eq!(
+            vec!["model.nusktb"],
+            new_model.errors().map(|(n, _)| n).collect::<Vec<_>>()
+        );
+
+        std::fs::remove_dir_all(&folder).unwrap();
+    }
+
+    #[test]
+    fn write_folder_material_count_mismatch() {
+        let folder = test_folder("material_count_mismatch");
+
+        let model = ModelFolder {
+            modl_file_name: MODL_FILE_NAME.to_string(),
+            modl: modl(),
+            mesh: ModelFile {
+                name: "model.numshb".to_string(),
+                data: Err(error::Error::MissingFile {
+                    path: "model.numshb".into(),
+                }),
+            },
+            skel: ModelFile {
+                name: "model.nusktb".to_string(),
+                data: Err(error::Error::MissingFile {
+                    path: "model.nusktb".into(),
+                }),
+            },
+            matls: Vec::new(),
+            anim: None,
+            hlpb: None,
+            meshex: None,
+            adj: None,
+        };
+        assert!(matches!(
+            model.write_to_folder(&folder),
+            Err(error::Error::MaterialCountMismatch {
+                matl_count: 0,
+                file_name_count: 1
+            })
+        ));
+        assert!(!folder.exists());
+    }
+
+    #[test]
+    fn write_folder_optional_file_names() {
+        let folder = test_folder("optional_file_names");
+
+        let mut model = ModelFolder {
+            modl_file_name: MODL_FILE_NAME.to_string(),
+            modl: modl(),
+            mesh: ModelFile {
+                name: "model.numshb".to_string(),
+                data: Err(error::Error::MissingFile {
+                    path: "model.numshb".into(),
+                }),
+            },
+            skel: ModelFile {
+                name: "model.nusktb".to_string(),
+                data: Err(error::Error::MissingFile {
+                    path: "model.nusktb".into(),
+                }),
+            },
+            matls: vec![ModelFile {
+                name: "model.numatb".to_string(),
+                data: Err(error::Error::MissingFile {
+                    path: "model.numatb".into(),
+                }),
+            }],
+            anim: None,
+            hlpb: None,
+            meshex: None,
+            adj: Some(ModelFile::new(
+                "other.adjb",
+                AdjData {
+                    entries: Vec::new(),
+                },
+            )),
+        };
+        model.modl.material_file_names = vec!["renamed.numatb".to_string()];
+
+        // Files are saved using the names from the ModlData or the fixed names.
+        assert_eq!(
+            vec!["model.numshb", "model.nusktb", "renamed.numatb"],
+            model.write_to_folder(&folder).unwrap()
+        );
+        assert!(folder.join(ADJ_FILE_NAME).is_file());
+        assert!(!folder.join("other.adjb").exists());
+
+        std::fs::remove_dir_all(&folder).unwrap();
+    }
+
+    #[test]
+    fn write_folder_anim_file_name_mismatch() {
+        let folder = test_folder("anim_file_name_mismatch");
+
+        let mut model = ModelFolder {
+            modl_file_name: MODL_FILE_NAME.to_string(),
+            modl: modl(),
+            mesh: ModelFile {
+                name: "model.numshb".to_string(),
+                data: Err(error::Error::MissingFile {
+                    path: "model.numshb".into(),
+                }),
+            },
+            skel: ModelFile {
+                name: "model.nusktb".to_string(),
+                data: Err(error::Error::MissingFile {
+                    path: "model.nusktb".into(),
+                }),
+            },
+            matls: vec![ModelFile {
+                name: "model.numatb".to_string(),
+                data: Err(error::Error::MissingFile {
+                    path: "model.numatb".into(),
+                }),
+            }],
+            anim: Some(ModelFile {
+                name: "model.nuanmb".to_string(),
+                data: Err(error::Error::MissingFile {
+                    path: "model.nuanmb".into(),
+                }),
+            }),
+            hlpb: None,
+            meshex: None,
+            adj: None,
+        };
+
+        // The anim has no file name in the ModlData.
+        assert_eq!(
+            vec![
+                "model.numshb",
+                "model.nusktb",
+                "model.numatb",
+                "model.nuanmb"
+            ],
+            model.write_to_folder(&folder).unwrap()
+        );
+
+        // The ModlData references an anim that is not loaded.
+        model.anim = None;
+        model.modl.animation_file_name = Some("other.nuanmb".to_string());
+        assert_eq!(
+            vec![
+                "model.numshb",
+                "model.nusktb",
+                "model.numatb",
+                "other.nuanmb"
+            ],
+            model.write_to_folder(&folder).unwrap()
+        );
+        assert!(!folder.join("other.nuanmb").exists());
+
+        std::fs::remove_dir_all(&folder).unwrap();
+    }
+}
@@ -24,7 +24,14 @@ use thiserror::Error;
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
-// TODO: Add methods to SkelData to find the index of a given bone?
+mod hierarchy;
+pub(crate) use hierarchy::parent_order;
+pub use hierarchy::SkelHierarchy;
+
+mod edit;
+
+mod merge;
+pub use merge::{BoneMergeConflict, MergeConflictPolicy, SkelMergeOptions};
 
 /// The data associated with a [Skel] file.
 /// The supported version is 1.0.
@@ -62,7 +69,7 @@ pub mod error {
     use super::*;
     use thiserror::Error;
 
-    /// Errors while creating an [Skel] from [SkelData].
+    /// Errors while creating an [Skel] from [SkelData] or editing the bones of a [SkelData].
     #[derive(Debug, Error)]
     pub enum Error {
         /// Creating a [Skel] file for the given version is not supported.
@@ -83,6 +90,29 @@ pub mod error {
         /// An error occurred while writing data to a buffer.
         #[error(transparent)]
         Io(#[from] std::io::Error),
+
+        /// The index does not refer to a bone.
+        #[error(
+            "bone index {} is out of range for a bone collection of size {}",
+            index,
+            bone_count
+        )]
+        BoneIndexOutOfRange { index: usize, bone_count: usize },
+
+        /// Another bone already uses this name.
+        #[error("a bone named {:?} already exists", name)]
+        DuplicateBoneName { name: String },
+
+        /// The new order does not contain each bone index exactly once.
+        #[error(
+            "the new order must contain each index less than {} exactly once",
+            bone_count
+        )]
+        InvalidOrder { bone_count: usize },
+
+        /// Bones in both skeletons have different data when merging with [MergeConflictPolicy::Error].
+        #[error("{} bone(s) conflict when merging skeletons", conflicts.len())]
+        MergeConflicts { conflicts: Vec<BoneMergeConflict> },
     }
 }
 
@@ -0,0 +1,356 @@
+use glam::Mat4;
+use itertools::Itertools;
+
+use super::{calculate_relative_transform, error, BoneData, BoneTransformError, SkelData};
+
+// Bones are referenced by name from other files.
+// See MeshData::rename_bone, HlpbData::rename_bone, and AnimData::rename_bone for updating references.
+impl SkelData {
+    /// Inserts `bone` at `index` and updates the parent indices of the other bones.
+    /// The [parent_index](struct.BoneData.html#structfield.parent_index) of `bone`
+    /// refers to the bones before inserting and must be in range.
+    pub fn insert_bone(&mut self, index: usize, bone: BoneData) -> Result<(), error::Error> {
+        if index > self.bones.len() {
+            return Err(error::Error::BoneIndexOutOfRange {
+                index,
+                bone_count: self.bones.len(),
+            });
+        }
+        if let Some(parent_index) = bone.parent_index {
+            self.check_index(parent_index)?;
+        }
+        self.check_unique_name(&bone.name, None)?;
+
+        self.bones.insert(index, bone);
+        for b in &mut self.bones {
+            if let Some(parent_index) = b.parent_index.as_mut() {
+                if *parent_index >= index {
+                    *parent_index += 1;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Removes the bone at `index` and returns the removed bone.
+    ///
+    /// The children of the removed bone are moved to the removed bone's parent
+    /// and keep their world transforms.
+    /// Use [MeshData::remove_bone](crate::mesh_data::MeshData::remove_bone) with the skeleton before removing the bone
+    /// to move any skin weights and parented objects for the removed bone.
+    /// Use [AnimData::remove_bone](crate::anim_data::AnimData::remove_bone) with the skeleton before removing the bone
+    /// to update the animations for the children.
+    /**
+    ```rust
+    # use ssbh_data::skel_data::{BoneData, SkelData, BillboardType};
+    # use ssbh_data::mesh_data::MeshData;
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    # let bone = |name: &str, parent_index| BoneData {
+    #     name: name.to_string(),
+    #     transform: [[0.0; 4]; 4],
+    #     parent_index,
+    #     billboard_type: BillboardType::Disabled,
+    # };
+    # let mut skel = SkelData {
+    #     major_version: 1,
+    #     minor_version: 0,
+    #     bones: vec![bone("Hip", None), bone("H_Helper", Some(0))],
+    # };
+    # let mut mesh = MeshData::new(1, 10, Vec::new());
+    mesh.remove_bone("H_Helper", &skel);
+
+    let index = skel.bone_index("H_Helper").unwrap();
+    skel.remove_bone(index)?;
+    # Ok(()) }
+    ```
+     */
+    pub fn remove_bone(&mut self, index: usize) -> Result<BoneData, error::Error> {
+        self.check_index(index)?;
+        self.hierarchy()?;
+
+        let removed = self.bones.remove(index);
+        let removed_transform = Mat4::from_cols_array_2d(&removed.transform);
+        for bone in &mut self.bones {
+            match bone.parent_index {
+                Some(parent_index) if parent_index == index => {
+                    // Combine the transforms to preserve the world transform.
+                    let transform = removed_transform * Mat4::from_cols_array_2d(&bone.transform);
+                    bone.transform = transform.to_cols_array_2d();
+                    bone.parent_index = removed.parent_index.map(|p| shift_down(p, index));
+                }
+                Some(parent_index) => bone.parent_index = Some(shift_down(parent_index, index)),
+                None => (),
+            }
+        }
+
+        Ok(removed)
+    }
+
+    /// Reorders the bones so the bone at `order[i]` is moved to index `i`
+    /// and updates the parent indices.
+    /// See [SkelData::sort_topologically] for moving parents before their children.
+    pub fn reorder_bones(&mut self, order: &[usize]) -> Result<(), error::Error> {
+        let count = self.bones.len();
+        if order.len() != count || !order.iter().all(|i| *i < count) || !order.iter().all_unique() {
+            return Err(error::Error::InvalidOrder { bone_count: count });
+        }
+
+        let mut old_to_new = vec![0; count];
+        for (new, old) in order.iter().enumerate() {
+            old_to_new[*old] = new;
+        }
+
+        self.bones = order
+            .iter()
+            .map(|i| {
+                let bone = &self.bones[*i];
+                BoneData {
+                    parent_index: bone
+                        .parent_index
+                        .map(|p| old_to_new.get(p).copied().unwrap_or(p)),
+                    ..bone.clone()
+                }
+            })
+            .collect();
+        Ok(())
+    }
+
+    /// Renames the bone at `index` to `name`.
+    /// Bone names must be unique, so renaming to the name of a different bone is an error.
+    /// Use the `rename_bone` methods for [MeshData](crate::mesh_data::MeshData::rename_bone),
+    /// [HlpbData](crate::hlpb_data::HlpbData::rename_bone),
+    /// and [AnimData](crate::anim_data::AnimData::rename_bone) to update references.
+    pub fn rename_bone(&mut self, index: usize, name: &str) -> Result<(), error::Error> {
+        self.check_index(index)?;
+        self.check_unique_name(name, Some(index))?;
+
+        self.bones[index].name = name.to_string();
+        Ok(())
+    }
+
+    /// Sets the parent of the bone at `index` to `parent_index`
+    /// and updates the bone's transform to preserve its world transform.
+    /// Returns [BoneTransformError::CycleDetected] if `parent_index` is the bone or one of its descendants.
+    pub fn reparent_bone(
+        &mut self,
+        index: usize,
+        parent_index: Option<usize>,
+    ) -> Result<(), error::Error> {
+        self.check_index(index)?;
+        if let Some(parent_index) = parent_index {
+            self.check_index(parent_index)?;
+
+            // The bone can't be its own ancestor.
+            let mut current = Some(parent_index);
+            while let Some(ancestor) = current.filter(|i| *i < self.bones.len()) {
+                if ancestor == index {
+                    return Err(BoneTransformError::CycleDetected { index }.into());
+                }
+                current = self.bones[ancestor].parent_index;
+            }
+        }
+        self.hierarchy()?;
+
+        let world_transform = self.calculate_world_transform(&self.bones[index])?;
+        let parent_world_transform = parent_index
+            .map(|p| self.calculate_world_transform(&self.bones[p]))
+            .transpose()?;
+
+        let bone = &mut self.bones[index];
+        bone.transform =
+            calculate_relative_transform(&world_transform, parent_world_transform.as_ref());
+        bone.parent_index = parent_index;
+        Ok(())
+    }
+
+    fn check_index(&self, index: usize) -> Result<(), error::Error> {
+        if index < self.bones.len() {
+            Ok(())
+        } else {
+            Err(error::Error::BoneIndexOutOfRange {
+                index,
+                bone_count: self.bones.len(),
+            })
+        }
+    }
+
+    fn check_unique_name(&self, name: &str, index: Option<usize>) -> Result<(), error::Error> {
+        if self
+            .bones
+            .iter()
+            .enumerate()
+            .any(|(i, b)| Some(i) != index && b.name == name)
+        {
+            Err(error::Error::DuplicateBoneName {
+                name: name.to_string(),
+            })
+        } else {
+            Ok(())
+        }
+    }
+}
+
+fn shift_down(parent_index: usize, removed_index: usize) -> usize {
+    if parent_index > removed_index {
+        parent_index - 1
+    } else {
+        parent_index
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::skel_data::BillboardType;
+
+    fn translation(x: f32) -> [[f32; 4]; 4] {
+        Mat4::from_translation(glam::Vec3::new(x, 0.0, 0.0)).to_cols_array_2d()
+    }
+
+    fn bone(name: &str, parent_index: Option<usize>, x: f32) -> BoneData {
+        BoneData {
+            name: name.to_owned(),
+            transform: translation(x),
+            parent_index,
+            billboard_type: BillboardType::Disabled,
+        }
+    }
+
+    fn skel() -> SkelData {
+        SkelData {
+            major_version: 1,
+            minor_version: 0,
+            bones: vec![
+                bone("Trans", None, 1.0),
+                bone("Hip", Some(0), 2.0),
+                bone("H_Helper", Some(1), 4.0),
+                bone("Waist", Some(2), 8.0),
+            ],
+        }
+    }
+
+    fn parents(skel: &SkelData) -> Vec<Option<usize>> {
+        skel.bones.iter().map(|b| b.parent_index).collect()
+    }
+
+    fn world_x(skel: &SkelData, index: usize) -> f32 {
+        skel.calculate_world_transform(&skel.bones[index]).unwrap()[3][0]
+    }
+
+    #[test]
+    fn insert_bone() {
+        let mut data = skel();
+        data.insert_bone(1, bone("New", Some(0), 0.0)).unwrap();
+        assert_eq!("New", data.bones[1].name);
+        assert_eq!(
+            vec![None, Some(0), Some(0), Some(2), Some(3)],
+            parents(&data)
+        );
+
+        assert!(matches!(
+            data.insert_bone(1, bone("Hip", None, 0.0)),
+            Err(error::Error::DuplicateBoneName { name }) if name == "Hip"
+        ));
+        assert!(matches!(
+            data.insert_bone(6, bone("A", None, 0.0)),
+            Err(error::Error::BoneIndexOutOfRange {
+                index: 6,
+                bone_count: 5
+            })
+        ));
+        assert!(matches!(
+            data.insert_bone(1, bone("A", Some(5), 0.0)),
+            Err(error::Error::BoneIndexOutOfRange {
+                index: 5,
+                bone_count: 5
+            })
+        ));
+        assert_eq!(5, data.bones.len());
+    }
+
+    #[test]
+    fn remove_bone_preserves_world_transforms() {
+        let mut data = skel();
+        let removed = data.remove_bone(2).unwrap();
+        assert_eq!("H_Helper", removed.name);
+        assert_eq!(vec![None, Some(0), Some(1)], parents(&data));
+        assert_eq!("Waist", data.bones[2].name);
+        assert_eq!(15.0, world_x(&data, 2));
+
+        let removed = data.remove_bone(0).unwrap();
+        assert_eq!("Trans", removed.name);
+        assert_eq!(vec![None, Some(0)], parents(&data));
+        assert_eq!(3.0, world_x(&data, 0));
+        assert_eq!(15.0, world_x(&data, 1));
+    }
+
+    #[test]
+    fn reorder_bones() {
+        let mut data = skel();
+        data.reorder_bones(&[3, 2, 1, 0]).unwrap();
+        let names: Vec<_> = data.bones.iter().map(|b| b.name.as_str()).collect();
+        assert_eq!(vec!["Waist", "H_Helper", "Hip", "Trans"], names);
+        assert_eq!(vec![Some(1), Some(2), Some(3), None], parents(&data));
+
+        assert!(matches!(
+            data.reorder_bones(&[0, 0, 1, 2]),
+            Err(error::Error::InvalidOrder { bone_count: 4 })
+        ));
+        assert_eq!(
+            "the new order must contain each index less than 4 exactly once",
+            data.reorder_bones(&[0, 1]).unwrap_err().to_string()
+        );
+    }
+
+    #[test]
+    fn rename_bone() {
+        let mut data = skel();
+        data.rename_bone(2, "Helper").unwrap();
+        assert_eq!("Helper", data.bones[2].name);
+        // Renaming to the same name is allowed.
+        data.rename_bone(2, "Helper").unwrap();
+
+        assert!(matches!(
+            data.rename_bone(2, "Hip"),
+            Err(error::Error::DuplicateBoneName { name }) if name == "Hip"
+        ));
+        assert!(matches!(
+            data.rename_bone(4, "A"),
+            Err(error::Error::BoneIndexOutOfRange {
+                index: 4,
+                bone_count: 4
+            })
+        ));
+    }
+
+    #[test]
+    fn reparent_bone_preserves_world_transform() {
+        let mut data = skel();
+        data.reparent_bone(3, Some(0)).unwrap();
+        assert_eq!(Some(0), data.bones[3].parent_index);
+        assert_eq!(15.0, world_x(&data, 3));
+        assert_eq!(14.0, data.bones[3].transform[3][0]);
+
+        data.reparent_bone(3, None).unwrap();
+        assert_eq!(None, data.bones[3].parent_index);
+        assert_eq!(15.0, world_x(&data, 3));
+    }
+
+    #[test]
+    fn reparent_bone_cycle() {
+        let mut data = skel();
+        assert!(matches!(
+            data.reparent_bone(1, Some(3)),
+            Err(error::Error::BoneTransform(
+                BoneTransformError::CycleDetected { index: 1 }
+            ))
+        ));
+        assert!(matches!(
+            data.reparent_bone(1, Some(1)),
+            Err(error::Error::BoneTransform(
+                BoneTransformError::CycleDetected { index: 1 }
+            ))
+        ));
+        assert_eq!(skel(), data);
+    }
+}
@@ -0,0 +1,440 @@
+use std::collections::{HashMap, VecDeque};
+
+use super::{BoneData, BoneTransformError, SkelData};
+
+/// The parent and child relationships of the bones in a [SkelData].
+///
+/// The name lookup and child lists are calculated once in [SkelHierarchy::new],
+/// so prefer reusing the hierarchy over searching [SkelData::bones] repeatedly.
+/// Bones with a parent index outside the bones collection are treated as root bones.
+/**
+```rust
+# use ssbh_data::skel_data::{BoneData, SkelData, BillboardType, SkelHierarchy};
+# let bone = |name: &str, parent_index| BoneData {
+#     name: name.to_string(),
+#     transform: [[0.0; 4]; 4],
+#     parent_index,
+#     billboard_type: BillboardType::Disabled,
+# };
+let data = SkelData {
+    major_version: 1,
+    minor_version: 0,
+    bones: vec![bone("Trans", None), bone("Hip", Some(0)), bone("Waist", Some(1))],
+};
+
+let hierarchy = SkelHierarchy::new(&data)?;
+assert_eq!(Some(1), hierarchy.bone_index("Hip"));
+assert_eq!(&[2], hierarchy.children(1));
+assert_eq!(vec![0, 1, 2], hierarchy.depth_first());
+# Ok::<(), ssbh_data::skel_data::BoneTransformError>(())
+```
+ */
+#[derive(Debug, Clone)]
+pub struct SkelHierarchy<'a> {
+    skel: &'a SkelData,
+    name_to_index: HashMap<&'a str, usize>,
+    children: Vec<Vec<usize>>,
+    roots: Vec<usize>,
+}
+
+impl<'a> SkelHierarchy<'a> {
+    /// Calculates the hierarchy for the bones in `skel`.
+    /// Returns [BoneTransformError::CycleDetected] if any bone is its own ancestor.
+    pub fn new(skel: &'a SkelData) -> Result<Self, BoneTransformError> {
+        check_cycles(&skel.bones)?;
+
+        let mut name_to_index = HashMap::new();
+        let mut children = vec![Vec::new(); skel.bones.len()];
+        let mut roots = Vec::new();
+        for (i, bone) in skel.bones.iter().enumerate() {
+            // Use the first bone for duplicate names to match searching the bones in order.
+            name_to_index.entry(bone.name.as_str()).or_insert(i);
+
+            match parent(&skel.bones, i) {
+                Some(parent_index) => children[parent_index].push(i),
+                None => roots.push(i),
+            }
+        }
+
+        Ok(Self {
+            skel,
+            name_to_index,
+            children,
+            roots,
+        })
+    }
+
+    /// The index of the first bone named `name` or [None] if there is no bone with this name.
+    pub fn bone_index(&self, name: &str) -> Option<usize> {
+        self.name_to_index.get(name).copied()
+    }
+
+    /// The indices of the bones with `index` as their parent in ascending order.
+    pub fn children(&self, index: usize) -> &[usize] {
+        self.children
+            .get(index)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    /// The indices of the bones with no parent in ascending order.
+    pub fn roots(&self) -> &[usize] {
+        &self.roots
+    }
+
+    /// The bone indices in depth-first order starting from each root.
+    /// Each parent appears before its children.
+    pub fn depth_first(&self) -> Vec<usize> {
+        self.roots
+            .iter()
+            .flat_map(|root| self.descendants(*root))
+            .collect()
+    }
+
+    /// The bone indices in breadth-first order starting from all roots.
+    /// Each parent appears before its children.
+    pub fn breadth_first(&self) -> Vec<usize> {
+        let mut indices = Vec::with_capacity(self.children.len());
+        let mut queue: VecDeque<_> = self.roots.iter().copied().collect();
+        while let Some(index) = queue.pop_front() {
+            indices.push(index);
+            queue.extend(self.children(index));
+        }
+        indices
+    }
+
+    /// The indices of the bone at `index` and all of its descendants in depth-first order.
+    /// Returns an empty list if `index` is out of range.
+    pub fn descendants(&self, index: usize) -> Vec<usize> {
+        let mut indices = Vec::new();
+        if index >= self.children.len() {
+            return indices;
+        }
+
+        let mut stack = vec![index];
+        while let Some(index) = stack.pop() {
+            indices.push(index);
+            // Reverse the order to visit children in ascending order.
+            stack.extend(self.children(index).iter().rev());
+        }
+        indices
+    }
+
+    /// Creates a new [SkelData] with the bone at `index` and all of its descendants
+    /// or [None] if `index` is out of range.
+    ///
+    /// The bones keep their relative order, and parent indices refer to the new bones collection.
+    /// The bone at `index` becomes a root bone with its world transform
+    /// so the bones in the subtree have the same world transforms as before.
+    pub fn subtree(&self, index: usize) -> Option<SkelData> {
+        let root = self.skel.bones.get(index)?;
+        // Cycles are checked when creating the hierarchy.
+        let root_transform = self.skel.calculate_world_transform(root).ok()?;
+
+        let mut indices = self.descendants(index);
+        indices.sort_unstable();
+
+        let new_indices: HashMap<_, _> = indices
+            .iter()
+            .enumerate()
+            .map(|(new, old)| (*old, new))
+            .collect();
+
+        let bones = indices
+            .iter()
+            .map(|i| {
+                let bone = &self.skel.bones[*i];
+                if *i == index {
+                    BoneData {
+                        transform: root_transform,
+                        parent_index: None,
+                        ..bone.clone()
+                    }
+                } else {
+                    BoneData {
+                        parent_index: parent(&self.skel.bones, *i).map(|p| new_indices[&p]),
+                        ..bone.clone()
+                    }
+                }
+            })
+            .collect();
+
+        Some(SkelData {
+            major_version: self.skel.major_version,
+            minor_version: self.skel.minor_version,
+            bones,
+        })
+    }
+}
+
+impl SkelData {
+    /// The index of the first bone named `name` or [None] if there is no bone with this name.
+    /// Use [SkelHierarchy::bone_index] for repeated lookups.
+    pub fn bone_index(&self, name: &str) -> Option<usize> {
+        self.bones.iter().position(|b| b.name == name)
+    }
+
+    /// Calculates the [SkelHierarchy] for these bones.
+    /// Returns [BoneTransformError::CycleDetected] if any bone is its own ancestor.
+    pub fn hierarchy(&self) -> Result<SkelHierarchy<'_>, BoneTransformError> {
+        SkelHierarchy::new(self)
+    }
+
+    /// Returns `true` if every parent bone appears before its children in [bones](#structfield.bones).
+    /// Returns [BoneTransformError::CycleDetected] if any bone is its own ancestor.
+    pub fn parents_precede_children(&self) -> Result<bool, BoneTransformError> {
+        check_cycles(&self.bones)?;
+        Ok((0..self.bones.len()).all(|i| parent(&self.bones, i).is_none_or(|p| p < i)))
+    }
+
+    /// Reorders [bones](#structfield.bones) so that every parent bone appears before its children
+    /// and updates the parent indices.
+    /// Bones that already appear after their parents keep their relative order.
+    ///
+    /// Returns the previous index for each bone in the new order.
+    /// Returns [BoneTransformError::CycleDetected] if any bone is its own ancestor
+    /// without modifying the bones.
+    /**
+    ```rust
+    # use ssbh_data::skel_data::{BoneData, SkelData, BillboardType};
+    # let bone = |name: &str, parent_index| BoneData {
+    #     name: name.to_string(),
+    #     transform: [[0.0; 4]; 4],
+    #     parent_index,
+    #     billboard_type: BillboardType::Disabled,
+    # };
+    let mut data = SkelData {
+        major_version: 1,
+        minor_version: 0,
+        bones: vec![bone("Hip", Some(1)), bone("Trans", None)],
+    };
+
+    assert!(!data.parents_precede_children()?);
+    assert_eq!(vec![1, 0], data.sort_topologically()?);
+    assert_eq!("Trans", data.bones[0].name);
+    assert_eq!(Some(0), data.bones[1].parent_index);
+    # Ok::<(), ssbh_data::skel_data::BoneTransformError>(())
+    ```
+     */
+    pub fn sort_topologically(&mut self) -> Result<Vec<usize>, BoneTransformError> {
+        let order = parent_order(&self.bones)?;
+
+        let mut new_indices = vec![0; self.bones.len()];
+        for (new, old) in order.iter().enumerate() {
+            new_indices[*old] = new;
+        }
+
+        let bones = order
+            .iter()
+            .map(|i| BoneData {
+                parent_index: parent(&self.bones, *i).map(|p| new_indices[p]),
+                ..self.bones[*i].clone()
+            })
+            .collect();
+        self.bones = bones;
+
+        Ok(order)
+    }
+}
+
+/// The indices of `bones` ordered so that parents precede their children.
+/// Bones keep their relative order when possible.
+pub(crate) fn parent_order(bones: &[BoneData]) -> Result<Vec<usize>, BoneTransformError> {
+    check_cycles(bones)?;
+
+    let mut order = Vec::with_capacity(bones.len());
+    let mut added = vec![false; bones.len()];
+    for i in 0..bones.len() {
+        // Add any missing ancestors starting from the root.
+        let mut ancestors = Vec::new();
+        let mut current = Some(i);
+        while let Some(index) = current.filter(|index| !added[*index]) {
+            ancestors.push(index);
+            current = parent(bones, index);
+        }
+        for index in ancestors.into_iter().rev() {
+            added[index] = true;
+            order.push(index);
+        }
+    }
+    Ok(order)
+}
+
+/// The parent index for the bone at `index` if it refers to a bone in `bones`.
+fn parent(bones: &[BoneData], index: usize) -> Option<usize> {
+    bones[index].parent_index.filter(|p| *p < bones.len())
+}
+
+fn check_cycles(bones: &[BoneData]) -> Result<(), BoneTransformError> {
+    #[derive(Clone, Copy, PartialEq)]
+    enum State {
+        Unvisited,
+        Visiting,
+        Visited,
+    }
+
+    let mut states = vec![State::Unvisited; bones.len()];
+    for i in 0..bones.len() {
+        // Walk up the hierarchy until reaching a root or an already checked bone.
+        let mut path = Vec::new();
+        let mut current = Some(i);
+        while let Some(index) = current {
+            match states[index] {
+                State::Visited => break,
+                State::Visiting => return Err(BoneTransformError::CycleDetected { index }),
+                State::Unvisited => {
+                    states[index] = State::Visiting;
+                    path.push(index);
+                    current = parent(bones, index);
+                }
+            }
+        }
+
+        for index in path {
+            states[index] = State::Visited;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::skel_data::BillboardType;
+
+    fn bone(name: &str, parent_index: Option<usize>) -> BoneData {
+        BoneData {
+            name: name.to_owned(),
+            transform: [
+                [1.0, 0.0, 0.0, 0.0],
+                [0.0, 1.0, 0.0, 0.0],
+                [0.0, 0.0, 1.0, 0.0],
+                [1.0, 2.0, 3.0, 1.0],
+            ],
+            parent_index,
+            billboard_type: BillboardType::Disabled,
+        }
+    }
+
+    fn skel(bones: Vec<BoneData>) -> SkelData {
+        SkelData {
+            major_version: 1,
+            minor_version: 0,
+            bones,
+        }
+    }
+
+    fn tree() -> SkelData {
+        //   0     5
+        //  / \
+        // 1   3
+        // |   |
+        // 2   4
+        skel(vec![
+            bone("Trans", None),
+            bone("A", Some(0)),
+            bone("A1", Some(1)),
+            bone("B", Some(0)),
+            bone("B1", Some(3)),
+            bone("Other", None),
+        ])
+    }
+
+    #[test]
+    fn hierarchy_lookup() {
+        let data = tree();
+        let hierarchy = data.hierarchy().unwrap();
+        assert_eq!(Some(3), hierarchy.bone_index("B"));
+        assert_eq!(None, hierarchy.bone_index("C"));
+        assert_eq!(Some(3), data.bone_index("B"));
+
+        assert_eq!(&[0, 5], hierarchy.roots());
+        assert_eq!(&[1, 3], hierarchy.children(0));
+        assert!(hierarchy.children(2).is_empty());
+        assert!(hierarchy.children(10).is_empty());
+    }
+
+    #[test]
+    fn hierarchy_traversal() {
+        let data = tree();
+        let hierarchy = data.hierarchy().unwrap();
+        assert_eq!(vec![0, 1, 2, 3, 4, 5], hierarchy.depth_first());
+        assert_eq!(vec![0, 5, 1, 3, 2, 4], hierarchy.breadth_first());
+        assert_eq!(vec![3, 4], hierarchy.descendants(3));
+        assert!(hierarchy.descendants(6).is_empty());
+    }
+
+    #[test]
+    fn hierarchy_out_of_range_parent_is_root() {
+        let data = skel(vec![bone("a", Some(5)), bone("b", Some(0))]);
+        let hierarchy = data.hierarchy().unwrap();
+        assert_eq!(&[0], hierarchy.roots());
+        assert_eq!(vec![0, 1], hierarchy.depth_first());
+    }
+
+    #[test]
+    fn hierarchy_cycle() {
+        let data = skel(vec![
+            bone("root", None),
+            bone("a", Some(2)),
+            bone("b", Some(1)),
+        ]);
+        assert!(matches!(
+            data.hierarchy(),
+            Err(BoneTransformError::CycleDetected { index: 1 })
+        ));
+        assert!(matches!(
+            data.parents_precede_children(),
+            Err(BoneTransformError::CycleDetected { index: 1 })
+        ));
+
+        let mut data = skel(vec![bone("a", Some(0))]);
+        assert!(matches!(
+            data.sort_topologically(),
+            Err(BoneTransformError::CycleDetected { index: 0 })
+        ));
+        assert_eq!(Some(0), data.bones[0].parent_index);
+    }
+
+    #[test]
+    fn subtree_world_transform() {
+        let data = tree();
+        let subtree = data.hierarchy().unwrap().subtree(3).unwrap();
+        assert_eq!(2, subtree.bones.len());
+        assert_eq!("B", subtree.bones[0].name);
+        assert_eq!(None, subtree.bones[0].parent_index);
+        assert_eq!([2.0, 4.0, 6.0, 1.0], subtree.bones[0].transform[3]);
+        assert_eq!("B1", subtree.bones[1].name);
+        assert_eq!(Some(0), subtree.bones[1].parent_index);
+        assert_eq!(data.bones[4].transform, subtree.bones[1].transform);
+
+        assert!(data.hierarchy().unwrap().subtree(6).is_none());
+    }
+
+    #[test]
+    fn sort_topologically_already_sorted() {
+        let mut data = tree();
+        assert!(data.parents_precede_children().unwrap());
+        assert_eq!(vec![0, 1, 2, 3, 4, 5], data.sort_topologically().unwrap());
+        assert_eq!(tree(), data);
+    }
+
+    #[test]
+    fn sort_topologically_reorder() {
+        let mut data = skel(vec![
+            bone("c", Some(2)),
+            bone("d", None),
+            bone("b", Some(3)),
+            bone("a", None),
+        ]);
+        assert!(!data.parents_precede_children().unwrap());
+
+        assert_eq!(vec![3, 2, 0, 1], data.sort_topologically().unwrap());
+        let names: Vec<_> = data.bones.iter().map(|b| b.name.as_str()).collect();
+        assert_eq!(vec!["a", "b", "c", "d"], names);
+        let parents: Vec<_> = data.bones.iter().map(|b| b.parent_index).collect();
+        assert_eq!(vec![None, Some(0), Some(1), None], parents);
+        assert!(data.parents_precede_children().unwrap());
+    }
+}
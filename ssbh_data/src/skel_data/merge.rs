@@ -0,0 +1,376 @@
+use std::collections::{HashMap, HashSet};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use super::{error, BoneData, SkelData};
+
+/// How to handle bones in both skeletons with different data in [SkelData::merge].
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MergeConflictPolicy {
+    /// Keep the transform and parent of the base bone.
+    #[default]
+    PreferBase,
+    /// Use the transform, parent, and billboard type of the donor bone.
+    PreferDonor,
+    /// Return [error::Error::MergeConflicts] with the list of conflicts.
+    Error,
+}
+
+/// Options for [SkelData::merge].
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct SkelMergeOptions {
+    pub conflict_policy: MergeConflictPolicy,
+    /// Transforms with all matrix elements within this value are treated as equal.
+    /// Elements that are NaN are always treated as different.
+    pub transform_tolerance: f32,
+}
+
+/// A bone name in both skeletons with different data in [SkelData::merge].
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[derive(Debug, Clone, PartialEq)]
+pub enum BoneMergeConflict {
+    /// The bones have different [transform](struct.BoneData.html#structfield.transform) values.
+    Transform { bone_name: String },
+
+    /// The bones have parents with different names.
+    /// Root bones have a parent name of [None].
+    Parent {
+        bone_name: String,
+        base_parent_name: Option<String>,
+        donor_parent_name: Option<String>,
+    },
+}
+
+impl SkelData {
+    /// Adds the bones from `donor` that are not in this skeleton and lists the conflicts for bones in both skeletons.
+    ///
+    /// Bones are matched by name. Existing bones keep their order, and new bones are added at the end
+    /// in the order they appear in `donor` with parents moved before their children.
+    /// The parent of each new bone is the bone with the same name as its donor parent.
+    /// New bones keep their transform relative to their parent.
+    ///
+    /// Conflicts are resolved using the [conflict_policy](struct.SkelMergeOptions.html#structfield.conflict_policy).
+    /// Returns [BoneTransformError::CycleDetected](super::BoneTransformError::CycleDetected)
+    /// if either skeleton has a cycle.
+    /**
+    ```rust no_run
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    use ssbh_data::prelude::*;
+    use ssbh_data::skel_data::{MergeConflictPolicy, SkelMergeOptions};
+
+    let base = SkelData::from_file("mario/model/body/c00/model.nusktb")?;
+    let donor = SkelData::from_file("accessory/model.nusktb")?;
+
+    let options = SkelMergeOptions {
+        conflict_policy: MergeConflictPolicy::PreferBase,
+        transform_tolerance: 0.0001,
+    };
+    let (merged, conflicts) = base.merge(&donor, &options)?;
+    for conflict in conflicts {
+        println!("{conflict:?}");
+    }
+    merged.write_to_file("model.nusktb")?;
+    # Ok(()) }
+    ```
+     */
+    pub fn merge(
+        &self,
+        donor: &SkelData,
+        options: &SkelMergeOptions,
+    ) -> Result<(SkelData, Vec<BoneMergeConflict>), error::Error> {
+        self.hierarchy()?;
+        let donor_hierarchy = donor.hierarchy()?;
+
+        let mut merged = self.clone();
+
+        // Find the donor bones to add using the first bone for duplicate names.
+        let mut indices: HashMap<_, _> = self
+            .bones
+            .iter()
+            .enumerate()
+            .rev()
+            .map(|(i, b)| (b.name.as_str(), i))
+            .collect();
+        let mut added_names = HashSet::new();
+        let added: Vec<_> = donor
+            .bones
+            .iter()
+            .enumerate()
+            .filter(|(_, b)| !indices.contains_key(b.name.as_str()) && added_names.insert(&b.name))
+            .map(|(i, _)| i)
+            .collect();
+        let added = parents_first(donor, &added);
+
+        // Find the merged index for each donor bone.
+        for (i, donor_index) in added.iter().enumerate() {
+            indices.insert(&donor.bones[*donor_index].name, self.bones.len() + i);
+        }
+        let donor_to_merged: Vec<_> = donor
+            .bones
+            .iter()
+            .map(|b| indices[b.name.as_str()])
+            .collect();
+        let donor_parent =
+            |b: &BoneData| b.parent_index.and_then(|p| donor_to_merged.get(p).copied());
+
+        let mut conflicts = Vec::new();
+        for (i, donor_bone) in donor.bones.iter().enumerate() {
+            let index = donor_to_merged[i];
+            if index >= self.bones.len() || donor_hierarchy.bone_index(&donor_bone.name) != Some(i)
+            {
+                continue;
+            }
+
+            let base_bone = &self.bones[index];
+            let base_parent = base_bone.parent_index.filter(|p| *p < self.bones.len());
+            let parent_name = |p: Option<usize>| p.map(|p| merged.bones[p].name.clone());
+
+            let transform_differs = base_bone
+                .transform
+                .iter()
+                .flatten()
+                .zip(donor_bone.transform.iter().flatten())
+                .any(|(a, b)| {
+                    let difference = (a - b).abs();
+                    difference.is_nan() || difference > options.transform_tolerance
+                });
+            let parent_differs = base_parent != donor_parent(donor_bone);
+
+            if transform_differs {
+                conflicts.push(BoneMergeConflict::Transform {
+                    bone_name: base_bone.name.clone(),
+                });
+            }
+            if parent_differs {
+                conflicts.push(BoneMergeConflict::Parent {
+                    bone_name: base_bone.name.clone(),
+                    base_parent_name: parent_name(base_parent),
+                    donor_parent_name: parent_name(donor_parent(donor_bone)),
+                });
+            }
+        }
+
+        merged.bones.extend(added.iter().map(|i| BoneData {
+            parent_index: donor_parent(&donor.bones[*i]),
+            ..donor.bones[*i].clone()
+        }));
+
+        match options.conflict_policy {
+            MergeConflictPolicy::PreferBase => (),
+            MergeConflictPolicy::PreferDonor => {
+                for (i, donor_bone) in donor.bones.iter().enumerate() {
+                    let index = donor_to_merged[i];
+                    if index < self.bones.len()
+                        && donor_hierarchy.bone_index(&donor_bone.name) == Some(i)
+                    {
+                        merged.bones[index] = BoneData {
+                            name: merged.bones[index].name.clone(),
+                            parent_index: donor_parent(donor_bone),
+                            ..donor_bone.clone()
+                        };
+                    }
+                }
+            }
+            MergeConflictPolicy::Error => {
+                if !conflicts.is_empty() {
+                    return Err(error::Error::MergeConflicts { conflicts });
+                }
+            }
+        }
+
+        Ok((merged, conflicts))
+    }
+}
+
+/// Orders the donor bone indices in `added` so that parents in `added` precede their children.
+fn parents_first(donor: &SkelData, added: &[usize]) -> Vec<usize> {
+    let mut is_added = vec![false; donor.bones.len()];
+    for i in added {
+        is_added[*i] = true;
+    }
+
+    let mut order = Vec::with_capacity(added.len());
+    for i in added {
+        // Add any missing ancestors starting from the root.
+        let mut ancestors = Vec::new();
+        let mut current = Some(*i);
+        while let Some(index) = current.filter(|index| is_added[*index]) {
+            is_added[index] = false;
+            ancestors.push(index);
+            current = donor.bones[index]
+                .parent_index
+                .filter(|p| *p < donor.bones.len());
+        }
+        order.extend(ancestors.into_iter().rev());
+    }
+    order
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::skel_data::{BillboardType, BoneTransformError};
+
+    fn bone(name: &str, parent_index: Option<usize>, x: f32) -> BoneData {
+        BoneData {
+            name: name.to_owned(),
+            transform: [
+                [1.0, 0.0, 0.0, 0.0],
+                [0.0, 1.0, 0.0, 0.0],
+                [0.0, 0.0, 1.0, 0.0],
+                [x, 0.0, 0.0, 1.0],
+            ],
+            parent_index,
+            billboard_type: BillboardType::Disabled,
+        }
+    }
+
+    fn skel(bones: Vec<BoneData>) -> SkelData {
+        SkelData {
+            major_version: 1,
+            minor_version: 0,
+            bones,
+        }
+    }
+
+    fn names(skel: &SkelData) -> Vec<&str> {
+        skel.bones.iter().map(|b| b.name.as_str()).collect()
+    }
+
+    fn parents(skel: &SkelData) -> Vec<Option<usize>> {
+        skel.bones.iter().map(|b| b.parent_index).collect()
+    }
+
+    #[test]
+    fn merge_adds_missing_bones() {
+        let base = skel(vec![
+            bone("Trans", None, 0.0),
+            bone("Hip", Some(0), 1.0),
+            bone("Head", Some(1), 2.0),
+        ]);
+        // The donor has a different order and an extra chain of swing bones.
+        let donor = skel(vec![
+            bone("Hip", None, 1.0),
+            bone("S_Hair2", Some(3), 5.0),
+            bone("Head", Some(0), 2.0),
+            bone("S_Hair1", Some(2), 4.0),
+        ]);
+
+        let (merged, conflicts) = base.merge(&donor, &SkelMergeOptions::default()).unwrap();
+        // The new bones are ordered with parents first.
+        assert_eq!(
+            vec!["Trans", "Hip", "Head", "S_Hair1", "S_Hair2"],
+            names(&merged)
+        );
+        assert_eq!(
+            vec![None, Some(0), Some(1), Some(2), Some(3)],
+            parents(&merged)
+        );
+        assert!(merged.parents_precede_children().unwrap());
+        assert_eq!(donor.bones[1].transform, merged.bones[4].transform);
+
+        // Hip is a root bone in the donor.
+        assert_eq!(
+            vec![BoneMergeConflict::Parent {
+                bone_name: "Hip".into(),
+                base_parent_name: Some("Trans".into()),
+                donor_parent_name: None
+            }],
+            conflicts
+        );
+    }
+
+    #[test]
+    fn merge_transform_tolerance() {
+        let base = skel(vec![bone("Trans", None, 0.0), bone("Hip", Some(0), 1.0)]);
+        let donor = skel(vec![bone("Trans", None, 0.0), bone("Hip", Some(0), 1.001)]);
+
+        let (_, conflicts) = base.merge(&donor, &SkelMergeOptions::default()).unwrap();
+        assert_eq!(
+            vec![BoneMergeConflict::Transform {
+                bone_name: "Hip".into()
+            }],
+            conflicts
+        );
+
+        let options = SkelMergeOptions {
+            transform_tolerance: 0.01,
+            ..Default::default()
+        };
+        let (merged, conflicts) = base.merge(&donor, &options).unwrap();
+        assert!(conflicts.is_empty());
+        assert_eq!(base, merged);
+
+        // NaN is never within the tolerance.
+        let mut nan = donor.clone();
+        nan.bones[1].transform[0][0] = f32::NAN;
+        let (_, conflicts) = base.merge(&nan, &options).unwrap();
+        assert_eq!(
+            vec![BoneMergeConflict::Transform {
+                bone_name: "Hip".into()
+            }],
+            conflicts
+        );
+    }
+
+    #[test]
+    fn merge_conflict_policy() {
+        let base = skel(vec![
+            bone("Trans", None, 0.0),
+            bone("Hip", Some(0), 1.0),
+            bone("Head", Some(1), 2.0),
+        ]);
+        let donor = skel(vec![bone("Trans", None, 0.0), bone("Head", Some(0), 3.0)]);
+
+        let (merged, conflicts) = base.merge(&donor, &SkelMergeOptions::default()).unwrap();
+        assert_eq!(2, conflicts.len());
+        assert_eq!(base, merged);
+
+        let options = SkelMergeOptions {
+            conflict_policy: MergeConflictPolicy::PreferDonor,
+            ..Default::default()
+        };
+        let (merged, conflicts) = base.merge(&donor, &options).unwrap();
+        assert_eq!(2, conflicts.len());
+        assert_eq!(vec!["Trans", "Hip", "Head"], names(&merged));
+        assert_eq!(vec![None, Some(0), Some(0)], parents(&merged));
+        assert_eq!(3.0, merged.bones[2].transform[3][0]);
+
+        let options = SkelMergeOptions {
+            conflict_policy: MergeConflictPolicy::Error,
+            ..Default::default()
+        };
+        assert!(matches!(
+            base.merge(&donor, &options),
+            Err(error::Error::MergeConflicts { conflicts }) if conflicts.len() == 2
+        ));
+    }
+
+    #[test]
+    fn merge_prefer_donor_hierarchy() {
+        let base = skel(vec![bone("A", None, 0.0), bone("B", Some(0), 0.0)]);
+        let donor = skel(vec![bone("B", None, 0.0), bone("A", Some(0), 0.0)]);
+        let options = SkelMergeOptions {
+            conflict_policy: MergeConflictPolicy::PreferDonor,
+            ..Default::default()
+        };
+
+        // The donor hierarchy replaces the base hierarchy for existing bones.
+        let (merged, _) = base.merge(&donor, &options).unwrap();
+        assert_eq!(vec![Some(1), None], parents(&merged));
+
+        let cycle = skel(vec![bone("A", Some(1), 0.0), bone("B", Some(0), 0.0)]);
+        assert!(matches!(
+            base.merge(&cycle, &SkelMergeOptions::default()),
+            Err(error::Error::BoneTransform(
+                BoneTransformError::CycleDetected { .. }
+            ))
+        ));
+    }
+}
@@ -0,0 +1,175 @@
+//! Shared skinning logic for calculating posed vertex positions from a [SkelData] and [AnimData].
+use std::collections::HashMap;
+
+use geometry_tools::glam::{Mat4, Quat, Vec3, Vec3A, Vec4};
+
+use crate::anim_data::{GroupType, TrackValues, Transform};
+use crate::mesh_data::{MeshObjectData, SkinWeights};
+use crate::prelude::*;
+use crate::skel_data::{parent_order, BoneData, BoneTransformError};
+
+/// The transform values and compensate scale for each animated bone name.
+pub(crate) type TransformTracks<'a> = HashMap<&'a str, (&'a [Transform], bool)>;
+
+/// How the vertices of an object follow the skeleton.
+pub(crate) enum Binding {
+    Static,
+    Parent(usize),
+    Skinned(SkinWeights),
+}
+
+impl Binding {
+    /// Objects with influences are skinned using their weights,
+    /// and objects without influences follow their parent bone if it is in `skel`.
+    pub(crate) fn new(
+        object: &MeshObjectData,
+        skel: &SkelData,
+    ) -> Result<Self, crate::mesh_data::error::Error> {
+        if !object.bone_influences.is_empty() {
+            Ok(Binding::Skinned(SkinWeights::from_bone_influences_skel(
+                &object.bone_influences,
+                skel,
+                object.vertex_count()?,
+            )?))
+        } else {
+            match skel
+                .bones
+                .iter()
+                .position(|b| b.name == object.parent_bone_name)
+            {
+                Some(index) => Ok(Binding::Parent(index)),
+                None => Ok(Binding::Static),
+            }
+        }
+    }
+
+    /// Transforms the vertex at `index` with rest pose `position` using the `skinning_transforms` for each bone.
+    /// Vertices without weights are not transformed.
+    pub(crate) fn skin_position(
+        &self,
+        index: usize,
+        position: Vec3A,
+        skinning_transforms: &[Mat4],
+    ) -> Vec3A {
+        match self {
+            Binding::Static => position,
+            Binding::Parent(bone) => skinning_transforms[*bone].transform_point3a(position),
+            Binding::Skinned(skin_weights) => match skin_weights.vertex_weights.get(index) {
+                Some(weights) if !weights.is_empty() => weights
+                    .iter()
+                    .map(|(bone, weight)| {
+                        skinning_transforms[*bone].transform_point3a(position) * *weight
+                    })
+                    .sum(),
+                _ => position,
+            },
+        }
+    }
+}
+
+/// Calculates the transform from the rest pose to the pose at `frame` for each bone.
+/// Bones without a track in `tracks` use their transform from `skel`.
+/// Tracks with fewer frames use their last value.
+pub(crate) fn skinning_transforms(
+    skel: &SkelData,
+    tracks: &TransformTracks,
+    frame: usize,
+    inverse_rest_transforms: &[Mat4],
+) -> Result<Vec<Mat4>, BoneTransformError> {
+    let animated_transforms = world_transforms(skel, |bone| {
+        tracks
+            .get(bone.name.as_str())
+            .and_then(|(values, compensate_scale)| {
+                values
+                    .get(frame)
+                    .or_else(|| values.last())
+                    .map(|t| (*t, *compensate_scale))
+            })
+    })?;
+    Ok(animated_transforms
+        .iter()
+        .zip(inverse_rest_transforms)
+        .map(|(animated, inverse_rest)| *animated * *inverse_rest)
+        .collect())
+}
+
+/// The number of frames in `anim` including the final frame.
+pub(crate) fn frame_count(anim: &AnimData) -> usize {
+    if anim.final_frame_index >= 0.0 {
+        anim.final_frame_index as usize + 1
+    } else {
+        0
+    }
+}
+
+/// Finds the transform values and compensate scale for each animated bone name.
+pub(crate) fn transform_tracks(anim: &AnimData) -> TransformTracks<'_> {
+    anim.groups
+        .iter()
+        .filter(|g| g.group_type == GroupType::Transform)
+        .flat_map(|g| &g.nodes)
+        .filter_map(|n| {
+            n.tracks.iter().find_map(|t| match &t.values {
+                TrackValues::Transform(values) => {
+                    Some((n.name.as_str(), (values.as_slice(), t.compensate_scale)))
+                }
+                _ => None,
+            })
+        })
+        .collect()
+}
+
+/// Converts the decomposed transform `t` to a matrix.
+pub(crate) fn transform_matrix(t: &Transform) -> Mat4 {
+    Mat4::from_scale_rotation_translation(
+        Vec3::new(t.scale.x, t.scale.y, t.scale.z),
+        Quat::from_xyzw(t.rotation.x, t.rotation.y, t.rotation.z, t.rotation.w),
+        Vec3::new(t.translation.x, t.translation.y, t.translation.z),
+    )
+}
+
+/// Calculates the world transform of each bone using the local transform from `animated_transform`
+/// or the bone's transform if there is no animated transform.
+pub(crate) fn world_transforms<'a>(
+    skel: &'a SkelData,
+    animated_transform: impl Fn(&'a BoneData) -> Option<(Transform, bool)>,
+) -> Result<Vec<Mat4>, BoneTransformError> {
+    let local_transforms: Vec<_> = skel
+        .bones
+        .iter()
+        .map(|b| match animated_transform(b) {
+            Some((t, compensate_scale)) => (transform_matrix(&t), compensate_scale),
+            None => (Mat4::from_cols_array_2d(&b.transform), false),
+        })
+        .collect();
+
+    // Only the immediate parent's scale is compensated.
+    // The inverse scale is applied before the translation like in Autodesk Maya.
+    let parent_relative_transform = |index: usize| {
+        let (transform, compensate_scale) = local_transforms[index];
+        match skel.bones[index].parent_index {
+            Some(parent) if compensate_scale && parent < local_transforms.len() => {
+                let (parent_scale, _, _) =
+                    local_transforms[parent].0.to_scale_rotation_translation();
+                let mut rotation_scale = transform;
+                rotation_scale.w_axis = Vec4::W;
+                Mat4::from_translation(transform.w_axis.truncate())
+                    * Mat4::from_scale(parent_scale.recip())
+                    * rotation_scale
+            }
+            _ => transform,
+        }
+    };
+
+    // Visit parents before their children to calculate each world transform once.
+    let mut transforms = vec![Mat4::IDENTITY; skel.bones.len()];
+    for i in parent_order(&skel.bones)? {
+        transforms[i] = match skel.bones[i].parent_index {
+            Some(parent) if parent < transforms.len() => {
+                transforms[parent] * parent_relative_transform(i)
+            }
+            _ => parent_relative_transform(i),
+        };
+    }
+    Ok(transforms)
+}
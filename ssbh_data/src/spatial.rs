@@ -0,0 +1,471 @@
+//! Ray casts, closest point queries, and box overlap tests for the triangles of a [MeshData].
+//!
+//! [MeshSpatialIndex] builds a bounding volume hierarchy over the triangles of every object
+//! in the rest pose or a skinned pose from an animation.
+//! The index is immutable, so rebuild it after editing the mesh or changing the pose.
+//!
+//! # Examples
+/*!
+```rust no_run
+# fn main() -> Result<(), Box<dyn std::error::Error>> {
+use ssbh_data::prelude::*;
+use ssbh_data::spatial::MeshSpatialIndex;
+
+let mesh = MeshData::from_file("model.numshb")?;
+let index = MeshSpatialIndex::new(&mesh)?;
+
+if let Some(hit) = index.cast_ray([0.0, 10.0, 50.0], [0.0, 0.0, -1.0], f32::INFINITY) {
+    let object = &mesh.objects[hit.triangle.object_index];
+    println!("{} {}: {:?}", object.name, hit.triangle.triangle_index, hit.point);
+}
+
+// Query the pose at a specific frame instead.
+let skel = SkelData::from_file("model.nusktb")?;
+let anim = AnimData::from_file("a00wait1.nuanmb")?;
+let posed_index = MeshSpatialIndex::from_pose(&mesh, &skel, &anim, 10)?;
+let closest = posed_index.closest_point([0.0, 10.0, 0.0]);
+# Ok(()) }
+```
+ */
+use geometry_tools::glam::Vec3A;
+
+use crate::mesh_data::bvh::{
+    closest_point_triangle, ray_triangle, triangle_bounds, triangle_overlaps_aabb, Bvh,
+};
+use crate::mesh_data::MeshObjectData;
+use crate::prelude::*;
+use crate::skinning::{skinning_transforms, transform_tracks, world_transforms, Binding};
+
+pub mod error {
+    use thiserror::Error;
+
+    /// Errors while creating a [MeshSpatialIndex](super::MeshSpatialIndex).
+    #[derive(Debug, Error)]
+    pub enum Error {
+        /// The mesh objects have invalid vertex data or influences for bones not in the skeleton.
+        #[error(transparent)]
+        Mesh(#[from] crate::mesh_data::error::Error),
+
+        /// An error occurred while calculating a transformation matrix.
+        #[error(transparent)]
+        BoneTransform(#[from] crate::skel_data::BoneTransformError),
+    }
+}
+
+/// Identifies a triangle in a [MeshData].
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy)]
+pub struct TriangleId {
+    /// The index into [objects](crate::mesh_data::MeshData#structfield.objects).
+    pub object_index: usize,
+    /// The index of the triangle formed by
+    /// [vertex_indices](crate::mesh_data::MeshObjectData#structfield.vertex_indices)
+    /// `3 * triangle_index..3 * triangle_index + 3`.
+    pub triangle_index: usize,
+}
+
+/// The closest triangle hit by a ray from [MeshSpatialIndex::cast_ray].
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct RayHit {
+    pub triangle: TriangleId,
+    /// The distance along the ray to [point](#structfield.point).
+    pub distance: f32,
+    pub point: [f32; 3],
+    /// The weights of the triangle's three vertices for [point](#structfield.point).
+    /// This can be used to interpolate attributes like texture coordinates.
+    pub barycentrics: [f32; 3],
+}
+
+/// The closest point on any triangle from [MeshSpatialIndex::closest_point].
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct ClosestPoint {
+    pub triangle: TriangleId,
+    /// The distance from the query point to [point](#structfield.point).
+    pub distance: f32,
+    pub point: [f32; 3],
+    /// The weights of the triangle's three vertices for [point](#structfield.point).
+    pub barycentrics: [f32; 3],
+}
+
+/// A bounding volume hierarchy over the triangles of all objects in a [MeshData].
+/// Only the first position attribute of each object is used.
+/// Objects without positions have no triangles.
+#[derive(Debug, Clone)]
+pub struct MeshSpatialIndex {
+    ids: Vec<TriangleId>,
+    triangles: Vec<[Vec3A; 3]>,
+    bvh: Bvh,
+}
+
+impl MeshSpatialIndex {
+    /// Creates an index for the rest pose of `mesh`.
+    pub fn new(mesh: &MeshData) -> Result<Self, error::Error> {
+        Self::from_positions(mesh, |_, positions| Ok(positions))
+    }
+
+    /// Creates an index for the pose of `mesh` at `frame` in `anim`.
+    ///
+    /// Objects with [bone_influences](crate::mesh_data::MeshObjectData#structfield.bone_influences) are skinned
+    /// using their weights, and objects without influences follow their
+    /// [parent_bone_name](crate::mesh_data::MeshObjectData#structfield.parent_bone_name).
+    /// Bones without an animated transform in the [GroupType::Transform](crate::anim_data::GroupType::Transform)
+    /// group use their transform from `skel`.
+    pub fn from_pose(
+        mesh: &MeshData,
+        skel: &SkelData,
+        anim: &AnimData,
+        frame: usize,
+    ) -> Result<Self, error::Error> {
+        let inverse_rest_transforms: Vec<_> = world_transforms(skel, |_| None)?
+            .iter()
+            .map(|t| t.inverse())
+            .collect();
+        let transforms = skinning_transforms(
+            skel,
+            &transform_tracks(anim),
+            frame,
+            &inverse_rest_transforms,
+        )?;
+
+        Self::from_positions(mesh, |object, positions| {
+            let binding = Binding::new(object, skel)?;
+            Ok(positions
+                .into_iter()
+                .enumerate()
+                .map(|(i, p)| binding.skin_position(i, p, &transforms))
+                .collect())
+        })
+    }
+
+    fn from_positions(
+        mesh: &MeshData,
+        mut transform_positions: impl FnMut(
+            &MeshObjectData,
+            Vec<Vec3A>,
+        )
+            -> Result<Vec<Vec3A>, crate::mesh_data::error::Error>,
+    ) -> Result<Self, error::Error> {
+        let mut ids = Vec::new();
+        let mut triangles = Vec::new();
+        for (object_index, object) in mesh.objects.iter().enumerate() {
+            let Some(positions) = object.positions.first() else {
+                continue;
+            };
+            let object_triangles = object.triangles()?;
+            let positions = transform_positions(object, positions.data.to_glam_vec3a())?;
+
+            for (triangle_index, [v0, v1, v2]) in object_triangles.into_iter().enumerate() {
+                ids.push(TriangleId {
+                    object_index,
+                    triangle_index,
+                });
+                triangles.push([positions[v0], positions[v1], positions[v2]]);
+            }
+        }
+
+        let bounds: Vec<_> = triangles.iter().map(|t| triangle_bounds(*t)).collect();
+        Ok(Self {
+            ids,
+            triangles,
+            bvh: Bvh::new(&bounds),
+        })
+    }
+
+    /// The number of triangles in the index.
+    pub fn triangle_count(&self) -> usize {
+        self.triangles.len()
+    }
+
+    /// The positions of the vertices of `triangle` in the indexed pose
+    /// or [None] if the triangle is not in the index.
+    pub fn triangle_positions(&self, triangle: TriangleId) -> Option<[[f32; 3]; 3]> {
+        let index = self.ids.binary_search(&triangle).ok()?;
+        Some(self.triangles[index].map(|v| v.to_array()))
+    }
+
+    /// Finds the closest triangle hit by the ray from `origin` in `direction`
+    /// within `max_distance` or [None] if no triangle is hit.
+    /// Both sides of each triangle are considered.
+    pub fn cast_ray(
+        &self,
+        origin: [f32; 3],
+        direction: [f32; 3],
+        max_distance: f32,
+    ) -> Option<RayHit> {
+        let origin = Vec3A::from(origin);
+        // Normalize the direction so that distances are in world units.
+        let direction = Vec3A::from(direction).try_normalize()?;
+
+        self.bvh
+            .ray(origin, direction, max_distance, |i| {
+                ray_triangle(origin, direction, self.triangles[i])
+            })
+            .map(|(i, distance, barycentrics)| RayHit {
+                triangle: self.ids[i],
+                distance,
+                point: (origin + direction * distance).to_array(),
+                barycentrics,
+            })
+    }
+
+    /// Finds the closest point to `point` on any triangle or [None] if the index is empty.
+    pub fn closest_point(&self, point: [f32; 3]) -> Option<ClosestPoint> {
+        let point = Vec3A::from(point);
+        self.bvh
+            .closest(point, |i| {
+                let (closest, barycentrics) = closest_point_triangle(point, self.triangles[i]);
+                Some((closest.distance_squared(point), (closest, barycentrics)))
+            })
+            .map(
+                |(i, distance_squared, (closest, barycentrics))| ClosestPoint {
+                    triangle: self.ids[i],
+                    distance: distance_squared.sqrt(),
+                    point: closest.to_array(),
+                    barycentrics,
+                },
+            )
+    }
+
+    /// Finds all triangles that overlap the axis aligned box from `min` to `max`
+    /// sorted by object index and triangle index.
+    pub fn overlap_aabb(&self, min: [f32; 3], max: [f32; 3]) -> Vec<TriangleId> {
+        let min = Vec3A::from(min);
+        let max = Vec3A::from(max);
+
+        let mut overlapping = Vec::new();
+        self.bvh.overlapping(min, max, |i| {
+            if triangle_overlaps_aabb(self.triangles[i], min, max) {
+                overlapping.push(self.ids[i]);
+            }
+        });
+        overlapping.sort();
+        overlapping
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::anim_data::GroupType;
+    use crate::anim_data::{GroupData, NodeData, TrackData, TrackValues, Transform};
+    use crate::mesh_data::{
+        AttributeData, BoneInfluence, MeshObjectData, VectorData, VertexWeight,
+    };
+    use crate::skel_data::{BillboardType, BoneData};
+    use ssbh_lib::{Vector3, Vector4};
+
+    fn quad(name: &str, z: f32) -> MeshObjectData {
+        MeshObjectData {
+            name: name.into(),
+            vertex_indices: vec![0, 1, 2, 2, 1, 3],
+            positions: vec![AttributeData::new(
+                "Position0",
+                VectorData::Vector3(vec![
+                    [0.0, 0.0, z],
+                    [1.0, 0.0, z],
+                    [0.0, 1.0, z],
+                    [1.0, 1.0, z],
+                ]),
+            )],
+            ..Default::default()
+        }
+    }
+
+    fn mesh(objects: Vec<MeshObjectData>) -> MeshData {
+        MeshData::new(1, 10, objects)
+    }
+
+    #[test]
+    fn cast_ray_closest_object() {
+        let mesh = mesh(vec![
+            quad("back", -1.0),
+            MeshObjectData {
+                name: "empty".into(),
+                ..Default::default()
+            },
+            quad("front", 1.0),
+        ]);
+        let index = MeshSpatialIndex::new(&mesh).unwrap();
+        assert_eq!(4, index.triangle_count());
+
+        let hit = index
+            .cast_ray([0.25, 0.5, 5.0], [0.0, 0.0, -2.0], f32::INFINITY)
+            .unwrap();
+        assert_eq!(
+            TriangleId {
+                object_index: 2,
+                triangle_index: 0
+            },
+            hit.triangle
+        );
+        assert_eq!(4.0, hit.distance);
+        assert_eq!([0.25, 0.5, 1.0], hit.point);
+        assert_eq!([0.25, 0.25, 0.5], hit.barycentrics);
+
+        // Rays from behind hit the other object first.
+        let hit = index
+            .cast_ray([0.75, 0.75, -5.0], [0.0, 0.0, 1.0], f32::INFINITY)
+            .unwrap();
+        assert_eq!(
+            TriangleId {
+                object_index: 0,
+                triangle_index: 1
+            },
+            hit.triangle
+        );
+
+        assert!(index
+            .cast_ray([0.25, 0.5, 5.0], [0.0, 0.0, -1.0], 3.0)
+            .is_none());
+        assert!(index
+            .cast_ray([2.0, 2.0, 5.0], [0.0, 0.0, -1.0], f32::INFINITY)
+            .is_none());
+        assert!(index
+            .cast_ray([0.25, 0.5, 5.0], [0.0; 3], f32::INFINITY)
+            .is_none());
+    }
+
+    #[test]
+    fn cast_ray_axis_aligned_edge() {
+        let index = MeshSpatialIndex::new(&mesh(vec![quad("a", 0.0)])).unwrap();
+        for origin in [[0.0, 0.5, 5.0], [1.0, 0.5, 5.0]] {
+            let hit = index.cast_ray(origin, [0.0, 0.0, -1.0], f32::INFINITY);
+            assert_eq!(Some(5.0), hit.map(|h| h.distance));
+        }
+    }
+
+    #[test]
+    fn closest_point_and_overlap() {
+        let index = MeshSpatialIndex::new(&mesh(vec![quad("a", 0.0), quad("b", 3.0)])).unwrap();
+
+        let closest = index.closest_point([2.0, 0.0, 1.0]).unwrap();
+        assert_eq!(0, closest.triangle.object_index);
+        assert_eq!([1.0, 0.0, 0.0], closest.point);
+        assert_eq!(2.0f32.sqrt(), closest.distance);
+
+        assert_eq!(
+            vec![
+                TriangleId {
+                    object_index: 1,
+                    triangle_index: 0
+                },
+                TriangleId {
+                    object_index: 1,
+                    triangle_index: 1
+                },
+            ],
+            index.overlap_aabb([0.4, 0.4, 2.5], [0.6, 0.6, 3.5])
+        );
+        assert_eq!(
+            vec![TriangleId {
+                object_index: 0,
+                triangle_index: 0
+            }],
+            index.overlap_aabb([0.0, 0.0, -0.5], [0.2, 0.2, 0.5])
+        );
+        assert!(index
+            .overlap_aabb([0.0, 0.0, 1.0], [1.0, 1.0, 2.0])
+            .is_empty());
+
+        assert_eq!(
+            Some([[0.0, 1.0, 3.0], [1.0, 0.0, 3.0], [1.0, 1.0, 3.0]]),
+            index.triangle_positions(TriangleId {
+                object_index: 1,
+                triangle_index: 1
+            })
+        );
+    }
+
+    #[test]
+    fn empty_mesh() {
+        let index = MeshSpatialIndex::new(&mesh(Vec::new())).unwrap();
+        assert!(index.closest_point([0.0; 3]).is_none());
+        assert!(index
+            .cast_ray([0.0; 3], [1.0, 0.0, 0.0], f32::INFINITY)
+            .is_none());
+        assert!(index.overlap_aabb([-1.0; 3], [1.0; 3]).is_empty());
+    }
+
+    #[test]
+    fn invalid_vertex_indices() {
+        let mut object = quad("a", 0.0);
+        object.vertex_indices.push(4);
+        assert!(matches!(
+            MeshSpatialIndex::new(&mesh(vec![object])),
+            Err(error::Error::Mesh(
+                crate::mesh_data::error::Error::VertexIndexOutOfRange { .. }
+            ))
+        ));
+    }
+
+    #[test]
+    fn from_pose_skinned() {
+        let mut skinned = quad("skinned", 0.0);
+        skinned.bone_influences = vec![BoneInfluence {
+            bone_name: "Arm".into(),
+            vertex_weights: (0..4)
+                .map(|i| VertexWeight {
+                    vertex_index: i,
+                    vertex_weight: 1.0,
+                })
+                .collect(),
+        }];
+        let mut parented = quad("parented", 0.0);
+        parented.parent_bone_name = "Hip".into();
+
+        let bone = |name: &str, parent_index| BoneData {
+            name: name.into(),
+            transform: [
+                [1.0, 0.0, 0.0, 0.0],
+                [0.0, 1.0, 0.0, 0.0],
+                [0.0, 0.0, 1.0, 0.0],
+                [0.0, 0.0, 0.0, 1.0],
+            ],
+            parent_index,
+            billboard_type: BillboardType::Disabled,
+        };
+        let skel = SkelData {
+            major_version: 1,
+            minor_version: 0,
+            bones: vec![bone("Hip", None), bone("Arm", Some(0))],
+        };
+
+        let transform = |z| Transform {
+            scale: Vector3::new(1.0, 1.0, 1.0),
+            rotation: Vector4::new(0.0, 0.0, 0.0, 1.0),
+            translation: Vector3::new(0.0, 0.0, z),
+        };
+        let anim = AnimData {
+            major_version: 2,
+            minor_version: 0,
+            final_frame_index: 1.0,
+            groups: vec![GroupData {
+                group_type: GroupType::Transform,
+                nodes: vec![NodeData {
+                    name: "Arm".into(),
+                    tracks: vec![TrackData {
+                        name: "Transform".into(),
+                        compensate_scale: false,
+                        transform_flags: Default::default(),
+                        values: TrackValues::Transform(vec![transform(0.0), transform(5.0)]),
+                    }],
+                }],
+            }],
+        };
+
+        let mesh = mesh(vec![skinned, parented]);
+        let index = MeshSpatialIndex::from_pose(&mesh, &skel, &anim, 1).unwrap();
+
+        // The skinned object moved to z = 5 and the parented object stayed at the rest pose.
+        let hit = index
+            .cast_ray([0.25, 0.25, 10.0], [0.0, 0.0, -1.0], f32::INFINITY)
+            .unwrap();
+        assert_eq!(0, hit.triangle.object_index);
+        assert_eq!([0.25, 0.25, 5.0], hit.point);
+
+        let hit = index
+            .cast_ray([0.25, 0.25, 2.0], [0.0, 0.0, -1.0], f32::INFINITY)
+            .unwrap();
+        assert_eq!(1, hit.triangle.object_index);
+        assert_eq!([0.25, 0.25, 0.0], hit.point);
+    }
+}
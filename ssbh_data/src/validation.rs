@@ -0,0 +1,591 @@
+//! Consistency checks for the references between the files for a model.
+//!
+//! Each format can be saved independently, but the game expects names and indices
+//! to match across files. For example, [ModlData] entries should only reference
+//! mesh objects from the [MeshData] and materials from the [MatlData].
+//!
+//! # Examples
+/*!
+```rust no_run
+# fn main() -> Result<(), Box<dyn std::error::Error>> {
+use ssbh_data::prelude::*;
+use ssbh_data::validation::Severity;
+
+let model = ModelFolder::from_folder("mario/model/body/c00")?;
+for issue in model.validate() {
+    if issue.severity() == Severity::Error {
+        println!("{issue}");
+    }
+}
+# Ok(()) }
+```
+ */
+use itertools::Itertools;
+use std::collections::HashSet;
+use thiserror::Error;
+
+use crate::model_folder::ModelFolder;
+use crate::prelude::*;
+
+/// How likely an issue is to cause problems in game.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+pub enum Severity {
+    /// The data is valid but may not be intended like a mesh object without a material.
+    Warning,
+    /// The data is likely to cause rendering issues or crashes in game.
+    Error,
+}
+
+/// An inconsistency between the files for a model.
+#[derive(Debug, PartialEq, Eq, Clone, Error)]
+pub enum ModelIssue {
+    /// A [ModlEntryData](crate::modl_data::ModlEntryData) references a mesh object not in the [MeshData].
+    #[error(
+        "modl entry references mesh object {}{} not present in the mesh",
+        mesh_object_name,
+        mesh_object_subindex
+    )]
+    MissingMeshObject {
+        mesh_object_name: String,
+        mesh_object_subindex: u64,
+    },
+
+    /// A [ModlEntryData](crate::modl_data::ModlEntryData) references a material not in any [MatlData].
+    #[error(
+        "mesh object {}{} is assigned to material {} not present in the matl",
+        mesh_object_name,
+        mesh_object_subindex,
+        material_label
+    )]
+    MissingMaterial {
+        mesh_object_name: String,
+        mesh_object_subindex: u64,
+        material_label: String,
+    },
+
+    /// A mesh object has no [ModlEntryData](crate::modl_data::ModlEntryData).
+    #[error(
+        "mesh object {}{} has no material assignment",
+        mesh_object_name,
+        mesh_object_subindex
+    )]
+    UnassignedMeshObject {
+        mesh_object_name: String,
+        mesh_object_subindex: u64,
+    },
+
+    /// A [BoneInfluence](crate::mesh_data::BoneInfluence) references a bone not in the [SkelData].
+    #[error(
+        "mesh object {}{} has influences for bone {} not present in the skel",
+        mesh_object_name,
+        mesh_object_subindex,
+        bone_name
+    )]
+    MissingInfluenceBone {
+        mesh_object_name: String,
+        mesh_object_subindex: u64,
+        bone_name: String,
+    },
+
+    /// A mesh object's parent bone is not in the [SkelData].
+    #[error(
+        "mesh object {}{} has parent bone {} not present in the skel",
+        mesh_object_name,
+        mesh_object_subindex,
+        bone_name
+    )]
+    MissingParentBone {
+        mesh_object_name: String,
+        mesh_object_subindex: u64,
+        bone_name: String,
+    },
+
+    /// The [MeshExData] has a different number of groups than the unique consecutive mesh object names.
+    #[error(
+        "meshex has {} groups but the mesh has {} groups of mesh objects with the same name",
+        meshex_group_count,
+        mesh_group_count
+    )]
+    MeshExGroupCount {
+        mesh_group_count: usize,
+        meshex_group_count: usize,
+    },
+
+    /// The [MeshExData] group name does not match the mesh object name for that group.
+    #[error(
+        "meshex group {} has name {} but the mesh object name is {}",
+        group_index,
+        meshex_name,
+        mesh_object_name
+    )]
+    MeshExGroupName {
+        group_index: usize,
+        mesh_object_name: String,
+        meshex_name: String,
+    },
+
+    /// The [MeshExData] group has a different number of entries than mesh objects with that name.
+    #[error(
+        "meshex group {} has {} entries but there are {} mesh objects with name {}",
+        group_index,
+        entry_count,
+        mesh_object_count,
+        mesh_object_name
+    )]
+    MeshExEntryCount {
+        group_index: usize,
+        mesh_object_name: String,
+        mesh_object_count: usize,
+        entry_count: usize,
+    },
+
+    /// An [AdjEntryData](crate::adj_data::AdjEntryData) has an index not in the [MeshData] objects.
+    #[error(
+        "adj mesh object index {} is out of range for a mesh object collection of size {}",
+        mesh_object_index,
+        mesh_object_count
+    )]
+    AdjIndexOutOfRange {
+        mesh_object_index: usize,
+        mesh_object_count: usize,
+    },
+}
+
+impl ModelIssue {
+    pub fn severity(&self) -> Severity {
+        match self {
+            ModelIssue::UnassignedMeshObject { .. } => Severity::Warning,
+            _ => Severity::Error,
+        }
+    }
+}
+
+/// References to the data for a model.
+/// Checks that require a missing file are skipped.
+#[derive(Debug, Default, Clone)]
+pub struct ModelRefs<'a> {
+    pub modl: Option<&'a ModlData>,
+    pub mesh: Option<&'a MeshData>,
+    pub skel: Option<&'a SkelData>,
+    pub matls: Vec<&'a MatlData>,
+    pub meshex: Option<&'a MeshExData>,
+    pub adj: Option<&'a AdjData>,
+}
+
+impl<'a> From<&'a ModelFolder> for ModelRefs<'a> {
+    fn from(model: &'a ModelFolder) -> Self {
+        Self {
+            modl: Some(&model.modl),
+            mesh: model.mesh.data.as_ref().ok(),
+            skel: model.skel.data.as_ref().ok(),
+            matls: model
+                .matls
+                .iter()
+                .filter_map(|m| m.data.as_ref().ok())
+                .collect(),
+            meshex: model.meshex.as_ref().and_then(|m| m.data.as_ref().ok()),
+            adj: model.adj.as_ref().and_then(|a| a.data.as_ref().ok()),
+        }
+    }
+}
+
+impl ModelFolder {
+    /// Checks the references between all successfully loaded files.
+    /// See [validate_model].
+    pub fn validate(&self) -> Vec<ModelIssue> {
+        validate_model(&ModelRefs::from(self))
+    }
+}
+
+/// Checks the references between the files in `model`.
+/// Issues are returned in the order they are found rather than by [Severity].
+pub fn validate_model(model: &ModelRefs) -> Vec<ModelIssue> {
+    let mut issues = Vec::new();
+
+    if let Some(modl) = model.modl {
+        validate_modl(modl, model.mesh, &model.matls, &mut issues);
+    }
+
+    if let Some(mesh) = model.mesh {
+        if let Some(skel) = model.skel {
+            validate_bones(mesh, skel, &mut issues);
+        }
+        if let Some(meshex) = model.meshex {
+            validate_meshex(mesh, meshex, &mut issues);
+        }
+        if let Some(adj) = model.adj {
+            validate_adj(mesh, adj, &mut issues);
+        }
+    }
+
+    issues
+}
+
+fn validate_modl(
+    modl: &ModlData,
+    mesh: Option<&MeshData>,
+    matls: &[&MatlData],
+    issues: &mut Vec<ModelIssue>,
+) {
+    // Skip material checks if there are no materials to avoid reporting every entry.
+    if !matls.is_empty() {
+        let labels: HashSet<_> = matls
+            .iter()
+            .flat_map(|m| m.entries.iter().map(|e| e.material_label.as_str()))
+            .collect();
+        for entry in &modl.entries {
+            if !labels.contains(entry.material_label.as_str()) {
+                issues.push(ModelIssue::MissingMaterial {
+                    mesh_object_name: entry.mesh_object_name.clone(),
+                    mesh_object_subindex: entry.mesh_object_subindex,
+                    material_label: entry.material_label.clone(),
+                });
+            }
+        }
+    }
+
+    if let Some(mesh) = mesh {
+        let objects: HashSet<_> = mesh
+            .objects
+            .iter()
+            .map(|o| (o.name.as_str(), o.subindex))
+            .collect();
+        let entries: HashSet<_> = modl
+            .entries
+            .iter()
+            .map(|e| (e.mesh_object_name.as_str(), e.mesh_object_subindex))
+            .collect();
+
+        for entry in &modl.entries {
+            if !objects.contains(&(entry.mesh_object_name.as_str(), entry.mesh_object_subindex)) {
+                issues.push(ModelIssue::MissingMeshObject {
+                    mesh_object_name: entry.mesh_object_name.clone(),
+                    mesh_object_subindex: entry.mesh_object_subindex,
+                });
+            }
+        }
+
+        for o in &mesh.objects {
+            if !entries.contains(&(o.name.as_str(), o.subindex)) {
+                issues.push(ModelIssue::UnassignedMeshObject {
+                    mesh_object_name: o.name.clone(),
+                    mesh_object_subindex: o.subindex,
+                });
+            }
+        }
+    }
+}
+
+fn validate_bones(mesh: &MeshData, skel: &SkelData, issues: &mut Vec<ModelIssue>) {
+    let bone_names: HashSet<_> = skel.bones.iter().map(|b| b.name.as_str()).collect();
+
+    for o in &mesh.objects {
+        if !o.parent_bone_name.is_empty() && !bone_names.contains(o.parent_bone_name.as_str()) {
+            issues.push(ModelIssue::MissingParentBone {
+                mesh_object_name: o.name.clone(),
+                mesh_object_subindex: o.subindex,
+                bone_name: o.parent_bone_name.clone(),
+            });
+        }
+
+        for bone_name in o
+            .bone_influences
+            .iter()
+            .map(|i| &i.bone_name)
+            .filter(|n| !bone_names.contains(n.as_str()))
+            .unique()
+        {
+            issues.push(ModelIssue::MissingInfluenceBone {
+                mesh_object_name: o.name.clone(),
+                mesh_object_subindex: o.subindex,
+                bone_name: bone_name.clone(),
+            });
+        }
+    }
+}
+
+fn validate_meshex(mesh: &MeshData, meshex: &MeshExData, issues: &mut Vec<ModelIssue>) {
+    // Match the grouping used for MeshExData::from_mesh_objects.
+    let groups = mesh
+        .objects
+        .iter()
+        .group_by(|o| &o.name)
+        .into_iter()
+        .map(|(name, group)| (name.clone(), group.count()))
+        .collect_vec();
+
+    if groups.len() != meshex.mesh_object_groups.len() {
+        issues.push(ModelIssue::MeshExGroupCount {
+            mesh_group_count: groups.len(),
+            meshex_group_count: meshex.mesh_object_groups.len(),
+        });
+    }
+
+    for (group_index, ((name, count), group)) in groups
+        .iter()
+        .zip(meshex.mesh_object_groups.iter())
+        .enumerate()
+    {
+        if *name != group.mesh_object_full_name {
+            issues.push(ModelIssue::MeshExGroupName {
+                group_index,
+                mesh_object_name: name.clone(),
+                meshex_name: group.mesh_object_full_name.clone(),
+            });
+        }
+        if *count != group.entry_flags.len() {
+            issues.push(ModelIssue::MeshExEntryCount {
+                group_index,
+                mesh_object_name: name.clone(),
+                mesh_object_count: *count,
+                entry_count: group.entry_flags.len(),
+            });
+        }
+    }
+}
+
+fn validate_adj(mesh: &MeshData, adj: &AdjData, issues: &mut Vec<ModelIssue>) {
+    for entry in &adj.entries {
+        if entry.mesh_object_index >= mesh.objects.len() {
+            issues.push(ModelIssue::AdjIndexOutOfRange {
+                mesh_object_index: entry.mesh_object_index,
+                mesh_object_count: mesh.objects.len(),
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        adj_data::AdjEntryData,
+        matl_data::MatlEntryData,
+        mesh_data::{BoneInfluence, MeshObjectData},
+        meshex_data::{EntryFlags, MeshObjectGroupData},
+        modl_data::ModlEntryData,
+        skel_data::{BillboardType, BoneData},
+    };
+    use ssbh_lib::{formats::mesh::BoundingSphere, Vector3};
+
+    fn mesh(objects: &[(&str, u64)]) -> MeshData {
+        MeshData::new(
+            1,
+            10,
+            objects
+                .iter()
+                .map(|(name, subindex)| MeshObjectData {
+                    name: name.to_string(),
+                    subindex: *subindex,
+                    ..Default::default()
+                })
+                .collect(),
+        )
+    }
+
+    fn modl(entries: &[(&str, u64, &str)]) -> ModlData {
+        ModlData {
+            major_version: 1,
+            minor_version: 7,
+            model_name: String::new(),
+            skeleton_file_name: String::new(),
+            material_file_names: Vec::new(),
+            animation_file_name: None,
+            mesh_file_name: String::new(),
+            entries: entries
+                .iter()
+                .map(|(name, subindex, label)| ModlEntryData {
+                    mesh_object_name: name.to_string(),
+                    mesh_object_subindex: *subindex,
+                    material_label: label.to_string(),
+                })
+                .collect(),
+        }
+    }
+
+    fn matl(labels: &[&str]) -> MatlData {
+        MatlData {
+            major_version: 1,
+            minor_version: 6,
+            entries: labels
+                .iter()
+                .map(|label| MatlEntryData {
+                    material_label: label.to_string(),
+                    shader_label: String::new(),
+                    blend_states: Vec::new(),
+                    floats: Vec::new(),
+                    booleans: Vec::new(),
+                    vectors: Vec::new(),
+                    rasterizer_states: Vec::new(),
+                    samplers: Vec::new(),
+                    textures: Vec::new(),
+                    uv_transforms: Vec::new(),
+                })
+                .collect(),
+        }
+    }
+
+    fn meshex_group(name: &str, entry_count: usize) -> MeshObjectGroupData {
+        MeshObjectGroupData {
+            bounding_sphere: BoundingSphere {
+                center: Vector3::ZERO,
+                radius: 0.0,
+            },
+            mesh_object_full_name: name.to_string(),
+            mesh_object_name: name.to_string(),
+            entry_flags: vec![
+                EntryFlags {
+                    draw_model: true,
+                    cast_shadow: true,
+                };
+                entry_count
+            ],
+        }
+    }
+
+    #[test]
+    fn validate_empty_model() {
+        assert!(validate_model(&ModelRefs::default()).is_empty());
+    }
+
+    #[test]
+    fn validate_modl_entries() {
+        let modl = modl(&[("a", 0, "mat_a"), ("b", 0, "mat_b"), ("c", 1, "mat_a")]);
+        let mesh = mesh(&[("a", 0), ("b", 0), ("d", 0)]);
+        let matl = matl(&["mat_a"]);
+
+        let issues = validate_model(&ModelRefs {
+            modl: Some(&modl),
+            mesh: Some(&mesh),
+            matls: vec![&matl],
+            ..Default::default()
+        });
+        assert_eq!(
+            vec![
+                ModelIssue::MissingMaterial {
+                    mesh_object_name: "b".to_string(),
+                    mesh_object_subindex: 0,
+                    material_label: "mat_b".to_string()
+                },
+                ModelIssue::MissingMeshObject {
+                    mesh_object_name: "c".to_string(),
+                    mesh_object_subindex: 1
+                },
+                ModelIssue::UnassignedMeshObject {
+                    mesh_object_name: "d".to_string(),
+                    mesh_object_subindex: 0
+                },
+            ],
+            issues
+        );
+        assert_eq!(Severity::Warning, issues[2].severity());
+    }
+
+    #[test]
+    fn validate_missing_bones() {
+        let mut mesh = mesh(&[("a", 0)]);
+        mesh.objects[0].parent_bone_name = "Head".to_string();
+        mesh.objects[0].bone_influences = vec![
+            BoneInfluence {
+                bone_name: "Hip".to_string(),
+                vertex_weights: Vec::new(),
+            },
+            BoneInfluence {
+                bone_name: "Trans".to_string(),
+                vertex_weights: Vec::new(),
+            },
+        ];
+        let skel = SkelData {
+            major_version: 1,
+            minor_version: 0,
+            bones: vec![BoneData {
+                name: "Trans".to_string(),
+                transform: [[0.0; 4]; 4],
+                parent_index: None,
+                billboard_type: BillboardType::Disabled,
+            }],
+        };
+
+        assert_eq!(
+            vec![
+                ModelIssue::MissingParentBone {
+                    mesh_object_name: "a".to_string(),
+                    mesh_object_subindex: 0,
+                    bone_name: "Head".to_string()
+                },
+                ModelIssue::MissingInfluenceBone {
+                    mesh_object_name: "a".to_string(),
+                    mesh_object_subindex: 0,
+                    bone_name: "Hip".to_string()
+                },
+            ],
+            validate_model(&ModelRefs {
+                mesh: Some(&mesh),
+                skel: Some(&skel),
+                ..Default::default()
+            })
+        );
+    }
+
+    #[test]
+    fn validate_meshex_groups() {
+        let mesh = mesh(&[("a", 0), ("a", 1), ("b", 0), ("c", 0)]);
+        let meshex = MeshExData {
+            mesh_object_groups: vec![meshex_group("a", 1), meshex_group("c", 1)],
+        };
+
+        assert_eq!(
+            vec![
+                ModelIssue::MeshExGroupCount {
+                    mesh_group_count: 3,
+                    meshex_group_count: 2
+                },
+                ModelIssue::MeshExEntryCount {
+                    group_index: 0,
+                    mesh_object_name: "a".to_string(),
+                    mesh_object_count: 2,
+                    entry_count: 1
+                },
+                ModelIssue::MeshExGroupName {
+                    group_index: 1,
+                    mesh_object_name: "b".to_string(),
+                    meshex_name: "c".to_string()
+                },
+            ],
+            validate_model(&ModelRefs {
+                mesh: Some(&mesh),
+                meshex: Some(&meshex),
+                ..Default::default()
+            })
+        );
+    }
+
+    #[test]
+    fn validate_adj_indices() {
+        let mesh = mesh(&[("a", 0)]);
+        let adj = AdjData {
+            entries: vec![
+                AdjEntryData {
+                    mesh_object_index: 0,
+                    vertex_adjacency: Vec::new(),
+                },
+                AdjEntryData {
+                    mesh_object_index: 1,
+                    vertex_adjacency: Vec::new(),
+                },
+            ],
+        };
+
+        assert_eq!(
+            vec![ModelIssue::AdjIndexOutOfRange {
+                mesh_object_index: 1,
+                mesh_object_count: 1
+            }],
+            validate_model(&ModelRefs {
+                mesh: Some(&mesh),
+                adj: Some(&adj),
+                ..Default::default()
+            })
+        );
+    }
+}